@@ -0,0 +1,54 @@
+fn main() {
+    // This example shows how to make a program with git-style subcommands, like "example-2 add"
+    // and "example-2 remove". Make sure to read example-1 first!
+    //
+    // We start just like before, by creating a MUTABLE Argument instance
+    let mut arguments = taap::Argument::new(
+        "example-2",
+        "The second example program for TAAP, a tiny todo list!",
+        "The text at the bottom of the help!",
+        "SpamixOfficial 2024",
+    );
+
+    // Options marked as global are inherited by every subcommand, so "-v" can be used both
+    // before and after the name of the subcommand
+    arguments.add(taap::Opt::new('v', "verbose").global().help("Tell what's going on"));
+
+    // Now let's add our subcommands!
+    //
+    // add_subcommand takes the name of the subcommand and its description, and returns the
+    // subcommand itself, which is an Argument of its own. This means that it has its own options,
+    // positional arguments and help page ("example-2 add --help")
+    let add = arguments.add_subcommand("add", "Add a todo");
+    add.add_arg("TODO", "1", Some("What there is to do"));
+    add.add_option('p', "priority", "1", Some("How important it is"));
+
+    let remove = arguments.add_subcommand("remove", "Remove todos");
+    remove.add_arg("NUMBERS", "+", Some("The numbers of the todos to remove"));
+
+    // Parsing works just like before
+    let parsed_arguments = arguments.parse_args(None);
+
+    if parsed_arguments.get("v").unwrap().0 {
+        println!("Verbose mode is on!");
+    };
+
+    // subcommand() tells us which subcommand was used (if any), together with the parsed
+    // arguments of that subcommand, which are used just like the ones in example-1
+    match parsed_arguments.subcommand() {
+        Some(("add", add_arguments)) => {
+            println!("Adding \"{}\"", add_arguments.get("TODO").unwrap().1[0]);
+            let priority = add_arguments.get("p").unwrap();
+            if priority.0 {
+                println!("With the priority {}", priority.1[0]);
+            };
+        }
+        Some(("remove", remove_arguments)) => {
+            for number in remove_arguments.get("NUMBERS").unwrap().1.iter() {
+                println!("Removing todo number {}", number);
+            }
+        }
+        // no subcommand was used
+        _ => println!("Nothing to do!"),
+    };
+}
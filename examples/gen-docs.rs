@@ -0,0 +1,84 @@
+// Keeps the usage section of a Markdown file in sync with the help of example-1
+//
+// Add the markers to the file first:
+//
+//     <!-- taap:usage:start -->
+//     <!-- taap:usage:end -->
+//
+// and then run:
+//
+//     cargo run --example gen-docs -- README.md
+//
+// With --check, nothing is written and the exit status is 1 if the file is out of date, which
+// is handy in CI.
+
+use std::process::exit;
+use taap::{Argument, Opt, Pos};
+
+// The definition of the program being documented, usually shared with the program itself
+fn cli() -> Argument {
+    let mut arguments = Argument::new(
+        "example-1",
+        "The first example program for TAAP!",
+        "The text at the bottom of the help!",
+        "SpamixOfficial 2024",
+    );
+    arguments.add_option('f', "foo", "0", Some("Some help!"));
+    arguments.add_option('-', "no-help", "2", None);
+    arguments.add_arg("BAR", "1", None);
+    arguments.add_exit_status(0, "Everything went just fine");
+    arguments.add_exit_status(1, "Something went a little wrong");
+    arguments.add_exit_status(2, "Something went horribly wrong!");
+    arguments
+}
+
+fn main() {
+    let mut arguments = Argument::new(
+        "gen-docs",
+        "Writes the Markdown help of example-1 into a section of a file",
+        "The section is marked with <!-- taap:SECTION:start --> and <!-- taap:SECTION:end -->",
+        "SpamixOfficial 2024",
+    );
+    arguments.add(Pos::new("FILE").help("The Markdown file to update"));
+    arguments.add(
+        Opt::new('s', "section")
+            .takes(1)
+            .default("usage")
+            .help("The name of the section"),
+    );
+    arguments.add(Opt::new('c', "check").help("Only check that the file is up to date"));
+    let parsed_arguments = arguments.parse_args(None);
+
+    let file = &parsed_arguments.get("FILE").unwrap().1[0];
+    let section = &parsed_arguments.get("s").unwrap().1[0];
+    let cli = cli();
+
+    if parsed_arguments.get("c").unwrap().0 {
+        let copy = std::env::temp_dir().join("gen-docs-check.md");
+        let up_to_date = std::fs::copy(file, &copy)
+            .and_then(|_| cli.update_help_section(&copy, section))
+            .map(|changed| !changed);
+        let _ = std::fs::remove_file(&copy);
+        match up_to_date {
+            Ok(true) => println!("{} is up to date", file),
+            Ok(false) => {
+                eprintln!("{} is out of date, run gen-docs to update it", file);
+                exit(1);
+            }
+            Err(err) => {
+                eprintln!("Error! {}", err);
+                exit(1);
+            }
+        };
+        return;
+    };
+
+    match cli.update_help_section(file, section) {
+        Ok(true) => println!("Updated {}", file),
+        Ok(false) => println!("{} was already up to date", file),
+        Err(err) => {
+            eprintln!("Error! {}", err);
+            exit(1);
+        }
+    };
+}
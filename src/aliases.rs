@@ -0,0 +1,86 @@
+// Aliases defined by the end user, like the ones of git
+//
+// The file has one alias per line, written as the name of the alias, an equals sign and the
+// arguments it expands to, split like a shell would (so 'a b' is a single argument). Empty lines
+// and lines starting with # are ignored:
+//
+//     st = status -s
+//     sst = st --short-branch
+//
+// An alias is expanded when it's the first argument, and can expand to another alias.
+
+use crate::{output::fail, sanitize::sanitize, shell, subcommand, Argument, Error};
+use std::{collections::BTreeMap, fs};
+
+// Expands the first argument if it's an alias. The tokens an alias expands to weren't given, so
+// their origins (see parse::origins) are None
+pub(crate) fn expand_aliases(
+    argument: &Argument,
+    path: &str,
+    tokens: Vec<String>,
+    origins: &mut Vec<Option<usize>>,
+) -> Result<Vec<String>, Error> {
+    let is_alias_candidate =
+        |token: &str| !token.starts_with('-') && !subcommand::is_subcommand(argument, token);
+    if !tokens
+        .first()
+        .is_some_and(|first| is_alias_candidate(first))
+    {
+        return Ok(tokens);
+    };
+
+    let aliases = read_aliases(path)?;
+    let mut tokens = tokens;
+    let mut used: Vec<String> = vec![];
+    while let Some(expansion) = tokens
+        .first()
+        .filter(|first| is_alias_candidate(first))
+        .and_then(|first| aliases.get(first))
+    {
+        used.push(tokens[0].to_owned());
+        if used[..used.len() - 1].contains(&tokens[0]) {
+            return Err(fail(format_args!(
+                "Alias \"{}\" expands to itself ({})",
+                sanitize(&tokens[0]),
+                sanitize(&used.join(" -> "))
+            )));
+        };
+        tokens.splice(..1, expansion.iter().cloned());
+        origins.splice(..1, vec![None; expansion.len()]);
+    }
+    Ok(tokens)
+}
+
+fn read_aliases(path: &str) -> Result<BTreeMap<String, Vec<String>>, Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        // without an aliases file there simply aren't any aliases
+        Err(_) => return Ok(BTreeMap::new()),
+    };
+    let mut aliases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        };
+        match line.split_once('=') {
+            Some((name, expansion)) if !name.trim().is_empty() => {
+                let Some(expansion) = shell::split(expansion) else {
+                    return Err(fail(format_args!(
+                        "Alias \"{}\" in \"{}\" has a quote that isn't closed",
+                        sanitize(name.trim()),
+                        sanitize(path)
+                    )));
+                };
+                aliases.insert(name.trim().to_string(), expansion);
+            }
+            _ => {
+                return Err(fail(format_args!(
+                    "Invalid alias \"{}\" in \"{}\"",
+                    sanitize(line),
+                    sanitize(path)
+                )));
+            }
+        };
+    }
+    Ok(aliases)
+}
@@ -0,0 +1,237 @@
+// Parsing with asynchronous value sources and choices (requires the "async" feature). The parser
+// stops when it gets to one that wasn't awaited yet, which is then awaited and handed to the
+// parser like its blocking counterpart when parsing again, so the parser itself doesn't have to
+// know about futures
+
+use crate::{
+    callback::Callback,
+    choices::Choices,
+    output::{self, Stop},
+    parse::ParseResult,
+    spec::OptionSpec,
+    value_source::{self, ValueSource},
+    Argument, ParsedArgs,
+};
+use std::{cell::RefCell, collections::BTreeMap, future::Future, pin::Pin, sync::Arc};
+
+/// A boxed future, as returned by the asynchronous hooks
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// An external store the values of an option are fetched from asynchronously, see `ValueSource`
+///
+/// The async counterpart of `ValueSource`, for stores reached through an async client, or for
+/// prompting the user from a TUI, since asking the user for a missing value is just another
+/// source. Options with one have to be parsed using `Argument::parse_args_async`.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, AsyncValueSource, BoxFuture, Opt};
+///
+/// struct Vault;
+///
+/// impl AsyncValueSource for Vault {
+///     fn name(&self) -> String {
+///         "the vault".to_string()
+///     }
+///
+///     fn fetch<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<String>>, String>> {
+///         Box::pin(async move {
+///             // ask the vault for the secret stored under the key
+///             Ok(Some(vec![format!("secret for {}", key)]))
+///         })
+///     }
+/// }
+///
+/// // run by the runtime of the program, e.g. from a #[tokio::main] function
+/// async fn run() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(Opt::new('t', "token").takes(1).async_value_source(Vault, "api-token"));
+/// let parsed_arguments = arguments.parse_args_async(None).await;
+/// }
+/// ```
+pub trait AsyncValueSource: Send + Sync {
+    /// The name of the store, shown in error messages and returned by `ParsedArgs::source`
+    fn name(&self) -> String;
+
+    /// Returns the values stored under the key, None if there are none, or a description of
+    /// what went wrong, which makes parsing fail
+    fn fetch<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<String>>, String>>;
+}
+
+// An async value source as stored in the spec, with the key of the option in it
+pub(crate) type SharedAsyncSource = (Callback<dyn AsyncValueSource>, String);
+
+pub(crate) fn shared(source: impl AsyncValueSource + 'static, key: &str) -> SharedAsyncSource {
+    (Callback(Arc::new(source)), key.to_string())
+}
+
+// Choices produced by a future every time the command line is parsed
+pub(crate) type AsyncChoices = Callback<dyn Fn() -> BoxFuture<'static, Vec<String>> + Send + Sync>;
+
+pub(crate) fn async_choices(
+    choices: impl Fn() -> BoxFuture<'static, Vec<String>> + Send + Sync + 'static,
+) -> AsyncChoices {
+    Callback(Arc::new(choices))
+}
+
+// The result of an async value source, handed to the parser as a blocking one
+#[derive(Clone)]
+struct Fetched {
+    name: String,
+    result: Result<Option<Vec<String>>, String>,
+}
+
+impl ValueSource for Fetched {
+    fn name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    fn fetch(&self, _key: &str) -> Result<Option<Vec<String>>, String> {
+        self.result.clone()
+    }
+}
+
+// An option, as (the name of the command it's in, its key)
+type Key = (String, String);
+
+// An async part of an option the parser got to, which is awaited before parsing again
+#[derive(Debug)]
+pub(crate) enum Pending {
+    Choices(Key, AsyncChoices),
+    Source(Key, SharedAsyncSource),
+}
+
+// The async parts awaited so far while parsing with parse_args_async
+#[derive(Default)]
+struct Resolved {
+    choices: BTreeMap<Key, Vec<String>>,
+    sources: BTreeMap<Key, Fetched>,
+}
+
+thread_local! {
+    // set while the parser runs for parse_args_async, None while parsing blocks
+    static RESOLVED: RefCell<Option<Resolved>> = const { RefCell::new(None) };
+}
+
+// Hands the async parts awaited so far to the options of the command about to be parsed. The
+// choices of a command are awaited as soon as it's parsed, since they're needed for matching its
+// options and for its help. Panics if an option needs to be awaited while parsing blocks
+pub(crate) fn prepare(argument: &mut Argument) -> Result<(), Stop> {
+    RESOLVED.with(|resolved| {
+        let resolved = resolved.borrow();
+        let Some(resolved) = &*resolved else {
+            check_blocking(argument);
+            return Ok(());
+        };
+        let mut pending: Vec<Pending> = vec![];
+        for option in argument.options.iter_mut() {
+            let key = (argument.name.to_owned(), option.key());
+            if let Some(choices) = &option.async_choices {
+                match resolved.choices.get(&key) {
+                    Some(resolved) => {
+                        option.choices = Some(Choices::Static(resolved.to_owned()));
+                        option.async_choices = None;
+                    }
+                    None => pending.push(Pending::Choices(key.to_owned(), choices.to_owned())),
+                };
+            };
+            if let Some(fetched) = resolved.sources.get(&key) {
+                if let Some((_, source_key)) = option.async_value_source.take() {
+                    option.value_source =
+                        Some(value_source::shared(fetched.to_owned(), &source_key));
+                };
+            };
+        }
+        match pending.is_empty() {
+            true => Ok(()),
+            false => Err(Stop::Await(pending)),
+        }
+    })
+}
+
+// Asks for the async value sources of the options that weren't used, once the command line is
+// matched, so none are awaited for the help, an option like --version, or an option that got
+// its value from elsewhere
+pub(crate) fn check_sources(argument: &Argument, return_map: &ParseResult) -> Result<(), Stop> {
+    let pending: Vec<Pending> = argument
+        .options
+        .iter()
+        .filter(|option| !return_map.get(&option.key()).unwrap().0)
+        .filter_map(|option| {
+            let source = option.async_value_source.to_owned()?;
+            Some(Pending::Source(
+                (argument.name.to_owned(), option.key()),
+                source,
+            ))
+        })
+        .collect();
+    match pending.is_empty() {
+        true => Ok(()),
+        false => Err(Stop::Await(pending)),
+    }
+}
+
+// Panics if an option needs to be awaited, since the blocking parser can't do that
+fn check_blocking(argument: &Argument) {
+    let awaited = argument
+        .options
+        .iter()
+        .find(|option| option.async_value_source.is_some() || option.async_choices.is_some());
+    if let Some(option) = awaited {
+        panic!(
+            "Error! \"{}\" has an async value source or async choices, so the command line has \
+             to be parsed using parse_args_async",
+            option.display_name()
+        );
+    };
+}
+
+// Parses the command line, awaiting the async parts of the options the parser asks for and
+// parsing again, until it gets through. What a parse asking for async parts printed is dropped,
+// so e.g. warnings aren't printed twice. The options are put back once parsing is done
+pub(crate) async fn parse(
+    argument: &mut Argument,
+    custom_arglist: Option<Vec<String>>,
+) -> Result<ParsedArgs, Stop> {
+    let restore = Restore {
+        options: argument.options.clone(),
+        argument,
+    };
+    let mut resolved = Resolved::default();
+    loop {
+        RESOLVED.with(|current| current.replace(Some(resolved)));
+        let (result, printed) = output::hold(|| restore.argument.run(custom_arglist.clone()));
+        resolved = RESOLVED.with(RefCell::take).unwrap_or_default();
+        let Err(Stop::Await(pending)) = result else {
+            output::release(printed);
+            return result;
+        };
+        for pending in pending {
+            match pending {
+                Pending::Choices(key, choices) => {
+                    resolved.choices.insert(key, (choices.0)().await);
+                }
+                Pending::Source(key, (source, source_key)) => {
+                    let fetched = Fetched {
+                        name: source.0.name(),
+                        result: source.0.fetch(&source_key).await,
+                    };
+                    resolved.sources.insert(key, fetched);
+                }
+            };
+        }
+    }
+}
+
+// Puts the options back as they were once parsing is done, even if it failed
+struct Restore<'a> {
+    argument: &'a mut Argument,
+    options: Vec<OptionSpec>,
+}
+
+impl Drop for Restore<'_> {
+    fn drop(&mut self) {
+        self.argument.options = std::mem::take(&mut self.options);
+        RESOLVED.with(RefCell::take);
+    }
+}
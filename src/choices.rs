@@ -0,0 +1,42 @@
+// The values an option accepts, which are either given up front or produced by a closure every
+// time they're needed (when parsing, or when a completion script asks for them)
+
+use crate::{callback::Callback, output::fail_with, sanitize::sanitize, Error, ErrorKind};
+use std::{borrow::Cow, sync::Arc};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) enum Choices {
+    Static(Vec<String>),
+    Dynamic(Callback<dyn Fn() -> Vec<String> + Send + Sync>),
+}
+
+impl Choices {
+    pub(crate) fn dynamic(choices: impl Fn() -> Vec<String> + Send + Sync + 'static) -> Self {
+        Choices::Dynamic(Callback(Arc::new(choices)))
+    }
+
+    // Returns the choices, calling the closure if they're dynamic
+    pub(crate) fn resolve(&self) -> Cow<'_, [String]> {
+        match self {
+            Choices::Static(choices) => Cow::Borrowed(choices),
+            Choices::Dynamic(choices) => Cow::Owned((choices.0)()),
+        }
+    }
+
+    // Fails if one of the values given to an option isn't one of the choices
+    pub(crate) fn check(&self, used_as: &str, values: &[String]) -> Result<(), Error> {
+        let choices = self.resolve();
+        if let Some(invalid) = values.iter().find(|value| !choices.contains(value)) {
+            return Err(fail_with(
+                ErrorKind::InvalidValue,
+                format_args!(
+                    "Invalid value \"{}\" for {}, expected one of: {}",
+                    sanitize(invalid),
+                    used_as,
+                    sanitize(&choices.join(", "))
+                ),
+            ));
+        };
+        Ok(())
+    }
+}
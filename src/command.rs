@@ -0,0 +1,131 @@
+// Typed subcommand results, generated by the subcommands! macro
+
+use crate::ParsedArgs;
+
+/// Conversion from the parsed arguments of a subcommand into a type of your own
+///
+/// Implement it for the types used in the `subcommands!` macro. It's implemented for ParsedArgs
+/// itself, for subcommands where a typed struct isn't worth it.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{FromArgs, ParsedArgs};
+///
+/// struct AddArgs {
+///     name: String,
+///     force: bool,
+/// }
+///
+/// impl FromArgs for AddArgs {
+///     fn from_args(parsed: &ParsedArgs) -> Self {
+///         AddArgs {
+///             name: parsed.get("NAME").unwrap().1[0].to_owned(),
+///             force: parsed.get("f").unwrap().0,
+///         }
+///     }
+/// }
+///
+/// fn main() {}
+/// ```
+pub trait FromArgs {
+    /// Builds the type from the parsed arguments of a subcommand
+    fn from_args(parsed: &ParsedArgs) -> Self;
+}
+
+impl FromArgs for ParsedArgs {
+    fn from_args(parsed: &ParsedArgs) -> Self {
+        parsed.clone()
+    }
+}
+
+/// Generates an enum with one variant per subcommand
+///
+/// Every variant maps the name of a subcommand to a type implementing `FromArgs`, and the
+/// generated `from_parsed` function turns the result of parse_args into the variant of the
+/// subcommand that was used (or None if no subcommand was used). Matching on the enum is then
+/// checked by the compiler, instead of comparing names from the result map.
+///
+/// `from_parsed` panics if the subcommand that was used isn't a part of the enum, since that
+/// means the enum and the subcommands added to the Argument don't match.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, FromArgs, ParsedArgs};
+///
+/// struct AddArgs {
+///     name: String,
+/// }
+///
+/// impl FromArgs for AddArgs {
+///     fn from_args(parsed: &ParsedArgs) -> Self {
+///         AddArgs {
+///             name: parsed.get("NAME").unwrap().1[0].to_owned(),
+///         }
+///     }
+/// }
+///
+/// taap::subcommands! {
+///     enum Command {
+///         Add("add") => AddArgs,
+///         List("list") => ParsedArgs,
+///     }
+/// }
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_subcommand("add", "Add something").add_arg("NAME", "1", None);
+/// arguments.add_subcommand("list", "List everything");
+///
+/// let parsed_arguments = arguments.parse_args(None);
+/// match Command::from_parsed(&parsed_arguments) {
+///     Some(Command::Add(add)) => println!("Adding {}", add.name),
+///     Some(Command::List(_)) => println!("Listing"),
+///     None => arguments.print_help(),
+/// }
+/// }
+/// ```
+#[macro_export]
+macro_rules! subcommands {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $enum_name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident($name:literal) => $args:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $enum_name {
+            $(
+                $(#[$variant_meta])*
+                $variant($args),
+            )*
+        }
+
+        impl $enum_name {
+            /// The names of the subcommands, in the order they were declared
+            #[allow(dead_code)]
+            pub const NAMES: &'static [&'static str] = &[$($name),*];
+
+            /// Returns the variant of the subcommand that was used, or None if no subcommand
+            /// was used
+            #[allow(dead_code)]
+            pub fn from_parsed(parsed: &$crate::ParsedArgs) -> ::core::option::Option<Self> {
+                let (name, subcommand) = parsed.subcommand()?;
+                match name {
+                    $(
+                        $name => ::core::option::Option::Some($enum_name::$variant(
+                            <$args as $crate::FromArgs>::from_args(subcommand),
+                        )),
+                    )*
+                    other => ::core::panic!(
+                        "Error! Subcommand \"{}\" isn't a part of {}",
+                        other,
+                        ::core::stringify!($enum_name)
+                    ),
+                }
+            }
+        }
+    };
+}
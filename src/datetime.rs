@@ -0,0 +1,155 @@
+//! Date and time values (requires the "chrono" feature)
+//!
+//! Scheduling and logging tools often take dates as arguments, e.g. `--since "2 days ago"`.
+//! This module parses such values in a consistent way, accepting:
+//! * RFC 3339 timestamps, e.g. `2024-01-31T12:30:00Z` or `2024-01-31T12:30:00+02:00`
+//! * Plain dates, e.g. `2024-01-31` (midnight, UTC)
+//! * `now`, `today` and `yesterday`
+//! * Relative times, e.g. `2 days ago` or `1 week ago` (seconds, minutes, hours, days, weeks,
+//!   months and years)
+//!
+//! Code Example:
+//! ```no_run
+//! let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+//! arguments.add_option('s', "since", "1", Some("Only show entries newer than this"));
+//! let parsed_arguments = arguments.parse_args(None);
+//!
+//! let since = parsed_arguments.get("s").unwrap();
+//! if since.0 {
+//!     match taap::datetime::parse_datetime(&since.1[0]) {
+//!         Ok(since) => println!("Showing entries since {}", since),
+//!         Err(err) => {
+//!             eprintln!("Error! {}", err);
+//!             std::process::exit(1);
+//!         }
+//!     }
+//! };
+//! ```
+
+use crate::sanitize::sanitize;
+use chrono::{DateTime, Days, Duration, Months, NaiveDate, Utc};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// The error returned when a value isn't a valid date or time
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DateTimeError {
+    input: String,
+}
+
+impl DateTimeError {
+    /// Returns the value that failed to parse
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl Display for DateTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid date/time \"{}\", expected a timestamp (2024-01-31T12:30:00Z), a date (2024-01-31) or a relative time (2 days ago)",
+            sanitize(&self.input)
+        )
+    }
+}
+
+impl std::error::Error for DateTimeError {}
+
+/// A parsed date and time, usable with `str::parse`
+///
+/// Code Example:
+/// ```
+/// let timestamp: taap::datetime::Timestamp = "2024-01-31".parse().unwrap();
+/// assert_eq!(timestamp.0.to_rfc3339(), "2024-01-31T00:00:00+00:00");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct Timestamp(pub DateTime<Utc>);
+
+impl FromStr for Timestamp {
+    type Err = DateTimeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_datetime(input).map(Timestamp)
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_rfc3339())
+    }
+}
+
+/// Parses a date or time, with relative times counted from the current time
+///
+/// | Parameter | Type | Description           |
+/// |-----------|------|-----------------------|
+/// | input     | &str | The value to be parsed |
+///
+pub fn parse_datetime(input: &str) -> Result<DateTime<Utc>, DateTimeError> {
+    parse_datetime_from(input, Utc::now())
+}
+
+/// Parses a date or time, with relative times counted from `now`
+///
+/// Useful for tests, or when several values should be relative to the same point in time
+///
+/// | Parameter | Type          | Description                          |
+/// |-----------|---------------|--------------------------------------|
+/// | input     | &str          | The value to be parsed               |
+/// | now       | DateTime\<Utc\> | The time relative values count from |
+///
+pub fn parse_datetime_from(
+    input: &str,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, DateTimeError> {
+    let error = || DateTimeError {
+        input: input.to_string(),
+    };
+    let trimmed = input.trim();
+    let midnight = |time: DateTime<Utc>| time.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+    match trimmed.to_lowercase().as_str() {
+        "now" => return Ok(now),
+        "today" => return Ok(midnight(now)),
+        "yesterday" => {
+            return midnight(now)
+                .checked_sub_days(Days::new(1))
+                .ok_or_else(error)
+        }
+        _ => (),
+    };
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(time.with_timezone(&Utc));
+    };
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    };
+
+    // relative times, e.g. "2 days ago"
+    let parts: Vec<String> = trimmed
+        .split_whitespace()
+        .map(|part| part.to_lowercase())
+        .collect();
+    if parts.len() != 3 || parts[2] != "ago" {
+        return Err(error());
+    };
+    let amount: u32 = parts[0].parse().map_err(|_| error())?;
+    let unit = parts[1].strip_suffix('s').unwrap_or(&parts[1]);
+    match unit {
+        "second" | "sec" => now.checked_sub_signed(Duration::seconds(amount.into())),
+        "minute" | "min" => now.checked_sub_signed(Duration::minutes(amount.into())),
+        "hour" => now.checked_sub_signed(Duration::hours(amount.into())),
+        "day" => now.checked_sub_days(Days::new(amount.into())),
+        "week" => now.checked_sub_days(Days::new(u64::from(amount) * 7)),
+        "month" => now.checked_sub_months(Months::new(amount)),
+        "year" => amount
+            .checked_mul(12)
+            .and_then(|months| now.checked_sub_months(Months::new(months))),
+        _ => None,
+    }
+    .ok_or_else(error)
+}
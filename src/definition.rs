@@ -0,0 +1,302 @@
+// Loading the definition of a command line from data (requires the "serde" feature)
+//
+// Argument, OptionSpec and PositionalSpec implement Deserialize through the plain structs below,
+// so a definition can be shipped as JSON, TOML, RON or anything else serde supports:
+//
+//     {
+//         "name": "mytool",
+//         "description": "Does things",
+//         "options": [{"short": "p", "long": "port", "values": 1, "default": ["8080"]}],
+//         "positionals": [{"placeholder": "FILES", "values": "+"}],
+//         "subcommands": [{"name": "remote", "description": "Manage remotes"}]
+//     }
+//
+// The amount of values is written like for add_option and add_arg, either as a number or as a
+// string ("0", "2", "+"). Closures (lazy help texts, dynamic choices, normalizers and hooks)
+// can't be part of the data, and are added to the deserialized Argument afterwards.
+//
+// A definition is checked just like Argument::validate does, so a mistake in the data is an
+// error while deserializing instead of a panic while parsing.
+
+use crate::{spec::Arity, text::Text, Argument, OptionSpec, PositionalSpec};
+use serde::{de::Error, Deserialize, Deserializer};
+use std::collections::HashSet;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArgumentDefinition {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    epilog: String,
+    #[serde(default)]
+    credits: String,
+    #[serde(default)]
+    exit_statuses: Vec<(u16, String)>,
+    #[serde(default)]
+    options: Vec<OptionSpec>,
+    #[serde(default)]
+    positionals: Vec<PositionalSpec>,
+    #[serde(default)]
+    subcommands: Vec<ArgumentDefinition>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptionDefinition {
+    #[serde(default)]
+    short: Option<char>,
+    #[serde(default)]
+    long: Option<String>,
+    #[serde(default = "no_values")]
+    values: Amount,
+    #[serde(default)]
+    help: String,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    global: bool,
+    #[serde(default)]
+    require_equals: bool,
+    #[serde(default)]
+    default: Option<Vec<String>>,
+    #[serde(default)]
+    default_template: Option<String>,
+    #[serde(default)]
+    choices: Option<Vec<String>>,
+    #[serde(default)]
+    until: Option<String>,
+    #[serde(default)]
+    env: Option<String>,
+    #[serde(default)]
+    required_if: Vec<(String, String)>,
+    #[serde(default)]
+    short_circuit: bool,
+    #[serde(default)]
+    value_files: bool,
+    #[serde(default)]
+    secret: bool,
+    #[serde(default)]
+    allow_empty: Option<bool>,
+    #[serde(default)]
+    negatable: bool,
+    #[serde(default)]
+    enables: Vec<String>,
+    #[serde(default)]
+    since: Option<String>,
+    #[serde(default)]
+    removed: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PositionalDefinition {
+    placeholder: String,
+    #[serde(default = "one_value")]
+    values: Amount,
+    #[serde(default)]
+    help: String,
+}
+
+// The amount of values an argument takes, as a number or as a string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Amount {
+    Number(usize),
+    Text(String),
+}
+
+fn no_values() -> Amount {
+    Amount::Number(0)
+}
+
+fn one_value() -> Amount {
+    Amount::Number(1)
+}
+
+impl Amount {
+    fn arity<E: Error>(&self) -> Result<Arity, E> {
+        match self {
+            Amount::Number(amount) => Ok(Arity::Exactly(*amount)),
+            Amount::Text(amount) => Arity::parse(amount).ok_or_else(|| {
+                E::custom(format!(
+                    "invalid amount of values \"{}\", expected a positive integer, 0 or +",
+                    amount
+                ))
+            }),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OptionSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let definition = OptionDefinition::deserialize(deserializer)?;
+        let arity = definition.values.arity()?;
+        let fits_default = match (&definition.default, arity) {
+            (Some(default), Arity::Exactly(amount)) => amount > 0 && amount == default.len(),
+            _ => true,
+        };
+        if !fits_default {
+            return Err(D::Error::custom(
+                "the default doesn't match the amount of values the option takes",
+            ));
+        };
+
+        let mut option = OptionSpec::new(
+            definition.short.unwrap_or('-'),
+            definition.long.unwrap_or_default(),
+        )
+        .arity(arity)
+        .help(definition.help);
+        if option.short.is_none() && option.long.is_none() {
+            return Err(D::Error::custom("an option needs a short or a long name"));
+        };
+        if definition.required {
+            option = option.required();
+        };
+        for (name, value) in definition.required_if {
+            option = option.required_if(&name, &value);
+        }
+        if definition.short_circuit {
+            option = option.short_circuit();
+        };
+        if definition.global {
+            option = option.global();
+        };
+        if definition.value_files {
+            option = option.value_files();
+        };
+        if definition.secret {
+            option = option.secret();
+        };
+        if let Some(allow) = definition.allow_empty {
+            option = option.allow_empty(allow);
+        };
+        if definition.require_equals {
+            if arity != Arity::Exactly(1) || option.long.is_none() {
+                return Err(D::Error::custom(
+                    "an option requiring = has to take one value and have a long name",
+                ));
+            };
+            option = option.require_equals();
+        };
+        if let Some(default) = definition.default {
+            option = option.default_values(default);
+        };
+        if let Some(template) = definition.default_template {
+            if option.default.is_some() || arity == Arity::Exactly(0) {
+                return Err(D::Error::custom(
+                    "an option with a default template has to take values, without a default",
+                ));
+            };
+            option = option.default_template(&template);
+        };
+        if let Some(choices) = definition.choices {
+            option = option.choices(choices);
+        };
+        if let Some(keyword) = definition.until {
+            if arity == Arity::Exactly(0) || option.require_equals {
+                return Err(D::Error::custom(
+                    "an option ending at a keyword has to take values, without requiring =",
+                ));
+            };
+            option = option.until(&keyword);
+        };
+        if let Some(variable) = definition.env {
+            option = option.env(&variable);
+        };
+        if definition.negatable {
+            if arity != Arity::Exactly(0) || option.long.is_none() {
+                return Err(D::Error::custom(
+                    "a negatable option has to take no values and have a long name",
+                ));
+            };
+            option = option.negatable();
+        };
+        if !definition.enables.is_empty() {
+            if arity != Arity::Exactly(0) {
+                return Err(D::Error::custom(
+                    "an option enabling other flags has to take no values",
+                ));
+            };
+            option = option.enables(definition.enables);
+        };
+        if let Some(version) = definition.since {
+            option = option.since(&version);
+        };
+        if let Some(version) = definition.removed {
+            option = option.removed(&version);
+        };
+        Ok(option)
+    }
+}
+
+impl<'de> Deserialize<'de> for PositionalSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let definition = PositionalDefinition::deserialize(deserializer)?;
+        Ok(PositionalSpec::new(definition.placeholder)
+            .arity(definition.values.arity()?)
+            .help(definition.help))
+    }
+}
+
+impl<'de> Deserialize<'de> for Argument {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let definition = ArgumentDefinition::deserialize(deserializer)?;
+        let mut argument = Argument::new(
+            &definition.name,
+            &definition.description,
+            &definition.epilog,
+            &definition.credits,
+        );
+        fill(&mut argument, definition).map_err(D::Error::custom)?;
+        argument
+            .validate()
+            .map_err(|problems| D::Error::custom(problems.join(", ")))?;
+        Ok(argument)
+    }
+}
+
+// Adds the arguments and subcommands of a definition to an Argument. Adding an argument using the
+// name of another one replaces it, so names used twice in the data are an error instead
+fn fill(argument: &mut Argument, definition: ArgumentDefinition) -> Result<(), String> {
+    for (code, help) in definition.exit_statuses {
+        argument.add_exit_status(code, &help);
+    }
+    let mut names: HashSet<String> = HashSet::new();
+    for option in definition.options {
+        let shorts = option.short.map(|short| format!("-{}", short));
+        let longs = option.long.as_deref().map(|long| format!("--{}", long));
+        if let Some(name) = shorts
+            .into_iter()
+            .chain(longs)
+            .find(|name| !names.insert(name.clone()))
+        {
+            return Err(format!(
+                "{} is defined twice in \"{}\"",
+                name, argument.name
+            ));
+        };
+        argument.add(option);
+    }
+    for positional in definition.positionals {
+        if !names.insert(positional.placeholder.to_string()) {
+            return Err(format!(
+                "{} is defined twice in \"{}\"",
+                positional.placeholder, argument.name
+            ));
+        };
+        argument.add(positional);
+    }
+    for subcommand in definition.subcommands {
+        let child = argument.add_subcommand(&subcommand.name, &subcommand.description);
+        child.epilog = Text::from(subcommand.epilog.as_str());
+        // subcommands without credits of their own keep the ones of their parent
+        if !subcommand.credits.is_empty() {
+            child.credits = subcommand.credits.to_owned();
+        };
+        fill(child, subcommand)?;
+    }
+    Ok(())
+}
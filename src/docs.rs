@@ -0,0 +1,51 @@
+// Keeping a section of a documentation file (e.g. a README) in sync with the help
+//
+// The section is delimited by two HTML comments, which Markdown doesn't render:
+//
+//     <!-- taap:usage:start -->
+//     ...replaced by the Markdown help...
+//     <!-- taap:usage:end -->
+
+use std::{fs, io, path::Path};
+
+fn markers(section: &str) -> (String, String) {
+    (
+        format!("<!-- taap:{}:start -->", section),
+        format!("<!-- taap:{}:end -->", section),
+    )
+}
+
+// Replaces the contents of a section, returning None if the markers are missing
+pub(crate) fn replace_section(text: &str, section: &str, contents: &str) -> Option<String> {
+    let (start_marker, end_marker) = markers(section);
+    let start = text.find(&start_marker)? + start_marker.len();
+    let end = start + text[start..].find(&end_marker)?;
+    Some(format!(
+        "{}\n{}\n{}",
+        &text[..start],
+        contents.trim_end(),
+        &text[end..]
+    ))
+}
+
+// Rewrites the section in a file, returning if the file changed
+pub(crate) fn update_section(path: &Path, section: &str, contents: &str) -> io::Result<bool> {
+    let text = fs::read_to_string(path)?;
+    let Some(updated) = replace_section(&text, section, contents) else {
+        let (start_marker, end_marker) = markers(section);
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} doesn't contain a section marked with {} and {}",
+                path.display(),
+                start_marker,
+                end_marker
+            ),
+        ));
+    };
+    if updated == text {
+        return Ok(false);
+    };
+    fs::write(path, updated)?;
+    Ok(true)
+}
@@ -0,0 +1,104 @@
+// The error returned when parsing fails, instead of printing it and exiting
+
+use std::{error, fmt, io};
+
+/// An error found while parsing the command line, returned by `Argument::try_parse_args`
+///
+/// It implements std::error::Error, so it can be returned using `?` from functions returning
+/// e.g. `Result<T, Box<dyn std::error::Error>>` or `anyhow::Result<T>`, or wrapped by an error
+/// type made with thiserror. Errors caused by something else, like a file that couldn't be
+/// read, keep that error as their source.
+///
+/// Code Example:
+/// ```no_run
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_option('c', "count", "1", Some("How many times to do it"));
+/// let parsed_arguments = arguments.try_parse_args(None)?;
+/// // ...
+/// Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+/// The kind of an Error, for handling some errors differently than others
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The command line was used incorrectly, in another way than the kinds below
+    Usage,
+    /// An option didn't get all of the values it takes, or the keyword ending them
+    MissingOptionValue,
+    /// A positional argument didn't get all of the values it takes
+    MissingPositional,
+    /// A required option wasn't used, either always required or because of the value of
+    /// another argument
+    MissingRequired,
+    /// A value isn't accepted, e.g. it's not one of the choices or it's empty
+    InvalidValue,
+    /// A value was given to an option taking none
+    UnexpectedValue,
+    /// An option that can only be used once was used again
+    DuplicateOption,
+    /// Arguments that can't be used together were, see `Argument::add_exclusive`
+    ConflictingArguments,
+    /// An option doesn't exist, only an error after `Argument::set_strict`
+    UnknownOption,
+    /// A subcommand doesn't exist
+    UnknownSubcommand,
+    /// The command line is larger than the limits set with `Argument::set_limits`
+    LimitExceeded,
+    /// An argument of the program isn't valid UTF-8. Holds the index of the argument, where 0
+    /// is the name of the program
+    InvalidEncoding(usize),
+}
+
+impl Error {
+    pub(crate) fn new(
+        kind: ErrorKind,
+        message: String,
+        source: Option<Box<dyn error::Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            kind,
+            message,
+            source,
+        }
+    }
+
+    /// Returns the kind of the error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the error message, the same one that parse_args would print
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn error::Error + 'static))
+    }
+}
+
+// so main can return an io::Result
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
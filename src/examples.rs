@@ -0,0 +1,70 @@
+// Usage examples on the help page, written once and rendered for the shell of the platform
+//
+// The command of an example has one line per "\n". When it's rendered, the lines are joined
+// using the line continuation of the shell, and the placeholder {/} becomes the path separator.
+
+/// The shell the usage examples on the help page are written for
+///
+/// It decides how the examples added using `Argument::add_example` are rendered: the path
+/// separator used for the placeholder {/}, and how a command continues on the next line.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_example("{bin} --config config{/}app.toml\n    --verbose", "Run with a config file");
+/// // always show the examples as PowerShell commands
+/// arguments.set_example_shell(taap::ExampleShell::PowerShell);
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExampleShell {
+    /// PowerShell on Windows, and a POSIX shell everywhere else
+    #[default]
+    Auto,
+    /// A POSIX shell like sh or bash: "/" and "\" at the end of a line
+    Posix,
+    /// PowerShell: "\" and "`" at the end of a line
+    PowerShell,
+    /// cmd.exe: "\" and "^" at the end of a line
+    Cmd,
+}
+
+impl ExampleShell {
+    fn resolve(self) -> Self {
+        match self {
+            ExampleShell::Auto if cfg!(windows) => ExampleShell::PowerShell,
+            ExampleShell::Auto => ExampleShell::Posix,
+            shell => shell,
+        }
+    }
+
+    pub(crate) fn path_separator(self) -> &'static str {
+        match self.resolve() {
+            ExampleShell::Posix | ExampleShell::Auto => "/",
+            ExampleShell::PowerShell | ExampleShell::Cmd => "\\",
+        }
+    }
+
+    // The language of the code blocks in the Markdown help
+    pub(crate) fn code_language(self) -> &'static str {
+        match self.resolve() {
+            ExampleShell::Posix | ExampleShell::Auto => "sh",
+            ExampleShell::PowerShell => "powershell",
+            ExampleShell::Cmd => "bat",
+        }
+    }
+
+    // Joins the lines of a command using the line continuation of the shell
+    pub(crate) fn join_lines(self, command: &str) -> String {
+        let continuation = match self.resolve() {
+            ExampleShell::Posix | ExampleShell::Auto => " \\\n",
+            ExampleShell::PowerShell => " `\n",
+            ExampleShell::Cmd => " ^\n",
+        };
+        command
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<&str>>()
+            .join(continuation)
+    }
+}
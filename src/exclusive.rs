@@ -0,0 +1,81 @@
+// Groups of arguments of which only one can be used at a time, like FILE and --stdin, see
+// Argument::add_exclusive
+
+use crate::{output::fail_with, parse::ParseResult, parsed::Source, Argument, Error, ErrorKind};
+use std::collections::{BTreeMap, BTreeSet};
+
+// The key of an argument in a group
+fn key(argument: &Argument, name: &str) -> String {
+    argument.argument_key(name).unwrap_or_else(|| {
+        panic!(
+            "Error! \"{}\" is exclusive with other arguments, but there's no such argument",
+            name
+        )
+    })
+}
+
+// An argument counts as used if it got values from anywhere but its default
+fn used(
+    argument: &Argument,
+    name: &str,
+    return_map: &ParseResult,
+    sources: &BTreeMap<String, Source>,
+) -> bool {
+    let key = key(argument, name);
+    let (used, values) = return_map.get(&key).unwrap();
+    match argument.option_id(name) {
+        Some(_) => *used && sources.get(&key) != Some(&Source::Default),
+        None => !values.is_empty(),
+    }
+}
+
+// The positional arguments that can go without values, since an option of their group is used
+pub(crate) fn excused(
+    argument: &Argument,
+    return_map: &ParseResult,
+    sources: &BTreeMap<String, Source>,
+) -> BTreeSet<String> {
+    let mut excused = BTreeSet::new();
+    for group in argument.exclusive.iter() {
+        let option_used = group.iter().any(|name| {
+            argument.option_id(name).is_some() && used(argument, name, return_map, sources)
+        });
+        if option_used {
+            excused.extend(
+                group
+                    .iter()
+                    .filter(|name| argument.option_id(name).is_none())
+                    .map(|name| key(argument, name)),
+            );
+        };
+    }
+    excused
+}
+
+// Fails if more than one argument of a group is used
+pub(crate) fn check(
+    argument: &Argument,
+    return_map: &ParseResult,
+    sources: &BTreeMap<String, Source>,
+) -> Result<(), Error> {
+    let display = |name: &str| match argument.option_id(name) {
+        Some(id) => argument.options[id].display_name(),
+        None => name.to_owned(),
+    };
+    for group in argument.exclusive.iter() {
+        let mut used = group
+            .iter()
+            .filter(|name| used(argument, name, return_map, sources));
+        if let (Some(first), Some(second)) = (used.next(), used.next()) {
+            return Err(fail_with(
+                ErrorKind::ConflictingArguments,
+                format_args!(
+                    "{} can't be used together with {}",
+                    display(first),
+                    display(second)
+                ),
+            ));
+        };
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+// Exit statuses shown on the help page, besides the single codes added with add_exit_status
+
+use std::collections::BTreeMap;
+
+/// A titled group of exit statuses, rendered below the other exit statuses on the help page
+///
+/// Add it to an Argument using `Argument::add_exit_status_category`.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, ExitStatusCategory};
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_exit_status(0, "Everything went well!");
+/// arguments.add_exit_status_category(
+///     ExitStatusCategory::new("Usage and system errors (see sysexits.h)")
+///         .status(64, "The command was used incorrectly")
+///         .range(65, 78, "Other errors, like missing files or permissions"),
+/// );
+/// // ...
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ExitStatusCategory {
+    pub(crate) title: String,
+    // keyed by the first and the last code, which are the same for a single code
+    pub(crate) statuses: BTreeMap<(u16, u16), String>,
+}
+
+impl ExitStatusCategory {
+    /// Returns a new, empty category
+    ///
+    /// | Parameter | Type | Description                                |
+    /// |-----------|------|--------------------------------------------|
+    /// | title     | &str | The title of the category on the help page |
+    ///
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            statuses: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a single exit status to the category
+    pub fn status(self, code: u16, help: &str) -> Self {
+        self.range(code, code, help)
+    }
+
+    /// Adds a range of exit statuses (including the first and the last) to the category
+    pub fn range(mut self, first: u16, last: u16, help: &str) -> Self {
+        check_range(first, last);
+        self.statuses.insert((first, last), help.to_string());
+        self
+    }
+}
+
+pub(crate) fn check_range(first: u16, last: u16) {
+    if first > last {
+        panic!(
+            "Error! The exit status range {}-{} ends before it starts",
+            first, last
+        );
+    };
+}
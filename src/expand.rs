@@ -0,0 +1,135 @@
+// Expansion of file-list positionals: glob patterns (with the "glob" feature) and paths read
+// from a --files-from style list, and values of options read from files
+
+use crate::{
+    limits::Limits,
+    output::{fail, fail_caused},
+    sanitize::sanitize,
+    Error,
+};
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+// Expands every value as a glob pattern and appends the paths listed in each of the list files
+pub(crate) fn expand_file_list(
+    values: &[String],
+    lists: &[String],
+    limits: &Limits,
+) -> Result<Vec<String>, Error> {
+    let mut expanded: Vec<String> = vec![];
+    for value in values.iter() {
+        expand_pattern(value, &mut expanded, limits)?;
+    }
+    for list in lists.iter() {
+        read_file_list(list, &mut expanded, limits)?;
+    }
+    Ok(expanded)
+}
+
+// Adds a path to the expanded list, while keeping it within the limits
+fn push_path(expanded: &mut Vec<String>, path: String, limits: &Limits) -> Result<(), Error> {
+    limits.check_value(&path)?;
+    limits.check_expansion(expanded.len() + 1)?;
+    expanded.push(path);
+    Ok(())
+}
+
+#[cfg(feature = "glob")]
+fn expand_pattern(value: &str, expanded: &mut Vec<String>, limits: &Limits) -> Result<(), Error> {
+    if !value.contains(['*', '?', '[']) {
+        return push_path(expanded, value.to_string(), limits);
+    };
+    match glob::glob(value) {
+        Ok(paths) => {
+            let matched_before = expanded.len();
+            for path in paths.flatten() {
+                push_path(expanded, path.to_string_lossy().into_owned(), limits)?;
+            }
+            // just like a shell, a pattern without any matches is kept as it is
+            if expanded.len() == matched_before {
+                push_path(expanded, value.to_string(), limits)?;
+            };
+            Ok(())
+        }
+        Err(err) => Err(fail_caused(
+            format_args!("Invalid glob pattern \"{}\"", sanitize(value)),
+            err,
+        )),
+    }
+}
+
+#[cfg(not(feature = "glob"))]
+fn expand_pattern(value: &str, expanded: &mut Vec<String>, limits: &Limits) -> Result<(), Error> {
+    push_path(expanded, value.to_string(), limits)
+}
+
+// Reads one path per line, skipping empty lines. "-" reads the list from stdin
+fn read_file_list(list: &str, expanded: &mut Vec<String>, limits: &Limits) -> Result<(), Error> {
+    let contents = if list == "-" {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer).map(|_| buffer)
+    } else {
+        fs::read_to_string(list)
+    };
+    match contents {
+        Ok(contents) => {
+            for line in contents.lines().filter(|line| !line.is_empty()) {
+                push_path(expanded, line.to_string(), limits)?;
+            }
+            Ok(())
+        }
+        Err(err) => Err(fail_caused(
+            format_args!("Could not read file list \"{}\"", sanitize(list)),
+            err,
+        )),
+    }
+}
+
+// Reads the value of an option from a file if it's written as @PATH or file:PATH, returning the
+// path and the contents without the surrounding whitespace. The contents are never part of an
+// error message, since they're often secrets
+pub(crate) fn value_file(
+    used_as: &str,
+    value: &str,
+    limits: &Limits,
+) -> Result<Option<(String, String)>, Error> {
+    let Some(path) = value
+        .strip_prefix('@')
+        .or_else(|| value.strip_prefix("file:"))
+    else {
+        return Ok(None);
+    };
+    let could_not_read = |err: io::Error| {
+        fail_caused(
+            format_args!(
+                "Could not read the value of {} from \"{}\"",
+                used_as,
+                sanitize(path)
+            ),
+            err,
+        )
+    };
+    // at most one byte more than the limit is read, so a huge file (or one without an end, like
+    // /dev/zero or a pipe) is never read in full
+    let file = fs::File::open(path).map_err(could_not_read)?;
+    let limit = limits
+        .max_value_length
+        .map_or(u64::MAX, |max| max as u64 + 1);
+    let mut bytes: Vec<u8> = vec![];
+    file.take(limit)
+        .read_to_end(&mut bytes)
+        .map_err(could_not_read)?;
+    if let Some(max_value_length) = limits.max_value_length.filter(|&max| bytes.len() > max) {
+        return Err(fail(format_args!(
+            "The value of {} in \"{}\" is longer than the limit of {} bytes",
+            used_as,
+            sanitize(path),
+            max_value_length
+        )));
+    };
+    let contents = String::from_utf8(bytes)
+        .map_err(|err| could_not_read(io::Error::new(io::ErrorKind::InvalidData, err)))?;
+    Ok(Some((path.to_string(), contents.trim().to_string())))
+}
@@ -0,0 +1,55 @@
+// External subcommands: executables named after the program (e.g. "mytool-deploy"), which get
+// the rest of the command line when they're used as a subcommand ("mytool deploy ...")
+
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Finds the executables starting with the prefix in the directories of a PATH-like value, by
+// the name of the subcommand. Like a shell, the first directory containing one wins
+pub(crate) fn discover(prefix: &str, path: &OsStr) -> BTreeMap<String, PathBuf> {
+    let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for directory in env::split_paths(path) {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = subcommand_name(prefix, &path) else {
+                continue;
+            };
+            if !name.is_empty() && is_executable(&path) {
+                found.entry(name).or_insert(path);
+            };
+        }
+    }
+    found
+}
+
+// The name of the subcommand, without the prefix (and without .exe on Windows)
+fn subcommand_name(prefix: &str, path: &Path) -> Option<String> {
+    let name = match cfg!(windows) {
+        true => path.file_stem()?,
+        false => path.file_name()?,
+    };
+    name.to_str()?.strip_prefix(prefix).map(str::to_string)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("exe"))
+}
@@ -0,0 +1,99 @@
+use crate::{
+    help,
+    output::{self, Stop},
+    parse,
+    spec::Arity,
+    Argument,
+};
+
+/// The result of parsing a flag-only command line with `Argument::parse_flags`
+///
+/// Instead of building a map of vectors, the flags that were used are stored as bits, which
+/// means no allocations are made at all
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Flags<'a> {
+    argument: &'a Argument,
+    set: u128,
+}
+
+impl Flags<'_> {
+    /// Returns true if the flag was used
+    ///
+    /// The flag is looked up by the same name as in the result of parse_args (the short name,
+    /// or the long name if the flag has no short name), but the long name is accepted as well
+    ///
+    /// | Parameter | Type | Description          |
+    /// |-----------|------|----------------------|
+    /// | name      | &str | The name of the flag |
+    ///
+    pub fn is_set(&self, name: &str) -> bool {
+        self.argument
+            .options
+            .iter()
+            .position(|option| option.is_named(name))
+            .is_some_and(|id| self.set & (1 << id) != 0)
+    }
+}
+
+pub(crate) fn parse_flags<'a, I, S>(argument: &'a Argument, arglist: I) -> Result<Flags<'a>, Stop>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let options = &argument.options;
+    if !argument.positionals.is_empty()
+        || options
+            .iter()
+            .any(|option| option.arity != Arity::Exactly(0))
+    {
+        panic!(
+            "Error! parse_flags can only be used when every argument is a flag without parameters"
+        );
+    };
+    if options.len() > 128 {
+        panic!("Error! parse_flags supports at most 128 flags");
+    };
+
+    let mut set: u128 = 0;
+    let mut tokens: usize = 0;
+    for token in arglist.into_iter() {
+        let token = token.as_ref();
+        // everything after -- is a positional argument, which are skipped as well
+        if token == "--" || argument.terminator.as_deref() == Some(token) {
+            break;
+        };
+        tokens += 1;
+        argument.limits.check_token_count(tokens)?;
+        argument.limits.check_value(token)?;
+        if let Some(long) = token.strip_prefix("--") {
+            match argument.longs.get(long) {
+                Some(id) => set |= 1 << id,
+                None if argument.strict => return Err(parse::fail_unknown(argument, token).into()),
+                None => {}
+            };
+        } else if let Some(shorts) = token.strip_prefix('-') {
+            for short in shorts.chars() {
+                match argument.shorts.get(&short) {
+                    Some(id) => set |= 1 << id,
+                    None if argument.strict => {
+                        return Err(parse::fail_unknown(argument, token).into())
+                    }
+                    None => {}
+                };
+            }
+        };
+    }
+
+    let flags = Flags { argument, set };
+    if !argument.raw && flags.is_set("h") {
+        help::print(argument, output::format());
+        return Err(Stop::Exited(0));
+    };
+    if let Some(version) = &argument.version {
+        if flags.is_set("version") {
+            output::print(&format!("{} {}", argument.name, version));
+            return Err(Stop::Exited(0));
+        };
+    };
+    Ok(flags)
+}
@@ -0,0 +1,1412 @@
+// Rendering of the help page
+
+use crate::{
+    choices::Choices,
+    examples::ExampleShell,
+    locale, man,
+    output::{self, json_string, OutputFormat},
+    sanitize::{sanitize, strip_ansi},
+    spec::Arity,
+    subcommand, Argument,
+};
+use std::{
+    borrow::Cow,
+    io::{self, IsTerminal},
+};
+
+/// How the help page is rendered
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// // rescue shells and serial consoles get the compact help
+/// if std::env::var("TERM").map_or(true, |term| term == "dumb" || term == "linux") {
+///     arguments.set_help_style(taap::HelpStyle::Compact);
+/// }
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HelpStyle {
+    /// The full help page, with the description, exit statuses, sections, epilog and credits
+    #[default]
+    Full,
+    /// Only the usage line and one line per argument, using nothing but ASCII and spaces, for
+    /// primitive terminals (e.g. in an initramfs or a rescue environment)
+    Compact,
+}
+
+/// How the amount of values of a positional argument is shown in the help
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_arg("FILES", "+", Some("The files to copy"));
+/// arguments.add_arg("TARGET", "1", Some("Where to copy them to"));
+/// // "Usage: Name FILES*∞ TARGET [OPTIONS]" instead of "Usage: Name [FILES]... <TARGET> [OPTIONS]"
+/// arguments.set_arity_notation(taap::ArityNotation::Stars);
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ArityNotation {
+    /// In brackets, like \<FILE\> \<FILE\> and \[FILES\]..., as in the synopsis of a man page.
+    /// Arguments that can go without values are in square brackets
+    #[default]
+    Brackets,
+    /// After the name, like FILE*2 and FILES*∞ (FILES... in the compact, Markdown and man help)
+    Stars,
+}
+
+/// The annotations shown after the help text of the options
+///
+/// Every annotation is off by default. The JSON help always contains all of them.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, HelpAnnotations, Opt};
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(Opt::new('p', "port").takes(1).default("8080").help("The port to listen on"));
+/// // "The port to listen on [default: 8080]"
+/// arguments.set_help_annotations(HelpAnnotations {
+///     defaults: true,
+///     ..Default::default()
+/// });
+/// // ...
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct HelpAnnotations {
+    /// Show the default of options that have one, as "\[default: VALUE\]"
+    pub defaults: bool,
+    /// Mark required options with "(required)"
+    pub required: bool,
+}
+
+/// When the help page keeps its decorations, like colors in the help texts
+///
+/// By default the decorations are only kept when the help is printed to a terminal, so e.g.
+/// `mytool --help | grep` always gets stable plain text.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "\u{1b}[1mDescription\u{1b}[0m", "Epilog, text at the bottom", "Credits");
+/// // the user asked for colors, even when piping the output
+/// if std::env::var_os("CLICOLOR_FORCE").is_some() {
+///     arguments.set_decorations(taap::Decorations::Always);
+/// }
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Decorations {
+    /// Keep the decorations if the standard output is a terminal
+    #[default]
+    Auto,
+    /// Always keep the decorations
+    Always,
+    /// Never keep the decorations
+    Never,
+}
+
+/// When the help page shows the Exit Statuses section
+///
+/// By default the section is shown as soon as anything besides a lone exit status 0 is
+/// registered, so a tool documenting a single error status still shows it. The JSON help always
+/// contains all exit statuses.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_exit_status(0, "Everything went well!");
+/// // show "0  Everything went well!" too
+/// arguments.set_exit_status_section(taap::ExitStatusSection::Always);
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExitStatusSection {
+    /// Show the section if an exit status other than 0 (or a range or category) is registered
+    #[default]
+    Auto,
+    /// Show the section if any exit status is registered
+    Always,
+    /// Never show the section
+    Never,
+}
+
+/// How the codes of the exit statuses are written on the help page
+///
+/// Hexadecimal codes suit tools whose exit statuses are bitmasks, e.g. "0x41" for the flags 0x40
+/// and 0x01. The JSON help always contains the codes as numbers.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_exit_status(0x41, "The device is busy and locked");
+/// arguments.set_exit_status_format(taap::ExitStatusFormat::Hexadecimal);
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExitStatusFormat {
+    /// Decimal codes, e.g. "65"
+    #[default]
+    Decimal,
+    /// Hexadecimal codes, e.g. "0x41"
+    Hexadecimal,
+}
+
+/// The direction the plain help page is laid out in
+///
+/// In a right-to-left layout, every line is marked as right-to-left and every column is isolated
+/// from the others using Unicode bidirectional controls, so terminals supporting them show the
+/// columns from right to left without mixing up e.g. Hebrew help texts and option names. The
+/// columns are separated by tabs, so wide (e.g. fullwidth) characters never break the alignment
+/// in either direction.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_translations("he", "description = תיאור");
+/// arguments.set_text_direction(taap::TextDirection::RightToLeft);
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TextDirection {
+    /// Right to left if the language used (see `Argument::set_language`) is written from right
+    /// to left, like Arabic or Hebrew
+    #[default]
+    Auto,
+    /// Always left to right
+    LeftToRight,
+    /// Always right to left
+    RightToLeft,
+}
+
+impl Decorations {
+    fn enabled(self) -> bool {
+        match self {
+            Decorations::Auto => io::stdout().is_terminal(),
+            Decorations::Always => true,
+            Decorations::Never => false,
+        }
+    }
+}
+
+// The help page of an Argument, independent of how it's rendered. Every renderer is driven off
+// this model, so all formats always show the same information
+pub(crate) struct HelpModel<'a> {
+    name: &'a str,
+    description: Cow<'a, str>,
+    positionals: Vec<PositionalEntry<'a>>,
+    options: Vec<OptionEntry<'a>>,
+    subcommands: Vec<(&'a str, Cow<'a, str>)>,
+    // the description and the command of every example
+    examples: Vec<(Cow<'a, str>, String)>,
+    example_shell: ExampleShell,
+    // if the built-in help subcommand is listed
+    help_dispatch: bool,
+    exit_statuses: Vec<ExitStatusEntry<'a>>,
+    exit_status_categories: Vec<(Cow<'a, str>, Vec<ExitStatusEntry<'a>>)>,
+    // if the Exit Statuses section is shown on the help page
+    exit_status_section: bool,
+    exit_status_format: ExitStatusFormat,
+    // the titles and texts of the sections added with add_section
+    sections: Vec<(&'a str, Cow<'a, str>)>,
+    epilog: Cow<'a, str>,
+    credits: &'a str,
+    annotations: HelpAnnotations,
+    notation: ArityNotation,
+    // the positional arguments and if there are subcommands, for the usage line, which stays
+    // the same when the entries are searched
+    synopsis: (Vec<(&'a str, Arity)>, bool),
+}
+
+// A single exit status, or a range of them if first and last differ
+struct ExitStatusEntry<'a> {
+    first: u16,
+    last: u16,
+    help: Cow<'a, str>,
+}
+
+impl ExitStatusEntry<'_> {
+    fn codes(&self, format: ExitStatusFormat) -> String {
+        let code = |code: u16| match format {
+            ExitStatusFormat::Decimal => code.to_string(),
+            ExitStatusFormat::Hexadecimal => format!("{:#x}", code),
+        };
+        match self.first == self.last {
+            true => code(self.first),
+            false => format!("{}-{}", code(self.first), code(self.last)),
+        }
+    }
+}
+
+struct PositionalEntry<'a> {
+    placeholder: &'a str,
+    arity: Arity,
+    help: Cow<'a, str>,
+}
+
+struct OptionEntry<'a> {
+    short: Option<char>,
+    long: Option<&'a str>,
+    negatable: bool,
+    arity: Arity,
+    help: Cow<'a, str>,
+    required: bool,
+    default: Option<&'a [String]>,
+    choices: Option<&'a Choices>,
+    until: Option<&'a str>,
+    env: Option<&'a str>,
+    required_if: &'a [(String, String)],
+    // the display names of the flags it enables
+    enables: Vec<String>,
+}
+
+impl<'a> OptionEntry<'a> {
+    // the long name as shown in the help, with the negation of a negatable flag
+    fn long_name(&self) -> Option<Cow<'a, str>> {
+        let long = self.long?;
+        Some(match self.negatable {
+            true => Cow::Owned(format!("[no-]{}", long)),
+            false => Cow::Borrowed(long),
+        })
+    }
+}
+
+impl<'a> HelpModel<'a> {
+    pub(crate) fn new(argument: &'a Argument) -> Self {
+        let placeholder = |name: &str| match argument.placeholders.get(name) {
+            Some(value) => Some(value.to_owned()),
+            None if name == "bin" => Some(argument.name.to_owned()),
+            None => None,
+        };
+        // the translation of a text, if there is one
+        let translations = locale::bundle(argument);
+        let translate = |key: &str, text: Cow<'a, str>| -> Cow<'a, str> {
+            match translations.and_then(|translations| translations.get(key)) {
+                Some(translation) => Cow::Borrowed(translation),
+                None => text,
+            }
+        };
+        let exit_status = |(first, last): (u16, u16), help: &'a String| ExitStatusEntry {
+            first,
+            last,
+            help: interpolate(
+                translate(
+                    &match first == last {
+                        true => format!("exit-status.{}", first),
+                        false => format!("exit-status.{}-{}", first, last),
+                    },
+                    Cow::Borrowed(help),
+                ),
+                &placeholder,
+            ),
+        };
+        let mut exit_statuses: Vec<ExitStatusEntry> = argument
+            .exit_statuses
+            .iter()
+            .map(|(code, help)| exit_status((*code, *code), help))
+            .chain(
+                argument
+                    .exit_status_ranges
+                    .iter()
+                    .map(|(range, help)| exit_status(*range, help)),
+            )
+            .collect();
+        exit_statuses.sort_by_key(|entry| (entry.first, entry.last));
+        let exit_status_section = match argument.exit_status_section {
+            ExitStatusSection::Auto => {
+                !argument.exit_status_categories.is_empty()
+                    || exit_statuses.iter().any(|entry| entry.last != 0)
+            }
+            ExitStatusSection::Always => {
+                !argument.exit_status_categories.is_empty() || !exit_statuses.is_empty()
+            }
+            ExitStatusSection::Never => false,
+        };
+        Self {
+            name: &argument.name,
+            description: interpolate(
+                translate("description", argument.description.render()),
+                &placeholder,
+            ),
+            positionals: argument
+                .positionals
+                .iter()
+                .map(|positional| PositionalEntry {
+                    placeholder: &positional.placeholder,
+                    arity: positional.arity,
+                    help: paragraphs(interpolate(
+                        translate(
+                            &format!("positional.{}", positional.placeholder),
+                            positional.help.render(),
+                        ),
+                        &placeholder,
+                    )),
+                })
+                .collect(),
+            options: argument
+                .options
+                .iter()
+                .map(|option| OptionEntry {
+                    short: option.short,
+                    long: option.long.as_deref(),
+                    negatable: option.negatable,
+                    arity: option.arity,
+                    // {default} is the default of the option itself
+                    help: paragraphs(interpolate(
+                        translate(&locale::option_key(option), option.help.render()),
+                        &|name: &str| match name {
+                            "default" => {
+                                Some(option.default.as_deref().unwrap_or_default().join(" "))
+                            }
+                            _ => placeholder(name),
+                        },
+                    )),
+                    required: option.required,
+                    default: option.default.as_deref(),
+                    choices: option.choices.as_ref(),
+                    until: option.until.as_deref(),
+                    env: option.env.as_deref(),
+                    required_if: &option.required_if,
+                    enables: option
+                        .enables
+                        .iter()
+                        .map(|name| match argument.option_id(name) {
+                            Some(id) => argument.options[id].display_name(),
+                            None => name.to_owned(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            subcommands: argument
+                .subcommands
+                .iter()
+                .map(|sub| {
+                    (
+                        sub.name.as_str(),
+                        interpolate(
+                            translate(
+                                &format!("subcommand.{}", sub.name),
+                                sub.description.render(),
+                            ),
+                            &placeholder,
+                        ),
+                    )
+                })
+                .collect(),
+            // unless it's been replaced by a subcommand of the same name
+            help_dispatch: !argument.subcommands.is_empty()
+                && !argument
+                    .subcommands
+                    .iter()
+                    .any(|sub| sub.name == subcommand::HELP),
+            exit_statuses,
+            exit_status_section,
+            exit_status_categories: argument
+                .exit_status_categories
+                .iter()
+                .map(|category| {
+                    (
+                        translate(
+                            &format!("exit-category.{}", category.title),
+                            Cow::Borrowed(&category.title),
+                        ),
+                        category
+                            .statuses
+                            .iter()
+                            .map(|(range, help)| exit_status(*range, help))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            exit_status_format: argument.exit_status_format,
+            examples: argument
+                .examples
+                .iter()
+                .map(|(command, description)| {
+                    let placeholder = |name: &str| match name {
+                        "/" => Some(argument.example_shell.path_separator().to_string()),
+                        _ => placeholder(name),
+                    };
+                    (
+                        interpolate(Cow::Borrowed(description), &placeholder),
+                        argument
+                            .example_shell
+                            .join_lines(&interpolate(Cow::Borrowed(command), &placeholder)),
+                    )
+                })
+                .collect(),
+            example_shell: argument.example_shell,
+            sections: argument
+                .sections
+                .iter()
+                .map(|(title, text)| {
+                    (
+                        title.as_str(),
+                        interpolate(
+                            translate(&format!("section.{}", title), text.render()),
+                            &placeholder,
+                        ),
+                    )
+                })
+                .collect(),
+            epilog: interpolate(translate("epilog", argument.epilog.render()), &placeholder),
+            credits: &argument.credits,
+            annotations: argument.help_annotations,
+            notation: argument.arity_notation,
+            synopsis: (
+                argument
+                    .positionals
+                    .iter()
+                    .map(|positional| (&*positional.placeholder, positional.arity))
+                    .collect(),
+                !argument.subcommands.is_empty(),
+            ),
+        }
+    }
+
+    // The help text of an option, followed by the annotations that are turned on
+    fn option_help(&self, option: &OptionEntry) -> String {
+        let mut help = option.help.to_string();
+        let mut annotate = |annotation: String| {
+            if !help.is_empty() {
+                help.push(' ');
+            };
+            help.push_str(&annotation);
+        };
+        // the keyword is needed to use the option at all, so it's always shown
+        if let Some(keyword) = option.until {
+            annotate(format!("(end the values with \"{}\")", keyword));
+        };
+        if !option.enables.is_empty() {
+            annotate(format!("(same as {})", option.enables.join(" ")));
+        };
+        // where the value can come from instead of the command line, so it's always shown
+        if let Some(variable) = option.env {
+            annotate(format!("[env: {}]", variable));
+        };
+        if let Some(default) = option.default.filter(|_| self.annotations.defaults) {
+            annotate(format!("[default: {}]", default.join(" ")));
+        };
+        if option.required && self.annotations.required {
+            annotate("(required)".to_string());
+        };
+        for (name, value) in option
+            .required_if
+            .iter()
+            .filter(|_| self.annotations.required)
+        {
+            annotate(format!("(required if {} is \"{}\")", name, value));
+        }
+        help
+    }
+
+    // The usage line without "Usage: ", marking the amount of values with the given markers
+    // Keeps only the entries whose names or texts contain the query, ignoring case. Returns if
+    // any entries are left
+    fn retain_matching(&mut self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let matches = |texts: &[&str]| {
+            texts
+                .iter()
+                .any(|text| text.to_lowercase().contains(&query))
+        };
+        self.positionals
+            .retain(|positional| matches(&[positional.placeholder, &positional.help]));
+        self.options.retain(|option| {
+            let short = option.short.map(String::from).unwrap_or_default();
+            matches(&[&short, option.long.unwrap_or_default(), &option.help])
+        });
+        self.subcommands
+            .retain(|(name, description)| matches(&[name, description]));
+        self.help_dispatch &= matches(&[subcommand::HELP]);
+        self.examples
+            .retain(|(description, command)| matches(&[description, command]));
+        self.exit_statuses.retain(|status| matches(&[&status.help]));
+        for (_, statuses) in self.exit_status_categories.iter_mut() {
+            statuses.retain(|status| matches(&[&status.help]));
+        }
+        self.exit_status_categories
+            .retain(|(title, statuses)| !statuses.is_empty() || matches(&[title]));
+        self.sections
+            .retain(|(title, text)| matches(&[title, text]));
+        !(self.positionals.is_empty()
+            && self.options.is_empty()
+            && self.subcommands.is_empty()
+            && self.examples.is_empty()
+            && self.exit_statuses.is_empty()
+            && self.exit_status_categories.is_empty()
+            && self.sections.is_empty())
+    }
+
+    fn usage(&self, amount: impl Fn(Arity) -> String) -> String {
+        let mut usage = self.name.to_string();
+        let (positionals, subcommands) = &self.synopsis;
+        for (placeholder, arity) in positionals.iter() {
+            usage.push(' ');
+            usage.push_str(&self.positional_word(placeholder, *arity, &amount));
+        }
+        if *subcommands {
+            usage.push_str(" SUBCOMMAND");
+        };
+        usage.push_str(" [OPTIONS]");
+        usage
+    }
+
+    // A positional argument with the amount of values it takes, in the notation of the help
+    fn positional_word(
+        &self,
+        placeholder: &str,
+        arity: Arity,
+        amount: impl Fn(Arity) -> String,
+    ) -> String {
+        match (self.notation, arity) {
+            (ArityNotation::Stars, _) => format!("{}{}", placeholder, amount(arity)),
+            (ArityNotation::Brackets, Arity::Exactly(0)) => format!("[{}]", placeholder),
+            (ArityNotation::Brackets, Arity::Exactly(nargs)) => {
+                vec![format!("<{}>", placeholder); nargs].join(" ")
+            }
+            (ArityNotation::Brackets, Arity::Infinite) => format!("[{}]...", placeholder),
+        }
+    }
+}
+
+// Splits a help text into paragraphs at its blank lines, and joins the lines of every paragraph,
+// so the renderers get "first paragraph\n\nsecond paragraph"
+fn paragraphs(text: Cow<str>) -> Cow<str> {
+    if !text.contains('\n') {
+        return text;
+    };
+    let mut paragraphs: Vec<String> = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    for line in text.lines().map(str::trim) {
+        if !line.is_empty() {
+            paragraph.push(line);
+        } else if !paragraph.is_empty() {
+            paragraphs.push(paragraph.join(" "));
+            paragraph.clear();
+        };
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph.join(" "));
+    };
+    Cow::Owned(paragraphs.join("\n\n"))
+}
+
+// Puts a help text after the columns before it. The lines of later paragraphs are indented with
+// the same tabs as the first line, so they line up with it
+fn hanging(columns: &str, help: &str) -> String {
+    let indentation: String = columns
+        .trim_start_matches('\n')
+        .chars()
+        .map(|character| match character {
+            '\t' => '\t',
+            _ => ' ',
+        })
+        .collect();
+    let mut lines = help.split('\n');
+    let mut text = format!("{}{}", columns, lines.next().unwrap_or_default());
+    for line in lines {
+        text.push('\n');
+        if !line.is_empty() {
+            text.push_str(&indentation);
+            text.push_str(line);
+        };
+    }
+    text
+}
+
+// Replaces the placeholders in a help text, like {bin}. Unknown placeholders are left as they are
+fn interpolate<'t>(text: Cow<'t, str>, value: &dyn Fn(&str) -> Option<String>) -> Cow<'t, str> {
+    if !text.contains('{') {
+        return text;
+    };
+    let mut interpolated = String::new();
+    let mut rest = text.as_ref();
+    while let Some(start) = rest.find('{') {
+        interpolated.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let replaced = rest
+            .find('}')
+            .and_then(|end| Some((value(&rest[1..end])?, end)));
+        match replaced {
+            Some((replacement, end)) => {
+                interpolated.push_str(&replacement);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                interpolated.push('{');
+                rest = &rest[1..];
+            }
+        };
+    }
+    interpolated.push_str(rest);
+    Cow::Owned(interpolated)
+}
+
+// Renders the help page in the given format, and in the style set on the Argument for plain text
+pub(crate) fn render(argument: &Argument, format: OutputFormat) -> String {
+    render_model(argument, HelpModel::new(argument), format)
+}
+
+fn render_model(argument: &Argument, model: HelpModel, format: OutputFormat) -> String {
+    let right_to_left = match argument.text_direction {
+        TextDirection::Auto => locale::is_right_to_left(argument),
+        TextDirection::LeftToRight => false,
+        TextDirection::RightToLeft => true,
+    };
+    match (format, argument.help_style) {
+        (OutputFormat::Plain, _) if right_to_left => {
+            right_to_left_layout(&render_plain(&model, argument.help_style))
+        }
+        (OutputFormat::Plain, style) => render_plain(&model, style),
+        (OutputFormat::Markdown, _) => render_markdown(&model),
+        (OutputFormat::Json, _) => render_json(&model),
+    }
+}
+
+fn render_plain(model: &HelpModel, style: HelpStyle) -> String {
+    match style {
+        HelpStyle::Full => render_full(model),
+        HelpStyle::Compact => render_compact(model),
+    }
+}
+
+// Marks every line as right-to-left (using RLM), and isolates every column of it (using FSI and
+// PDI), so the direction of one column doesn't affect the others
+fn right_to_left_layout(help: &str) -> String {
+    help.lines()
+        .map(|line| match line.trim().is_empty() {
+            true => line.to_string(),
+            false => format!(
+                "\u{200f}{}",
+                line.split('\t')
+                    .map(|column| match column.trim().is_empty() {
+                        true => column.to_string(),
+                        // the indentation stays outside of the isolate
+                        false => {
+                            let text = column.trim_start();
+                            let indentation = &column[..column.len() - text.len()];
+                            format!("{}\u{2068}{}\u{2069}", indentation, text)
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\t")
+            ),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Prints the help page in the given format
+pub(crate) fn print(argument: &Argument, format: OutputFormat) {
+    output::print(&undecorate(argument, render(argument, format)));
+}
+
+// Renders the help page with only the entries matching the query, as in "mytool --help output".
+// Returns if anything matched
+pub(crate) fn render_matching(
+    argument: &Argument,
+    format: OutputFormat,
+    query: &str,
+) -> (String, bool) {
+    let mut model = HelpModel::new(argument);
+    let matched = model.retain_matching(query);
+    (render_model(argument, model, format), matched)
+}
+
+pub(crate) fn print_matching(argument: &Argument, format: OutputFormat, query: &str) {
+    let (help, matched) = render_matching(argument, format, query);
+    if !matched {
+        output::warn(format_args!(
+            "Nothing on the help page matches \"{}\"",
+            sanitize(query)
+        ));
+    };
+    output::print(&undecorate(argument, help));
+}
+
+// Removes the decorations from a rendered help page, unless they're kept
+pub(crate) fn undecorate(argument: &Argument, help: String) -> String {
+    if argument.decorations.enabled() {
+        help
+    } else {
+        strip_ansi(&help)
+    }
+}
+
+fn render_full(model: &HelpModel) -> String {
+    let mut help_string = String::new();
+    let usage = model.usage(|arity| match arity {
+        Arity::Exactly(1) => String::new(),
+        Arity::Exactly(nargs) => format!("*{}", nargs),
+        Arity::Infinite => "*∞".to_string(),
+    });
+    let mut pos_args_help = String::new();
+    for positional in model.positionals.iter() {
+        let placeholder = positional.placeholder;
+        let help = &positional.help;
+        match positional.arity {
+            _ if model.notation == ArityNotation::Brackets => {
+                let word = model.positional_word(placeholder, positional.arity, |_| String::new());
+                // the help texts start at the same column as the ones of the options
+                let tabs_needed = 3usize.saturating_sub((4 + word.len()) / 8).max(1);
+                pos_args_help.push_str(&hanging(
+                    &format!("\n    {word}{:\t<tabs_needed$}", ""),
+                    help,
+                ));
+            }
+            Arity::Exactly(1) => {
+                pos_args_help.push_str(&hanging(&format!("\n    {placeholder}\t\t\t"), help));
+            }
+            Arity::Exactly(nargs) => {
+                let tabs_needed = 3 - (nargs.to_string().len() as f32 / 8.0).ceil() as usize;
+                pos_args_help.push_str(&hanging(
+                    &format!("\n    {placeholder}*{nargs}{:\t<tabs_needed$}", ""),
+                    help,
+                ));
+            }
+            Arity::Infinite => {
+                pos_args_help.push_str(&hanging(&format!("\n    {placeholder}*∞\t\t\t"), help));
+            }
+        };
+    }
+
+    help_string.push_str(
+        format!(
+            "Usage: {}\n{}\n\nPositional Arguments:{}\n\nOptions:",
+            usage, model.description, pos_args_help
+        )
+        .as_str(),
+    );
+
+    for option in model.options.iter() {
+        let long = option.long_name().unwrap_or_default();
+        let tabs_needed = match option.arity {
+            Arity::Exactly(0) => 2,
+            Arity::Exactly(nargs) => 2 - (nargs.to_string().len() as f32 / 8.0).ceil() as usize,
+            Arity::Infinite => 1,
+        };
+        help_string.push_str(&hanging(
+            &format!(
+                "\n    {}{}\t{}{}{}{:\t<tabs_needed$}",
+                if option.short.is_some() { "-" } else { "" },
+                option.short.unwrap_or(' '),
+                if long.is_empty() { "" } else { "--" },
+                long,
+                match option.arity {
+                    _ if long.is_empty() => "".to_string(),
+                    Arity::Exactly(0) | Arity::Exactly(1) => "".to_string(),
+                    Arity::Exactly(nargs) => format!("*{}", nargs),
+                    Arity::Infinite => "*∞".to_string(),
+                },
+                "",
+            ),
+            &model.option_help(option),
+        ));
+    }
+
+    if !model.subcommands.is_empty() {
+        help_string.push_str("\n\nSubcommands:");
+        for (name, description) in model.subcommands.iter() {
+            help_string.push_str(format!("\n    {}\t\t\t{}", name, description).as_str());
+        }
+        if model.help_dispatch {
+            help_string.push_str(
+                format!(
+                    "\n    {}\t\t\tPrint the help of a subcommand",
+                    subcommand::HELP
+                )
+                .as_str(),
+            );
+        };
+    };
+
+    if !model.examples.is_empty() {
+        help_string.push_str("\n\nExamples:");
+        for (description, command) in model.examples.iter() {
+            help_string.push_str(format!("\n    {}", description).as_str());
+            for line in command.lines() {
+                help_string.push_str(format!("\n        {}", line).as_str());
+            }
+        }
+    };
+
+    if model.exit_status_section {
+        help_string.push_str("\n\nExit Statuses:");
+        model.exit_statuses.iter().for_each(|entry| {
+            help_string.push_str(
+                format!(
+                    "\n    {}\t{}",
+                    entry.codes(model.exit_status_format),
+                    entry.help
+                )
+                .as_str(),
+            )
+        });
+        model
+            .exit_status_categories
+            .iter()
+            .for_each(|(title, entries)| {
+                help_string.push_str(format!("\n    {}:", title).as_str());
+                entries.iter().for_each(|entry| {
+                    help_string.push_str(
+                        format!(
+                            "\n        {}\t{}",
+                            entry.codes(model.exit_status_format),
+                            entry.help
+                        )
+                        .as_str(),
+                    )
+                });
+            });
+    };
+
+    for (title, text) in model.sections.iter() {
+        help_string.push_str(format!("\n\n{}:", title).as_str());
+        for line in text.lines() {
+            help_string.push('\n');
+            if !line.is_empty() {
+                help_string.push_str(format!("    {}", line).as_str());
+            };
+        }
+    }
+
+    help_string.push_str(format!("\n\n{}\n{}", model.epilog, model.credits).as_str());
+
+    help_string
+}
+
+fn render_compact(model: &HelpModel) -> String {
+    let amount = |arity: Arity| match arity {
+        Arity::Exactly(0) | Arity::Exactly(1) => String::new(),
+        Arity::Exactly(nargs) => format!("*{}", nargs),
+        Arity::Infinite => "...".to_string(),
+    };
+
+    let mut lines: Vec<String> = vec![format!("Usage: {}", model.usage(amount))];
+    for positional in model.positionals.iter() {
+        lines.push(hanging(
+            &format!(
+                "  {}  ",
+                model.positional_word(positional.placeholder, positional.arity, amount)
+            ),
+            &positional.help,
+        ));
+    }
+    for option in model.options.iter() {
+        let names = match (option.short, option.long_name()) {
+            (Some(short), Some(long)) => format!("-{}, --{}", short, long),
+            (Some(short), None) => format!("-{}", short),
+            (None, Some(long)) => format!("--{}", long),
+            (None, None) => String::new(),
+        };
+        lines.push(hanging(
+            &format!("  {}{}  ", names, amount(option.arity)),
+            &model.option_help(option),
+        ));
+    }
+    for (name, description) in model.subcommands.iter() {
+        lines.push(format!("  {}  {}", name, description));
+    }
+    lines
+        .iter()
+        .map(|line| line.trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+// The amount of values an argument takes, written the same way as for add_option and add_arg
+fn values(arity: Arity) -> String {
+    match arity {
+        Arity::Exactly(nargs) => nargs.to_string(),
+        Arity::Infinite => "+".to_string(),
+    }
+}
+
+fn render_markdown(model: &HelpModel) -> String {
+    // pipes would end a table cell, and paragraphs are separated using line breaks
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', "<br>");
+
+    let mut markdown = format!("# {}\n", model.name);
+    if !model.description.is_empty() {
+        markdown.push_str(format!("\n{}\n", model.description).as_str());
+    };
+    markdown.push_str(
+        format!(
+            "\n## Usage\n\n```text\n{}\n```\n",
+            model.usage(|arity| match arity {
+                Arity::Exactly(1) => String::new(),
+                Arity::Exactly(nargs) => format!("*{}", nargs),
+                Arity::Infinite => "...".to_string(),
+            })
+        )
+        .as_str(),
+    );
+
+    if !model.positionals.is_empty() {
+        markdown.push_str(
+            "\n## Positional Arguments\n\n| Argument | Values | Description |\n|---|---|---|\n",
+        );
+        for positional in model.positionals.iter() {
+            markdown.push_str(
+                format!(
+                    "| `{}` | {} | {} |\n",
+                    positional.placeholder,
+                    values(positional.arity),
+                    cell(&positional.help)
+                )
+                .as_str(),
+            );
+        }
+    };
+
+    markdown.push_str("\n## Options\n\n| Option | Values | Description |\n|---|---|---|\n");
+    for option in model.options.iter() {
+        let names: Vec<String> = option
+            .short
+            .map(|short| format!("`-{}`", short))
+            .into_iter()
+            .chain(option.long_name().map(|long| format!("`--{}`", long)))
+            .collect();
+        markdown.push_str(
+            format!(
+                "| {} | {} | {} |\n",
+                names.join(", "),
+                values(option.arity),
+                cell(&model.option_help(option))
+            )
+            .as_str(),
+        );
+    }
+
+    if !model.subcommands.is_empty() {
+        markdown.push_str("\n## Subcommands\n\n| Subcommand | Description |\n|---|---|\n");
+        for (name, description) in model.subcommands.iter() {
+            markdown.push_str(format!("| `{}` | {} |\n", name, cell(description)).as_str());
+        }
+        if model.help_dispatch {
+            markdown.push_str(
+                format!(
+                    "| `{}` | Print the help of a subcommand |\n",
+                    subcommand::HELP
+                )
+                .as_str(),
+            );
+        };
+    };
+
+    if !model.examples.is_empty() {
+        markdown.push_str("\n## Examples\n");
+        for (description, command) in model.examples.iter() {
+            markdown.push_str(
+                format!(
+                    "\n{}\n\n```{}\n{}\n```\n",
+                    description,
+                    model.example_shell.code_language(),
+                    command
+                )
+                .as_str(),
+            );
+        }
+    };
+
+    if model.exit_status_section {
+        markdown.push_str("\n## Exit Statuses\n");
+    };
+    if model.exit_status_section && !model.exit_statuses.is_empty() {
+        markdown.push_str("\n| Status | Description |\n|---|---|\n");
+        for entry in model.exit_statuses.iter() {
+            markdown.push_str(
+                format!(
+                    "| {} | {} |\n",
+                    entry.codes(model.exit_status_format),
+                    cell(&entry.help)
+                )
+                .as_str(),
+            );
+        }
+    };
+    for (title, entries) in model
+        .exit_status_categories
+        .iter()
+        .filter(|_| model.exit_status_section)
+    {
+        markdown
+            .push_str(format!("\n### {}\n\n| Status | Description |\n|---|---|\n", title).as_str());
+        for entry in entries.iter() {
+            markdown.push_str(
+                format!(
+                    "| {} | {} |\n",
+                    entry.codes(model.exit_status_format),
+                    cell(&entry.help)
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    for (title, text) in model.sections.iter() {
+        markdown.push_str(format!("\n## {}\n\n{}\n", title, text).as_str());
+    }
+
+    for text in [model.epilog.as_ref(), model.credits] {
+        if !text.is_empty() {
+            markdown.push_str(format!("\n{}\n", text).as_str());
+        };
+    }
+    markdown
+}
+
+fn render_json(model: &HelpModel) -> String {
+    let list = |items: Vec<String>| format!("[{}]", items.join(", "));
+    let strings = |items: &[String]| list(items.iter().map(|item| json_string(item)).collect());
+    let values = |arity: Arity| match arity {
+        Arity::Exactly(nargs) => nargs.to_string(),
+        Arity::Infinite => json_string("+"),
+    };
+
+    let positionals = model
+        .positionals
+        .iter()
+        .map(|positional| {
+            format!(
+                "{{\"placeholder\": {}, \"values\": {}, \"help\": {}}}",
+                json_string(positional.placeholder),
+                values(positional.arity),
+                json_string(&positional.help)
+            )
+        })
+        .collect();
+    let options = model
+        .options
+        .iter()
+        .map(|option| {
+            format!(
+                "{{\"short\": {}, \"long\": {}, \"values\": {}, \"help\": {}, \"required\": {}, \
+                 \"default\": {}, \"choices\": {}, \"until\": {}, \"env\": {}, \"required_if\": {}, \
+                 \"negatable\": {}}}",
+                option
+                    .short
+                    .map_or("null".to_string(), |short| json_string(&short.to_string())),
+                option.long.map_or("null".to_string(), json_string),
+                values(option.arity),
+                json_string(&option.help),
+                option.required,
+                option.default.map_or("null".to_string(), strings),
+                option
+                    .choices
+                    .map_or("null".to_string(), |choices| strings(&choices.resolve())),
+                option.until.map_or("null".to_string(), json_string),
+                option.env.map_or("null".to_string(), json_string),
+                list(
+                    option
+                        .required_if
+                        .iter()
+                        .map(|(name, value)| {
+                            format!(
+                                "{{\"name\": {}, \"value\": {}}}",
+                                json_string(name),
+                                json_string(value)
+                            )
+                        })
+                        .collect()
+                ),
+                option.negatable
+            )
+        })
+        .collect();
+    let subcommands = model
+        .subcommands
+        .iter()
+        .map(|(name, description)| {
+            format!(
+                "{{\"name\": {}, \"description\": {}}}",
+                json_string(name),
+                json_string(description)
+            )
+        })
+        .collect();
+    let examples = model
+        .examples
+        .iter()
+        .map(|(description, command)| {
+            format!(
+                "{{\"description\": {}, \"command\": {}}}",
+                json_string(description),
+                json_string(command)
+            )
+        })
+        .collect();
+    // ranges also have the last status of the range
+    let exit_status_list = |entries: &[ExitStatusEntry]| {
+        list(
+            entries
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{{\"status\": {}, {}\"description\": {}}}",
+                        entry.first,
+                        match entry.first == entry.last {
+                            true => String::new(),
+                            false => format!("\"last\": {}, ", entry.last),
+                        },
+                        json_string(&entry.help)
+                    )
+                })
+                .collect(),
+        )
+    };
+    let exit_status_categories = model
+        .exit_status_categories
+        .iter()
+        .map(|(title, entries)| {
+            format!(
+                "{{\"title\": {}, \"exit_statuses\": {}}}",
+                json_string(title),
+                exit_status_list(entries)
+            )
+        })
+        .collect();
+
+    let sections = model
+        .sections
+        .iter()
+        .map(|(title, text)| {
+            format!(
+                "{{\"title\": {}, \"text\": {}}}",
+                json_string(title),
+                json_string(text)
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"name\": {}, \"description\": {}, \"usage\": {}, \"positionals\": {}, \"options\": {}, \
+         \"subcommands\": {}, \"examples\": {}, \"exit_statuses\": {}, \"exit_status_categories\": {}, \
+         \"sections\": {}, \"epilog\": {}, \"credits\": {}}}",
+        json_string(model.name),
+        json_string(&model.description),
+        json_string(&model.usage(|arity| match arity {
+            Arity::Exactly(1) => String::new(),
+            Arity::Exactly(nargs) => format!("*{}", nargs),
+            Arity::Infinite => "...".to_string(),
+        })),
+        list(positionals),
+        list(options),
+        list(subcommands),
+        list(examples),
+        exit_status_list(&model.exit_statuses),
+        list(exit_status_categories),
+        list(sections),
+        json_string(&model.epilog),
+        json_string(model.credits)
+    )
+}
+
+// Renders the man page of an Argument in roff. The subcommands are either described on the same
+// page (their effective Arguments are given, nested ones right after their parent), or have
+// pages of their own, which are listed under SEE ALSO
+pub(crate) fn render_man(
+    argument: &Argument,
+    subcommands: &[Argument],
+    see_also: &[String],
+) -> String {
+    let model = HelpModel::new(argument);
+    let page = man::page_name(model.name);
+    let mut man = format!(
+        ".TH \"{}\" \"1\"\n.SH NAME\n{}",
+        roff(&page.to_uppercase()),
+        roff(&page)
+    );
+    // the first line of the description is the summary of the page
+    if let Some(summary) = model
+        .description
+        .lines()
+        .find(|line| !line.trim().is_empty())
+    {
+        man.push_str(format!(" \\- {}", roff(summary.trim())).as_str());
+    };
+    man.push_str("\n.SH SYNOPSIS\n");
+    man.push_str(&man_synopsis(&model));
+    if !model.description.is_empty() || !model.epilog.is_empty() {
+        man.push_str(".SH DESCRIPTION\n");
+        man.push_str(&man_paragraphs(&model.description));
+        man.push_str(&man_paragraphs(&model.epilog));
+    };
+    man.push_str(&man_body(&model, ".SH"));
+
+    for subcommand in subcommands.iter() {
+        let model = HelpModel::new(subcommand);
+        man.push_str(format!(".SH \"{}\"\n", roff(&model.name.to_uppercase())).as_str());
+        man.push_str(&man_synopsis(&model));
+        man.push_str(&man_paragraphs(&model.description));
+        man.push_str(&man_paragraphs(&model.epilog));
+        man.push_str(&man_body(&model, ".SS"));
+    }
+
+    if !see_also.is_empty() {
+        let pages: Vec<String> = see_also
+            .iter()
+            .map(|page| format!("\\fB{}\\fR(1)", roff(page)))
+            .collect();
+        man.push_str(format!(".SH \"SEE ALSO\"\n{}\n", pages.join(", ")).as_str());
+    };
+    if !model.credits.is_empty() {
+        man.push_str(".SH AUTHORS\n");
+        man.push_str(&man_paragraphs(model.credits));
+    };
+    man
+}
+
+fn man_synopsis(model: &HelpModel) -> String {
+    let usage = model.usage(|arity| match arity {
+        Arity::Exactly(1) => String::new(),
+        Arity::Exactly(nargs) => format!("*{}", nargs),
+        Arity::Infinite => "...".to_string(),
+    });
+    format!(
+        ".PP\n\\fB{}\\fR {}\n",
+        roff(model.name),
+        roff(usage[model.name.len()..].trim_start())
+    )
+}
+
+// The sections listing the arguments, subcommands, examples and exit statuses, and the sections
+// added with add_section, under the given heading (.SH on a page of its own, .SS for a
+// subcommand described on the page of the program)
+fn man_body(model: &HelpModel, heading: &str) -> String {
+    let mut man = String::new();
+    let entry = |term: String, help: &str| format!(".TP\n{}\n{}", term, man_help(help));
+
+    if !model.positionals.is_empty() {
+        man.push_str(format!("{} ARGUMENTS\n", heading).as_str());
+        for positional in model.positionals.iter() {
+            let term = match model.notation {
+                ArityNotation::Stars => format!(
+                    "\\fB{}\\fR{}",
+                    roff(positional.placeholder),
+                    match positional.arity {
+                        Arity::Exactly(0) | Arity::Exactly(1) => String::new(),
+                        Arity::Exactly(nargs) => format!("*{}", nargs),
+                        Arity::Infinite => "...".to_string(),
+                    }
+                ),
+                ArityNotation::Brackets => roff(&model.positional_word(
+                    positional.placeholder,
+                    positional.arity,
+                    |_| String::new(),
+                )),
+            };
+            man.push_str(&entry(term, &positional.help));
+        }
+    };
+
+    man.push_str(format!("{} OPTIONS\n", heading).as_str());
+    for option in model.options.iter() {
+        let names: Vec<String> = option
+            .short
+            .map(|short| format!("\\fB\\-{}\\fR", roff(&short.to_string())))
+            .into_iter()
+            .chain(
+                option
+                    .long_name()
+                    .map(|long| format!("\\fB\\-\\-{}\\fR", roff(&long))),
+            )
+            .collect();
+        let term = format!("{}{}", names.join(", "), man_values(option.arity));
+        man.push_str(&entry(term, &model.option_help(option)));
+    }
+
+    if !model.subcommands.is_empty() {
+        man.push_str(format!("{} SUBCOMMANDS\n", heading).as_str());
+        for (name, description) in model.subcommands.iter() {
+            man.push_str(&entry(format!("\\fB{}\\fR", roff(name)), description));
+        }
+        if model.help_dispatch {
+            man.push_str(&entry(
+                format!("\\fB{}\\fR", subcommand::HELP),
+                "Print the help of a subcommand",
+            ));
+        };
+    };
+
+    if !model.examples.is_empty() {
+        man.push_str(format!("{} EXAMPLES\n", heading).as_str());
+        for (description, command) in model.examples.iter() {
+            man.push_str(&man_paragraphs(description));
+            man.push_str(format!(".PP\n.RS 4\n.nf\n{}\n.fi\n.RE\n", roff(command)).as_str());
+        }
+    };
+
+    if model.exit_status_section
+        && !(model.exit_statuses.is_empty() && model.exit_status_categories.is_empty())
+    {
+        man.push_str(format!("{} \"EXIT STATUS\"\n", heading).as_str());
+        let categories = model
+            .exit_status_categories
+            .iter()
+            .flat_map(|(_, entries)| entries.iter());
+        for status in model.exit_statuses.iter().chain(categories) {
+            let codes = status.codes(model.exit_status_format);
+            man.push_str(&entry(roff(&codes), &status.help));
+        }
+    };
+
+    for (title, text) in model.sections.iter() {
+        man.push_str(
+            format!(
+                "{} \"{}\"\n.nf\n{}\n.fi\n",
+                heading,
+                roff(&title.to_uppercase()),
+                roff(text)
+            )
+            .as_str(),
+        );
+    }
+    man
+}
+
+fn man_values(arity: Arity) -> String {
+    match arity {
+        Arity::Exactly(nargs) => " \\fIVALUE\\fR".repeat(nargs),
+        Arity::Infinite => " \\fIVALUE\\fR...".to_string(),
+    }
+}
+
+// A help text after .TP, where later paragraphs are separated using .IP so they stay indented
+fn man_help(help: &str) -> String {
+    let paragraphs: Vec<String> = help
+        .split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .map(|paragraph| roff(paragraph.trim()))
+        .collect();
+    match paragraphs.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", paragraphs.join("\n.IP\n")),
+    }
+}
+
+// A text whose paragraphs are separated by blank lines, with a .PP before every paragraph
+fn man_paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .map(|paragraph| format!(".PP\n{}\n", roff(paragraph.trim())))
+        .collect()
+}
+
+// Escapes text for roff: backslashes, dashes (which would be printed as hyphens), and dots and
+// apostrophes at the start of a line, which would make it a request
+fn roff(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace('\\', "\\e").replace('-', "\\-");
+            match line.starts_with('.') || line.starts_with('\'') {
+                true => format!("\\&{}", line),
+                false => line,
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
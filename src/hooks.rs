@@ -0,0 +1,78 @@
+// Hooks run before and after parsing, and plugins bundling them
+
+use crate::{callback::Callback, Argument, Kind, ParsedArgs, Source};
+
+pub(crate) type PreParseHook = Callback<dyn Fn(Vec<String>) -> Vec<String> + Send + Sync>;
+pub(crate) type PostParseHook = Callback<dyn Fn(&mut ParsedArgs) + Send + Sync>;
+pub(crate) type AuditHook = Callback<dyn Fn(&str, &[String], &Source) + Send + Sync>;
+
+/// A reusable extension of an Argument
+///
+/// A plugin gets to modify the Argument it's added to with `Argument::add_plugin`, e.g. by
+/// adding its own options together with the hooks handling them. This makes it possible to ship
+/// extensions to taap as crates of their own.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Opt, Plugin};
+///
+/// // Makes "-q" a shorthand for "--verbosity 0"
+/// struct Quiet;
+///
+/// impl Plugin for Quiet {
+///     fn register(&self, argument: &mut Argument) {
+///         argument.add(Opt::new('q', "quiet").help("The same as --verbosity 0"));
+///         argument.add_pre_parse_hook(|arguments| {
+///             arguments
+///                 .into_iter()
+///                 .flat_map(|argument| match argument.as_str() {
+///                     "-q" | "--quiet" => vec!["--verbosity".to_string(), "0".to_string()],
+///                     _ => vec![argument],
+///                 })
+///                 .collect()
+///         });
+///     }
+/// }
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(Opt::new('-', "verbosity").takes(1));
+/// arguments.add_plugin(Quiet);
+/// // ...
+/// }
+/// ```
+pub trait Plugin {
+    /// Adds the options and hooks of the plugin to the Argument
+    fn register(&self, argument: &mut Argument);
+}
+
+// Runs the pre-parse hooks in the order they were added
+pub(crate) fn run_pre_parse(hooks: &[PreParseHook], tokens: Vec<String>) -> Vec<String> {
+    hooks.iter().fold(tokens, |tokens, hook| (hook.0)(tokens))
+}
+
+// Runs the post-parse hooks in the order they were added
+pub(crate) fn run_post_parse(hooks: &[PostParseHook], parsed: &mut ParsedArgs) {
+    for hook in hooks.iter() {
+        (hook.0)(parsed);
+    }
+}
+
+// Runs the audit hooks for every argument that was used, in the order the arguments were added.
+// Positional arguments without values weren't used
+pub(crate) fn run_audit(hooks: &[AuditHook], parsed: &ParsedArgs) {
+    if hooks.is_empty() {
+        return;
+    };
+    for (name, kind, used, values) in parsed.entries() {
+        let Some(source) = parsed.source(name) else {
+            continue;
+        };
+        if !used || (kind == Kind::Positional && values.is_empty()) {
+            continue;
+        };
+        for hook in hooks.iter() {
+            (hook.0)(name, values, source);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Defaults that refer to the values of other arguments, like "{INPUT}.out", see
+// OptionSpec::default_template. They're filled in after the command line is parsed, since they
+// may refer to positional arguments or to other options with such a default
+
+use crate::{normalize, parse::ParseResult, parsed::Source, Argument};
+use std::collections::BTreeMap;
+
+// The parts of a template, as literal text (false) or as the name of an argument (true). "{{" and
+// "}}" are literal braces, and an unclosed brace is kept as it is
+pub(crate) fn parts(template: &str) -> Vec<(bool, String)> {
+    let mut parts: Vec<(bool, String)> = vec![];
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        let brace = char::from(rest.as_bytes()[at]);
+        text.push_str(&rest[..at]);
+        rest = &rest[at + 1..];
+        match rest.find('}') {
+            _ if rest.starts_with(brace) => {
+                text.push(brace);
+                rest = &rest[1..];
+            }
+            Some(end) if brace == '{' => {
+                parts.push((false, std::mem::take(&mut text)));
+                parts.push((true, rest[..end].to_string()));
+                rest = &rest[end + 1..];
+            }
+            _ => text.push(brace),
+        }
+    }
+    text.push_str(rest);
+    parts.push((false, text));
+    parts
+}
+
+// Fills in the defaults of the unused options that have a template as their default
+pub(crate) fn resolve(
+    argument: &Argument,
+    return_map: &mut ParseResult,
+    sources: &mut BTreeMap<String, Source>,
+) {
+    let mut resolved: BTreeMap<usize, Option<String>> = BTreeMap::new();
+    for (id, option) in argument.options.iter().enumerate() {
+        if !option.default_template || return_map.get(&option.key()).unwrap().0 {
+            continue;
+        };
+        if let Some(value) = default_of(argument, return_map, id, &mut resolved, &mut vec![]) {
+            return_map.insert(
+                option.key(),
+                (true, normalize::apply(&option.normalizers, vec![value])),
+            );
+            sources.insert(option.key(), Source::Default);
+        };
+    }
+}
+
+// The default of an option, or None if an argument it refers to has no value. The stack holds the
+// options whose defaults are being filled in, to find defaults referring to each other
+fn default_of(
+    argument: &Argument,
+    return_map: &ParseResult,
+    id: usize,
+    resolved: &mut BTreeMap<usize, Option<String>>,
+    stack: &mut Vec<usize>,
+) -> Option<String> {
+    if let Some(value) = resolved.get(&id) {
+        return value.clone();
+    };
+    let option = &argument.options[id];
+    if let Some(at) = stack.iter().position(|other| *other == id) {
+        let names: Vec<String> = stack[at..]
+            .iter()
+            .chain([&id])
+            .map(|other| argument.options[*other].display_name())
+            .collect();
+        panic!(
+            "Error! The defaults of {} refer to each other",
+            names.join(" -> ")
+        );
+    };
+    stack.push(id);
+    let template = &option.default.as_ref().unwrap()[0];
+    let mut filled = Some(String::new());
+    for (reference, part) in parts(template) {
+        if !reference {
+            filled = filled.map(|filled| filled + &part);
+            continue;
+        };
+        let key = argument.argument_key(&part).unwrap_or_else(|| {
+            panic!(
+                "Error! The default of \"{}\" refers to \"{}\", but there's no such argument",
+                option.display_name(),
+                part
+            )
+        });
+        let (used, values) = return_map.get(&key).unwrap();
+        let referenced = match argument.option_id(&part) {
+            Some(other) if !used && argument.options[other].default_template => {
+                default_of(argument, return_map, other, resolved, stack)
+            }
+            Some(_) if !used => None,
+            _ if !used && values.is_empty() => None,
+            _ => Some(values.join(" ")),
+        };
+        filled = filled.zip(referenced).map(|(filled, part)| filled + &part);
+    }
+    stack.pop();
+    resolved.insert(id, filled.clone());
+    filled
+}
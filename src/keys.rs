@@ -0,0 +1,103 @@
+// Constants for the keys of the parse result, generated by the argument_keys! macro
+
+use crate::Argument;
+
+/// Generates a module of constants for the names the arguments are stored under in the parse
+/// result
+///
+/// Every constant is the key of an argument, i.e. the short name of an option, its long name if
+/// it doesn't have a short name, or the placeholder of a positional argument. Looking arguments
+/// up by constant, like `parsed.get(keys::PORT)`, turns a typo into a compile error. The module
+/// also gets `ALL`, every key in the order they were declared, and a `check` function that
+/// panics if a constant isn't the key of an argument of the given Argument. Call it from a test,
+/// next to `Argument::debug_assert`, so the constants can't drift from the definition.
+///
+/// Code Example:
+/// ```no_run
+/// taap::argument_keys! {
+///     pub mod keys {
+///         VERBOSE = "v",
+///         PORT = "port",
+///         FILE = "FILE",
+///     }
+/// }
+///
+/// fn arguments() -> taap::Argument {
+///     let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+///     arguments.add(taap::Opt::new('v', "verbose").help("Tell what's going on"));
+///     arguments.add(taap::Opt::new('-', "port").takes(1).help("The port to listen on"));
+///     arguments.add(taap::Pos::new("FILE").help("The file to serve"));
+///     arguments
+/// }
+///
+/// #[test]
+/// fn valid_keys() {
+///     keys::check(&arguments());
+/// }
+///
+/// fn main() {
+/// let parsed_arguments = arguments().parse_args(None);
+/// if parsed_arguments.is_present(keys::VERBOSE) {
+///     println!("Serving {}", parsed_arguments.get(keys::FILE).unwrap().1[0]);
+/// };
+/// }
+/// ```
+#[macro_export]
+macro_rules! argument_keys {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident {
+            $(
+                $(#[$key_meta:meta])*
+                $key:ident = $name:literal
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(dead_code)]
+        $vis mod $module {
+            $(
+                $(#[$key_meta])*
+                pub const $key: &str = $name;
+            )*
+
+            /// Every key, in the order they were declared
+            pub const ALL: &[&str] = &[$($name),*];
+
+            /// Panics if a key isn't the key of an argument of the Argument
+            pub fn check(argument: &$crate::Argument) {
+                $crate::check_argument_keys(
+                    argument,
+                    ::core::stringify!($module),
+                    &[$((::core::stringify!($key), $name)),*],
+                );
+            }
+        }
+    };
+}
+
+// Used by argument_keys!, which has to be able to call it from other crates
+#[doc(hidden)]
+pub fn check_argument_keys(argument: &Argument, module: &str, keys: &[(&str, &str)]) {
+    let problems: Vec<String> = keys
+        .iter()
+        .filter_map(|(constant, name)| match argument.argument_key(name) {
+            Some(key) if key == *name => None,
+            Some(key) => Some(format!(
+                "{}::{} is \"{}\", but the argument is stored as \"{}\"",
+                module, constant, name, key
+            )),
+            None => Some(format!(
+                "{}::{} is \"{}\", which isn't the name of an argument",
+                module, constant, name
+            )),
+        })
+        .collect();
+    if !problems.is_empty() {
+        panic!(
+            "Error! The keys of \"{}\" don't match its arguments:\n{}",
+            argument.name,
+            problems.join("\n")
+        );
+    };
+}
@@ -2,43 +2,138 @@
 #![doc(html_playground_url = "https://play.rust-lang.org/")]
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
+    ffi::OsString,
     fmt::{self, Display},
-    process::exit,
+    ops::Range,
+    process::ExitCode,
     str,
+    sync::Arc,
 };
 
+mod aliases;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod callback;
+mod choices;
+mod command;
+#[cfg(feature = "chrono")]
+pub mod datetime;
+#[cfg(feature = "serde")]
+mod definition;
+mod docs;
+mod error;
+mod examples;
+mod exclusive;
+mod exit_status;
+mod expand;
+mod external;
+mod flags;
+mod help;
+mod hooks;
+mod interpolate;
+mod keys;
+mod limits;
+mod locale;
+mod man;
+mod matcher;
+pub mod normalize;
+mod os;
+mod output;
+mod parse;
+mod parsed;
+mod profiles;
+mod reexec;
+mod renamed;
+mod sanitize;
+mod shell;
+mod show_config;
+mod sink;
+mod snapshot;
+mod spec;
+mod stats;
+mod subcommand;
+mod suggest;
+mod text;
+mod trace;
+mod validate;
+mod value_source;
+mod version;
+
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncValueSource, BoxFuture};
+pub use command::FromArgs;
+pub use error::{Error, ErrorKind};
+pub use examples::ExampleShell;
+pub use exit_status::ExitStatusCategory;
+pub use flags::Flags;
+pub use help::{
+    ArityNotation, Decorations, ExitStatusFormat, ExitStatusSection, HelpAnnotations, HelpStyle,
+    TextDirection,
+};
+pub use hooks::Plugin;
+#[doc(hidden)]
+pub use keys::check_argument_keys;
+pub use limits::Limits;
+pub use man::ManLayout;
+pub use matcher::{Matches, Outcome};
+pub use os::display_os;
+pub use output::{BrokenPipe, OutputFormat};
+pub use parsed::{Kind, ParsedArgs, Presence, Source, Span};
+pub use reexec::Reexec;
+pub use shell::QuoteDialect;
+pub use sink::ValueSink;
+#[doc(hidden)]
+pub use snapshot::check_help_snapshot;
+pub use spec::{
+    ArgSpec, Arity, Duplicates, MergeStrategy, Opt, OptionHandle, OptionSet, OptionSpec, Pos,
+    PositionalSpec,
+};
+pub use stats::ParseStats;
+pub use suggest::suggest;
+pub use value_source::ValueSource;
+
+use callback::Callback;
+use hooks::{AuditHook, PostParseHook, PreParseHook};
+use output::Stop;
+use renamed::Renamed;
+use spec::FileExpansion;
+use text::Text;
+
 #[cfg(test)]
 mod tests {
-    use crate::Argument;
-    use std::collections::BTreeMap;
-    
+    use crate::{
+        output::Stop, Argument, Arity, Error, MergeStrategy, Opt, OptionSet, OptionSpec,
+        ParsedArgs, Pos, Text,
+    };
+    use std::collections::{BTreeMap, HashMap};
+
+    // The error parsing stopped with
+    fn failure(result: Result<ParsedArgs, Stop>) -> Error {
+        match result {
+            Err(Stop::Failed(err)) => err,
+            _ => panic!("Parsing didn't fail"),
+        }
+    }
+
     // test of "new" function
     #[test]
     fn new() {
-        let mut args: (
-            BTreeMap<String, (String, isize)>,
-            BTreeMap<char, (String, isize, String)>,
-        ) = (BTreeMap::new(), BTreeMap::new());
-
         let exit_statuses: BTreeMap<u16, String> = BTreeMap::new();
 
-        args.1.insert(
-            'h',
-            (
-                "help".to_string(),
-                0,
-                "Use this to print this help message".to_string(),
-            ),
-        );
+        let options =
+            vec![OptionSpec::new('h', "help").help("Use this to print this help message")];
 
         let expected_test_obj = Argument {
             name: String::from("Hello"),
-            description: String::from("World"),
+            description: Text::from("World"),
             exit_statuses,
-            epilog: String::from("From"),
+            epilog: Text::from("From"),
             credits: String::from("TAAP"),
-            args,
+            options,
+            shorts: HashMap::from([('h', 0)]),
+            longs: HashMap::from([("help".to_string(), 0)]),
+            ..Default::default()
         };
 
         let result_test_obj = Argument::new("Hello", "World", "From", "TAAP");
@@ -50,11 +145,11 @@ mod tests {
     #[test]
     fn exit_status() {
         let mut expected_test_obj: BTreeMap<String, (bool, Vec<String>)> = BTreeMap::new();
-        
+
         expected_test_obj.insert("h".to_string(), (false, vec![]));
 
         let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
-        
+
         argument_test_obj.add_exit_status(0, "Everything went well!");
         let result_test_obj = argument_test_obj.parse_args(None);
 
@@ -65,23 +160,23 @@ mod tests {
     #[test]
     fn options() {
         let mut expected_test_obj: BTreeMap<String, (bool, Vec<String>)> = BTreeMap::new();
-        
+
         expected_test_obj.insert("f".to_string(), (false, vec![]));
         expected_test_obj.insert("h".to_string(), (false, vec![]));
 
         let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
-        
+
         argument_test_obj.add_option('f', "foo", "0", None);
         let result_test_obj = argument_test_obj.parse_args(None);
 
         assert_eq!(expected_test_obj, result_test_obj);
     }
-    
+
     // test of "add_arg" function
     #[test]
     fn args() {
         let mut expected_test_obj: BTreeMap<String, (bool, Vec<String>)> = BTreeMap::new();
-        
+
         expected_test_obj.insert("GOOD BYE".to_string(), (true, vec![]));
         expected_test_obj.insert("HELLO WORLD".to_string(), (true, vec![]));
         expected_test_obj.insert("h".to_string(), (false, vec![]));
@@ -94,560 +189,6527 @@ mod tests {
 
         assert_eq!(expected_test_obj, result_test_obj);
     }
-}
 
-/// The struct that actually contains all the info, and acts like the container for all commands
-/// needed
-///
-/// The Argument struct contains all info, e.g. the name, the description, all arguments added,
-/// etc.
-/// The Argument implementations are also what we use to create and modify our args!
-///
-/// An example of the Argument struct in use:
-/// ```no_run
-/// fn main() {
-///     let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits and year");
-///     // Add some arguments and options
-///     // ...
-///     let parsed_args = arguments.parse_args(None);
-///     // Do something with the parsed args
-///     // ...
-/// }
-/// ```
-#[derive(Clone, PartialEq, Eq, Debug, Default)]
-pub struct Argument {
-    name: String,
-    description: String,
-    exit_statuses: BTreeMap<u16, String>,
-    epilog: String,
-    credits: String,
-    args: (
-        BTreeMap<String, (String, isize)>,
-        BTreeMap<char, (String, isize, String)>,
-    ),
-}
+    // test of "parse_args" function, with options mixed in between positional arguments
+    #[test]
+    fn mixed_order() {
+        let mut expected_test_obj: BTreeMap<String, (bool, Vec<String>)> = BTreeMap::new();
 
-impl Display for Argument {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{{name: {}, description: {}, epilog: {}, credits: {}}}",
-            self.name, self.description, self.epilog, self.credits
+        expected_test_obj.insert("f".to_string(), (true, vec!["foo".to_string()]));
+        expected_test_obj.insert("h".to_string(), (false, vec![]));
+        expected_test_obj.insert("verbose".to_string(), (true, vec![]));
+        expected_test_obj.insert("ZED".to_string(), (true, vec!["zed".to_string()]));
+        expected_test_obj.insert(
+            "ALPHA".to_string(),
+            (true, vec!["alpha".to_string(), "-1".to_string()]),
+        );
+        expected_test_obj.insert(
+            "REST".to_string(),
+            (true, vec!["a".to_string(), "-b".to_string()]),
+        );
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.add_option('f', "foo", "1", None);
+        argument_test_obj.add_option('-', "verbose", "0", None);
+        argument_test_obj.add_arg("ZED", "1", None);
+        argument_test_obj.add_arg("ALPHA", "2", None);
+        argument_test_obj.add_arg("REST", "+", None);
+        let result_test_obj = argument_test_obj.parse_args(Some(
+            [
+                "--verbose",
+                "zed",
+                "-f",
+                "foo",
+                "alpha",
+                "-1",
+                "--",
+                "a",
+                "-b",
+            ]
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect(),
+        ));
+
+        assert_eq!(expected_test_obj, result_test_obj);
+    }
+
+    // test of "add" function, using the builders
+    #[test]
+    fn builders() {
+        let mut expected_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        expected_test_obj.add_option('f', "foo", "2", Some("Some help!"));
+        expected_test_obj.add_option('-', "bar", "+", None);
+        expected_test_obj.add_arg("BAZ", "1", Some("More help!"));
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.add(Opt::new('f', "foo").takes(2).help("Some help!"));
+        argument_test_obj.add(Opt::new(' ', "bar").infinite());
+        argument_test_obj.add(Pos::new("BAZ").help("More help!"));
+
+        assert_eq!(expected_test_obj, argument_test_obj);
+
+        argument_test_obj.add(Opt::new('r', "").required());
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["baz".to_string(), "-r".to_string()]));
+
+        assert_eq!(result_test_obj.get("r"), Some(&(true, vec![])));
+    }
+
+    // test of the closures producing help texts
+    #[test]
+    fn lazy_help() {
+        use crate::OutputFormat;
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.set_epilog_with(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            "Lazy epilog".to_string()
+        });
+        argument_test_obj.add(Opt::new('f', "foo").help_with(|| "Lazy help".to_string()));
+        argument_test_obj.parse_args(Some(vec!["-f".to_string()]));
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+
+        assert!(help.contains("Lazy epilog"));
+        assert!(help.contains("Lazy help"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    // test of "add_subcommand" function
+    #[test]
+    fn subcommands() {
+        use crate::OutputFormat;
+
+        let mut argument_test_obj = Argument::new("mytool", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose").global());
+        argument_test_obj.add_option('q', "-", "0", None);
+        let remote = argument_test_obj.add_subcommand("remote", "Manage remotes");
+        remote
+            .add_subcommand("add", "Add a remote")
+            .add_arg("NAME", "1", None);
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-q".to_string(),
+            "remote".to_string(),
+            "add".to_string(),
+            "origin".to_string(),
+            "-v".to_string(),
+        ]));
+
+        assert_eq!(result_test_obj.get("q"), Some(&(true, vec![])));
+        assert_eq!(result_test_obj.get("v"), Some(&(true, vec![])));
+        let (name, remote_result) = result_test_obj.subcommand().unwrap();
+        assert_eq!(name, "remote");
+        assert_eq!(remote_result.get("v"), Some(&(true, vec![])));
+        assert_eq!(remote_result.get("q"), None);
+        let (name, add_result) = remote_result.subcommand().unwrap();
+        assert_eq!(name, "add");
+        assert_eq!(
+            add_result.get("NAME"),
+            Some(&(true, vec!["origin".to_string()]))
+        );
+        assert_eq!(add_result.get("v"), Some(&(true, vec![])));
+
+        let mut expected_test_obj = Argument::new("mytool remote add", "Add a remote", "", "TAAP");
+        expected_test_obj.add_arg("NAME", "1", None);
+        expected_test_obj.add(Opt::new('v', "verbose").global());
+        let add = crate::subcommand::effective(
+            &crate::subcommand::effective(&argument_test_obj, "remote").unwrap(),
+            "add",
+        )
+        .unwrap();
+        assert_eq!(
+            add.render_help(OutputFormat::Plain),
+            expected_test_obj.render_help(OutputFormat::Plain)
+        );
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\n    remote\t\t\tManage remotes"));
+    }
+
+    // test of "subcommands" macro
+    #[test]
+    fn subcommands_macro() {
+        use crate::{FromArgs, ParsedArgs};
+
+        #[derive(Debug, PartialEq)]
+        struct AddArgs {
+            name: String,
+        }
+
+        impl FromArgs for AddArgs {
+            fn from_args(parsed: &ParsedArgs) -> Self {
+                AddArgs {
+                    name: parsed.get("NAME").unwrap().1[0].to_owned(),
+                }
+            }
+        }
+
+        crate::subcommands! {
+            #[derive(Debug, PartialEq)]
+            #[allow(clippy::large_enum_variant)]
+            enum Command {
+                Add("add") => AddArgs,
+                List("list") => ParsedArgs,
+            }
+        }
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj
+            .add_subcommand("add", "Add")
+            .add_arg("NAME", "1", None);
+        argument_test_obj.add_subcommand("list", "List");
+
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["add".to_string(), "foo".to_string()]));
+        assert_eq!(
+            Command::from_parsed(&result_test_obj),
+            Some(Command::Add(AddArgs {
+                name: "foo".to_string()
+            }))
+        );
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![]));
+        assert_eq!(Command::from_parsed(&result_test_obj), None);
+        assert_eq!(Command::NAMES, &["add", "list"]);
+    }
+
+    // test of "add_option_set" function
+    #[test]
+    fn option_set() {
+        let connection = OptionSet::new()
+            .option(Opt::new('H', "host").takes(1))
+            .option(Opt::new('-', "tls"));
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj
+            .add_subcommand("pull", "Pull")
+            .add_option_set(&connection);
+        argument_test_obj
+            .add_subcommand("push", "Push")
+            .add_option_set(&connection);
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "push".to_string(),
+            "--tls".to_string(),
+            "-H".to_string(),
+            "example.com".to_string(),
+        ]));
+        let (_, push_result) = result_test_obj.subcommand().unwrap();
+
+        assert_eq!(
+            push_result.get("H"),
+            Some(&(true, vec!["example.com".to_string()]))
+        );
+        assert_eq!(push_result.get("tls"), Some(&(true, vec![])));
+    }
+
+    // test of "add_pre_parse_hook" and "add_post_parse_hook" functions
+    #[test]
+    fn hooks() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('v', "verbose", "0", None);
+        argument_test_obj.add_option('d', "debug", "0", None);
+        argument_test_obj.add_pre_parse_hook(|arguments| {
+            arguments
+                .into_iter()
+                .filter(|argument| argument != "--ignored")
+                .collect()
+        });
+        argument_test_obj.add_pre_parse_hook(|mut arguments| {
+            arguments.push("-d".to_string());
+            arguments
+        });
+        argument_test_obj.add_post_parse_hook(|parsed| {
+            if parsed.get("d").unwrap().0 {
+                parsed.get_mut("v").unwrap().0 = true;
+            }
+        });
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["--ignored".to_string()]));
+
+        assert_eq!(result_test_obj.get("v"), Some(&(true, vec![])));
+        assert_eq!(result_test_obj.get("d"), Some(&(true, vec![])));
+    }
+
+    // test of "set_profiles_file" function
+    #[test]
+    fn profiles() {
+        use crate::Source;
+
+        let profiles_path = std::env::temp_dir().join("taap-profiles-test.txt");
+        std::fs::write(
+            &profiles_path,
+            "# test profiles\n[base]\n--host example.com -p 80\n\n[prod]\n--profile base\n-p 443\n",
+        )
+        .unwrap();
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('-', "host", "1", None);
+        argument_test_obj.add_option('p', "port", "1", None);
+        argument_test_obj.add_option('v', "-", "0", None);
+        argument_test_obj.set_profiles_file(&profiles_path.to_string_lossy());
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "--profile".to_string(),
+            "prod".to_string(),
+            "-p".to_string(),
+            "8080".to_string(),
+            "-v".to_string(),
+        ]));
+
+        assert_eq!(
+            result_test_obj.get("host"),
+            Some(&(true, vec!["example.com".to_string()]))
+        );
+        assert_eq!(
+            result_test_obj.get("p"),
+            Some(&(true, vec!["8080".to_string()]))
+        );
+        assert_eq!(
+            result_test_obj.source("host"),
+            Some(&Source::Profile("base".to_string()))
+        );
+        assert_eq!(result_test_obj.source("p"), Some(&Source::CommandLine));
+        assert_eq!(result_test_obj.source("v"), Some(&Source::CommandLine));
+
+        // a profile given after a subcommand is only expanded once, by the command owning the file
+        argument_test_obj
+            .add_subcommand("run", "Run")
+            .add_option('p', "port", "1", None);
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "run".to_string(),
+            "--profile".to_string(),
+            "base".to_string(),
+        ]));
+        assert_eq!(result_test_obj.get("p").unwrap().1, ["80"]);
+        let (_, run_result) = result_test_obj.subcommand().unwrap();
+        assert!(!run_result.get("p").unwrap().0);
+
+        // the value can be attached, and quotes keep spaces in a value of a profile
+        std::fs::write(&profiles_path, "[base]\n--host 'example com' -p 80\n").unwrap();
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('-', "host", "1", None);
+        argument_test_obj.add_option('p', "port", "1", None);
+        argument_test_obj.add_option('m', "message", "1", None);
+        argument_test_obj.add_option('t', "timeout", "1", None);
+        argument_test_obj.add(crate::Pos::new("COMMAND").command());
+        argument_test_obj.set_terminator(";");
+        argument_test_obj.set_profiles_file(&profiles_path.to_string_lossy());
+        let tokens = |list: &[&str]| list.iter().map(|token| token.to_string()).collect();
+        let result_test_obj = argument_test_obj.parse_args(Some(tokens(&["--profile=base"])));
+        assert_eq!(result_test_obj.get("host").unwrap().1, ["example com"]);
+        assert_eq!(result_test_obj.get("p").unwrap().1, ["80"]);
+
+        // a --profile that isn't an option of this command doesn't load anything
+        for list in [
+            &["-t", "5", "ls", "--profile", "base"][..],
+            &["-m", "--profile", "ls"],
+            &["ls", ";", "--profile", "base"],
+        ] {
+            let result_test_obj = argument_test_obj.parse_args(Some(tokens(list)));
+            assert!(!result_test_obj.get("p").unwrap().0, "{:?}", list);
+        }
+
+        std::fs::write(&profiles_path, "[unclosed]\n--host 'example\n").unwrap();
+        let err = argument_test_obj
+            .try_parse_args(Some(tokens(&["--profile", "unclosed"])))
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            format!(
+                "Profile \"unclosed\" in \"{}\" has a quote that isn't closed",
+                profiles_path.display()
+            )
+        );
+    }
+
+    // test of "set_aliases_file" function
+    #[test]
+    fn aliases() {
+        let aliases_path = std::env::temp_dir().join("taap-aliases-test.txt");
+        std::fs::write(
+            &aliases_path,
+            "# test aliases\nst = status -s\nsst = st -b\nstatus = nothing\n\
+             wip = status -m 'work in progress'\n",
         )
+        .unwrap();
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        let status = argument_test_obj.add_subcommand("status", "Status");
+        status.add_option('s', "short", "0", None);
+        status.add_option('b', "branch", "0", None);
+        status.add_option('m', "message", "1", None);
+        argument_test_obj.set_aliases_file(&aliases_path.to_string_lossy());
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["sst".to_string()]));
+        let (name, status_result) = result_test_obj.subcommand().unwrap();
+
+        assert_eq!(name, "status");
+        assert_eq!(status_result.get("s"), Some(&(true, vec![])));
+        assert_eq!(status_result.get("b"), Some(&(true, vec![])));
+
+        // the expansion is quoted like in a shell
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["wip".to_string()]));
+        let (_, status_result) = result_test_obj.subcommand().unwrap();
+        assert_eq!(status_result.get("m").unwrap().1, ["work in progress"]);
+    }
+
+    // test of "to_env_exports" function
+    #[test]
+    fn env_exports() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('v', "verbose", "0", None);
+        argument_test_obj.add_option('-', "dry-run", "0", None);
+        argument_test_obj.add_option('o', "output", "1", None);
+        argument_test_obj.add_arg("FILES", "+", None);
+        argument_test_obj.add_subcommand("run", "Run");
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-v".to_string(),
+            "-o".to_string(),
+            "it's here".to_string(),
+            "a.txt".to_string(),
+            "my file.txt".to_string(),
+            "run".to_string(),
+        ]));
+
+        // the values of FILES are shell words, so the space in "my file.txt" isn't lost
+        assert_eq!(
+            result_test_obj.to_env_exports("myapp"),
+            "export MYAPP_V=1\n\
+             export MYAPP_O='it'\\''s here'\n\
+             export MYAPP_FILES='a.txt '\\''my file.txt'\\'''\n\
+             export MYAPP_SUBCOMMAND=run\n"
+        );
+
+        // two arguments can't be exported under the same name
+        argument_test_obj.add_option('-', "dry_run", "0", None);
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["a.txt".to_string()]));
+        assert!(std::panic::catch_unwind(|| result_test_obj.to_env_exports("myapp")).is_err());
+    }
+
+    // test of "raw_args" function
+    #[test]
+    fn raw_args() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('f', "foo", "0", None);
+        argument_test_obj.add_pre_parse_hook(|_| vec![]);
+
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["-f".to_string(), "bar".to_string()]));
+
+        assert_eq!(result_test_obj.get("f"), Some(&(false, vec![])));
+        assert_eq!(result_test_obj.raw_args(), &["Hello", "-f", "bar"]);
+    }
+
+    // test of "Reexec" struct
+    #[test]
+    fn reexec() {
+        use crate::Reexec;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('d', "daemonize", "0", None);
+        argument_test_obj.add_option('o', "-", "2", None);
+        argument_test_obj.add_option('-', "include", "+", None);
+        argument_test_obj.add_arg("FILES", "+", None);
+
+        let mut result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-d".to_string(),
+            "a.txt".to_string(),
+            "--include".to_string(),
+            "x".to_string(),
+            "\\-y".to_string(),
+            "-o".to_string(),
+            "1".to_string(),
+            "2".to_string(),
+            "--".to_string(),
+            "-b.txt".to_string(),
+        ]));
+
+        let from_raw = Reexec::from_raw(&result_test_obj)
+            .remove("-d")
+            .arg("--verbose");
+        assert_eq!(
+            from_raw.args(),
+            &[
+                "a.txt",
+                "--include",
+                "x",
+                "\\-y",
+                "-o",
+                "1",
+                "2",
+                "--",
+                "-b.txt",
+                "--verbose"
+            ]
+        );
+
+        result_test_obj.get_mut("d").unwrap().0 = false;
+        let from_parsed = Reexec::from_parsed(&argument_test_obj, &result_test_obj);
+        assert_eq!(
+            from_parsed.args(),
+            &["-o", "1", "2", "--include", "x", "\\-y", "--", "a.txt"]
+        );
+    }
+
+    // test of "set_help_style" function
+    #[test]
+    fn compact_help() {
+        use crate::{HelpStyle, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_arg("FILES", "+", Some("The files"));
+        argument_test_obj.add_option('f', "foo", "2", Some("Two values"));
+        argument_test_obj.add_option('-', "bar", "0", None);
+        argument_test_obj.add_subcommand("run", "Run it");
+        argument_test_obj.set_help_style(HelpStyle::Compact);
+
+        assert_eq!(
+            argument_test_obj.render_help(OutputFormat::Plain),
+            "Usage: Hello [FILES]... SUBCOMMAND [OPTIONS]\n\
+             \x20 [FILES]...  The files\n\
+             \x20 -h, --help  Use this to print this help message\n\
+             \x20 -f, --foo*2  Two values\n\
+             \x20 --bar\n\
+             \x20 run  Run it"
+        );
+    }
+
+    // test of "render_help" function
+    #[test]
+    fn output_formats() {
+        use crate::OutputFormat;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add_arg("FILE", "1", Some("A \"file\""));
+        argument_test_obj.add(Opt::new('-', "level").takes(1).help("a | b").required());
+        argument_test_obj.set_help_annotations(crate::HelpAnnotations {
+            required: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            argument_test_obj.render_help(OutputFormat::Markdown),
+            "# Hello\n\nWorld\n\n## Usage\n\n```text\nHello <FILE> [OPTIONS]\n```\n\n\
+             ## Positional Arguments\n\n| Argument | Values | Description |\n|---|---|---|\n\
+             | `FILE` | 1 | A \"file\" |\n\n\
+             ## Options\n\n| Option | Values | Description |\n|---|---|---|\n\
+             | `-h`, `--help` | 0 | Use this to print this help message |\n\
+             | `--level` | 1 | a \\| b (required) |\n\nTAAP\n"
+        );
+        assert_eq!(
+            argument_test_obj.render_help(OutputFormat::Json),
+            "{\"name\": \"Hello\", \"description\": \"World\", \"usage\": \"Hello <FILE> [OPTIONS]\", \
+             \"positionals\": [{\"placeholder\": \"FILE\", \"values\": 1, \"help\": \"A \\\"file\\\"\"}], \
+             \"options\": [{\"short\": \"h\", \"long\": \"help\", \"values\": 0, \
+             \"help\": \"Use this to print this help message\", \"required\": false, \"default\": null, \"choices\": null, \"until\": null, \"env\": null, \"required_if\": [], \"negatable\": false}, \
+             {\"short\": null, \"long\": \"level\", \"values\": 1, \"help\": \"a | b\", \"required\": true, \
+             \"default\": null, \"choices\": null, \"until\": null, \"env\": null, \"required_if\": [], \"negatable\": false}], \
+             \"subcommands\": [], \"examples\": [], \"exit_statuses\": [], \"exit_status_categories\": [], \"sections\": [], \"epilog\": \"\", \"credits\": \"TAAP\"}"
+        );
+
+        // the value of the option can be attached using =
+        argument_test_obj.add_format_option();
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "--format=md".to_string(),
+            "--level".to_string(),
+            "1".to_string(),
+            "a.txt".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("format"),
+            Some(&(true, vec!["md".to_string()]))
+        );
+    }
+
+    // test of "default" function
+    #[test]
+    fn defaults() {
+        use crate::{HelpAnnotations, OutputFormat, Source};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add(
+            Opt::new('p', "port")
+                .takes(1)
+                .default("8080")
+                .help("The port"),
+        );
+        argument_test_obj.add(Opt::new('-', "size").takes(2).default_values(["80", "24"]));
+        argument_test_obj.add(Opt::new('u', "user").takes(1).required());
+        argument_test_obj.set_help_annotations(HelpAnnotations {
+            defaults: true,
+            required: true,
+        });
+
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\n    -p\t--port\tThe port [default: 8080]\n     \t--size*2\t[default: 80 24]\n    -u\t--user\t(required)"));
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-u".to_string(),
+            "root".to_string(),
+            "--size".to_string(),
+            "1".to_string(),
+            "2".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("p"),
+            Some(&(true, vec!["8080".to_string()]))
+        );
+        assert_eq!(result_test_obj.source("p"), Some(&Source::Default));
+        assert_eq!(
+            result_test_obj.get("size"),
+            Some(&(true, vec!["1".to_string(), "2".to_string()]))
+        );
+        assert_eq!(result_test_obj.source("size"), Some(&Source::CommandLine));
+    }
+
+    // test of "option_choices" function
+    #[test]
+    fn choices() {
+        use std::sync::{Arc, Mutex};
+
+        let devices = Arc::new(Mutex::new(vec!["eth0".to_string()]));
+        let current = Arc::clone(&devices);
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add(Opt::new('m', "mode").takes(1).choices(["fast", "slow"]));
+        argument_test_obj.add(
+            Opt::new('d', "device")
+                .takes(1)
+                .choices_with(move || current.lock().unwrap().clone()),
+        );
+
+        assert_eq!(
+            argument_test_obj.option_choices("mode"),
+            Some(vec!["fast".to_string(), "slow".to_string()])
+        );
+        assert_eq!(argument_test_obj.option_choices("h"), None);
+
+        // the choices are computed again every time
+        devices.lock().unwrap().push("wlan0".to_string());
+        assert_eq!(
+            argument_test_obj.option_choices("d"),
+            Some(vec!["eth0".to_string(), "wlan0".to_string()])
+        );
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-d".to_string(),
+            "wlan0".to_string(),
+            "--mode=slow".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("d"),
+            Some(&(true, vec!["wlan0".to_string()]))
+        );
+    }
+
+    // test of "normalize" function
+    #[test]
+    fn normalize() {
+        use crate::{normalize, Pos};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add(
+            Opt::new('u', "user")
+                .takes(1)
+                .normalize(normalize::trim)
+                .normalize(normalize::lowercase),
+        );
+        argument_test_obj.add(
+            Opt::new('-', "shell")
+                .takes(1)
+                .default("/BIN/SH")
+                .normalize(normalize::lowercase),
+        );
+        argument_test_obj.add(
+            Pos::new("NAMES")
+                .infinite()
+                .normalize(|name| name.replace('_', "-")),
+        );
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-u".to_string(),
+            " Root ".to_string(),
+            "a_b".to_string(),
+            "c".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("u"),
+            Some(&(true, vec!["root".to_string()]))
+        );
+        assert_eq!(
+            result_test_obj.get("shell"),
+            Some(&(true, vec!["/bin/sh".to_string()]))
+        );
+        assert_eq!(
+            result_test_obj.get("NAMES"),
+            Some(&(true, vec!["a-b".to_string(), "c".to_string()]))
+        );
+    }
+
+    // test of "entries" function
+    #[test]
+    fn entries() {
+        use crate::Kind;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add_option('z', "zip", "0", None);
+        argument_test_obj.add_option('a', "add", "1", None);
+        argument_test_obj.add_arg("FILE", "1", None);
+        argument_test_obj.add_subcommand("run", "Run it");
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-a".to_string(),
+            "x".to_string(),
+            "f.txt".to_string(),
+            "run".to_string(),
+        ]));
+        let files = vec!["f.txt".to_string()];
+        let added = vec!["x".to_string()];
+        assert_eq!(
+            result_test_obj.entries().collect::<Vec<_>>(),
+            vec![
+                ("h", Kind::Flag, false, &[] as &[String]),
+                ("z", Kind::Flag, false, &[]),
+                ("a", Kind::Option, true, added.as_slice()),
+                ("FILE", Kind::Positional, true, files.as_slice()),
+                ("run", Kind::Subcommand, true, &[]),
+            ]
+        );
+    }
+
+    // test of "sink" function
+    #[test]
+    fn sink() {
+        use crate::Pos;
+        use std::{
+            collections::BTreeSet,
+            sync::{Arc, Mutex},
+        };
+
+        let files = Arc::new(Mutex::new(BTreeSet::new()));
+        let tags = Arc::new(Mutex::new(vec![]));
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add(Opt::new('t', "tags").infinite().sink(Arc::clone(&tags)));
+        argument_test_obj.add(Pos::new("FILES").infinite().sink(Arc::clone(&files)));
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "b.txt".to_string(),
+            "a.txt".to_string(),
+            "b.txt".to_string(),
+            "-t".to_string(),
+            "x".to_string(),
+        ]));
+        assert_eq!(result_test_obj.get("FILES"), Some(&(true, vec![])));
+        assert_eq!(result_test_obj.get("t"), Some(&(true, vec![])));
+        assert_eq!(
+            *files.lock().unwrap(),
+            BTreeSet::from(["a.txt".to_string(), "b.txt".to_string()])
+        );
+        assert_eq!(*tags.lock().unwrap(), vec!["x".to_string()]);
+    }
+
+    // test of "trailing" function
+    #[test]
+    fn trailing() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose").global());
+        argument_test_obj.add(Pos::new("ARGS").arity(Arity::Infinite));
+        argument_test_obj.set_terminator(";");
+        argument_test_obj
+            .add_subcommand("run", "Run a program")
+            .add(Pos::new("COMMAND").arity(Arity::Infinite));
+
+        let mut parse = |tokens: &[&str]| {
+            argument_test_obj
+                .parse_args(Some(tokens.iter().map(|token| token.to_string()).collect()))
+        };
+        let result_test_obj = parse(&["run", "x", "--", "cargo", "-v", ";", "ls"]);
+        assert_eq!(result_test_obj.trailing(), None);
+        let (_, run) = result_test_obj.subcommand().unwrap();
+        assert_eq!(
+            run.trailing(),
+            Some(&["cargo".to_string(), "-v".to_string()][..])
+        );
+        assert_eq!(result_test_obj.innermost().trailing(), run.trailing());
+        assert_eq!(run.get("COMMAND").unwrap().1, vec!["x", "cargo", "-v"]);
+        assert_eq!(run.rest(), &["ls".to_string()]);
+        assert!(!run.is_present("v"));
+
+        // before the name of a subcommand, it's just another positional argument
+        let result_test_obj = parse(&["--", "run", "x"]);
+        assert!(result_test_obj.subcommand().is_none());
+        assert_eq!(
+            result_test_obj.trailing(),
+            Some(&["run".to_string(), "x".to_string()][..])
+        );
+        assert_eq!(result_test_obj.get("ARGS").unwrap().1, vec!["run", "x"]);
+
+        let result_test_obj = parse(&["run", "--"]);
+        assert_eq!(result_test_obj.innermost().trailing(), Some(&[][..]));
+    }
+
+    // test of "set_terminator" function
+    #[test]
+    fn terminator() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add_option('t', "tags", "+", None);
+        argument_test_obj.add_arg("FILES", "+", None);
+        argument_test_obj.set_terminator(";");
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-t".to_string(),
+            "a".to_string(),
+            ";".to_string(),
+            "echo".to_string(),
+            "-t".to_string(),
+            ";".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("t"),
+            Some(&(true, vec!["a".to_string()]))
+        );
+        assert_eq!(
+            result_test_obj.rest(),
+            &["echo".to_string(), "-t".to_string(), ";".to_string()]
+        );
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "--".to_string(),
+            "-x".to_string(),
+            ";".to_string(),
+            "ls".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("FILES"),
+            Some(&(true, vec!["-x".to_string()]))
+        );
+        assert_eq!(result_test_obj.rest(), &["ls".to_string()]);
+        assert_eq!(
+            crate::Reexec::from_parsed(&argument_test_obj, &result_test_obj).args(),
+            &["--", "-x", ";", "ls"]
+        );
+    }
+
+    // test of "command" function
+    #[test]
+    fn command_tail() {
+        use crate::Pos;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add_option('t', "timeout", "1", None);
+        argument_test_obj.add(Pos::new("HOST"));
+        argument_test_obj.add(Pos::new("COMMAND").command());
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-t".to_string(),
+            "5".to_string(),
+            "server".to_string(),
+            "ls".to_string(),
+            "-la".to_string(),
+            "\\x".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.command("COMMAND"),
+            Some(("ls", &["-la".to_string(), "\\x".to_string()][..]))
+        );
+        assert_eq!(
+            result_test_obj.get("HOST"),
+            Some(&(true, vec!["server".to_string()]))
+        );
+        assert_eq!(
+            crate::Reexec::from_parsed(&argument_test_obj, &result_test_obj).args(),
+            &["--timeout", "5", "--", "server", "ls", "-la", "\\x"]
+        );
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "--".to_string(),
+            "-server".to_string(),
+            "-t".to_string(),
+        ]));
+        assert_eq!(result_test_obj.command("COMMAND"), Some(("-t", &[][..])));
+        assert!(!result_test_obj.get("t").unwrap().0);
+    }
+
+    // test of "set_number_option" function
+    #[test]
+    fn number_option() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add_option('1', "", "0", None);
+        argument_test_obj.add_option('2', "", "0", None);
+        argument_test_obj.add_arg("OFFSET", "1", None);
+
+        // short options that are digits
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["-12".to_string(), "-3".to_string()]));
+        assert!(result_test_obj.get("1").unwrap().0);
+        assert!(result_test_obj.get("2").unwrap().0);
+        assert_eq!(
+            result_test_obj.get("OFFSET"),
+            Some(&(true, vec!["-3".to_string()]))
+        );
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add_option('n', "lines", "1", None);
+        argument_test_obj.add_option('q', "quiet", "0", None);
+        argument_test_obj.set_number_option("lines");
+
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["-q".to_string(), "-25".to_string()]));
+        assert_eq!(
+            result_test_obj.get("n"),
+            Some(&(true, vec!["25".to_string()]))
+        );
+        assert!(result_test_obj.get("q").unwrap().0);
+    }
+
+    // test of "require_equals" function
+    #[test]
+    fn require_equals() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        argument_test_obj.add(Opt::new('c', "color").takes(1).require_equals());
+        argument_test_obj.add_arg("FILES", "+", None);
+
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["--color".to_string(), "auto".to_string()]));
+        assert_eq!(result_test_obj.get("c"), Some(&(true, vec![])));
+        assert_eq!(
+            result_test_obj.get("FILES"),
+            Some(&(true, vec!["auto".to_string()]))
+        );
+
+        let result_test_obj = argument_test_obj
+            .parse_args(Some(vec!["--color=never".to_string(), "a.txt".to_string()]));
+        assert_eq!(
+            result_test_obj.get("c"),
+            Some(&(true, vec!["never".to_string()]))
+        );
+        assert_eq!(
+            crate::Reexec::from_parsed(&argument_test_obj, &result_test_obj).args(),
+            &["--color=never", "--", "a.txt"]
+        );
+    }
+
+    // test of "set_placeholder" function
+    #[test]
+    fn placeholders() {
+        use crate::OutputFormat;
+
+        let mut argument_test_obj =
+            Argument::new("Hello", "{bin} {version}", "See {bin} {missing}", "TAAP");
+        argument_test_obj.set_placeholder("version", "1.2.3");
+        argument_test_obj.add(
+            Opt::new('p', "port")
+                .takes(1)
+                .default("8080")
+                .help("Defaults to {default}"),
+        );
+        argument_test_obj
+            .add_subcommand("run", "Run {bin}")
+            .add_arg("FILE", "1", Some("Read by {bin} {version}"));
+
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(help.contains("\nHello 1.2.3\n"));
+        assert!(help.contains("--port\tDefaults to 8080\n"));
+        assert!(help.contains("\n    run\t\t\tRun Hello\n"));
+        assert!(help.ends_with("See Hello {missing}\nTAAP"));
+
+        let run = crate::subcommand::effective(&argument_test_obj, "run").unwrap();
+        assert!(run
+            .render_help(OutputFormat::Plain)
+            .contains("<FILE>\t\tRead by Hello 1.2.3"));
+    }
+
+    // test of "update_help_section" function
+    #[test]
+    fn help_section() {
+        use crate::docs::replace_section;
+
+        let readme = "# Tool\n<!-- taap:usage:start -->\nold\n<!-- taap:usage:end -->\nMore\n";
+        assert_eq!(
+            replace_section(readme, "usage", "# Usage\n\ntext\n"),
+            Some(
+                "# Tool\n<!-- taap:usage:start -->\n# Usage\n\ntext\n<!-- taap:usage:end -->\nMore\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(replace_section(readme, "options", "text"), None);
+
+        let path = std::env::temp_dir().join("taap-help-section-test.md");
+        std::fs::write(&path, readme).unwrap();
+        let argument_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        assert!(argument_test_obj
+            .update_help_section(&path, "usage")
+            .unwrap());
+        assert!(!argument_test_obj
+            .update_help_section(&path, "usage")
+            .unwrap());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("<!-- taap:usage:start -->\n# Hello\n"));
+    }
+
+    // test of "set_decorations" function
+    #[test]
+    fn decorations() {
+        use crate::{help::undecorate, Decorations, OutputFormat};
+
+        let mut argument_test_obj =
+            Argument::new("Hello", "\u{1b}[1;31mWorld\u{1b}[0m", "", "TAAP");
+        argument_test_obj.set_decorations(Decorations::Always);
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(undecorate(&argument_test_obj, help.clone()).contains("\u{1b}[1;31mWorld"));
+
+        argument_test_obj.set_decorations(Decorations::Never);
+        assert!(undecorate(&argument_test_obj, help).contains("\nWorld\n"));
+    }
+
+    // test of "add_exit_status_range" and "add_exit_status_category" functions
+    #[test]
+    fn exit_status_ranges() {
+        use crate::{ExitStatusCategory, OutputFormat};
+
+        let mut arg = Argument::new("Hello", "World", "From", "TAAP");
+        arg.add_exit_status(0, "Fine");
+        arg.add_exit_status_range(64, 78, "Usage and system errors");
+        arg.add_exit_status_category(
+            ExitStatusCategory::new("Network")
+                .status(10, "Unreachable")
+                .range(11, 19, "Interrupted"),
+        );
+
+        let help = arg.render_help(OutputFormat::Plain);
+        assert!(help.contains(
+            "Exit Statuses:\n    0\tFine\n    64-78\tUsage and system errors\n    Network:\n        \
+             10\tUnreachable\n        11-19\tInterrupted"
+        ));
+        let markdown = arg.render_help(OutputFormat::Markdown);
+        assert!(markdown.contains("| 64-78 | Usage and system errors |"));
+        assert!(markdown
+            .contains("### Network\n\n| Status | Description |\n|---|---|\n| 10 | Unreachable |"));
+        let json = arg.render_help(OutputFormat::Json);
+        assert!(json.contains(
+            "{\"status\": 64, \"last\": 78, \"description\": \"Usage and system errors\"}"
+        ));
+        assert!(json.contains("\"exit_status_categories\": [{\"title\": \"Network\", \"exit_statuses\": [{\"status\": 10, "));
+    }
+
+    // test of "set_exit_status_section" function
+    #[test]
+    fn exit_status_section() {
+        use crate::{ExitStatusSection, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_exit_status(0, "Fine");
+        for format in [OutputFormat::Plain, OutputFormat::Markdown] {
+            assert!(!argument_test_obj
+                .render_help(format)
+                .contains("Exit Statuses"));
+        }
+        argument_test_obj.set_exit_status_section(ExitStatusSection::Always);
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("Exit Statuses:\n    0\tFine"));
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_exit_status(2, "Not fine");
+        for format in [OutputFormat::Plain, OutputFormat::Markdown] {
+            assert!(argument_test_obj
+                .render_help(format)
+                .contains("Exit Statuses"));
+        }
+        argument_test_obj.set_exit_status_section(ExitStatusSection::Never);
+        for format in [OutputFormat::Plain, OutputFormat::Markdown] {
+            assert!(!argument_test_obj
+                .render_help(format)
+                .contains("Exit Statuses"));
+        }
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Json)
+            .contains("\"status\": 2"));
+    }
+
+    // test of "merge" function
+    #[test]
+    fn merge() {
+        let mut telemetry = Argument::new("telemetry", "", "", "");
+        telemetry.add(Opt::new('-', "no-telemetry").help("Don't send usage statistics"));
+        telemetry.add_exit_status(9, "Telemetry failed");
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Pos::new("FILE"));
+        argument_test_obj.merge(&telemetry);
+        assert_eq!(argument_test_obj.options.len(), 2);
+        assert_eq!(
+            argument_test_obj.exit_statuses.get(&9).unwrap(),
+            "Telemetry failed"
+        );
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "a.txt".to_string(),
+            "--no-telemetry".to_string(),
+        ]));
+        assert!(result_test_obj.get("no-telemetry").unwrap().0);
+
+        let mut conflicting = Argument::new("other", "", "", "");
+        conflicting.add(Opt::new('n', "no-telemetry").help("Something else"));
+        let merged = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            argument_test_obj.merge(&conflicting)
+        }));
+        assert!(merged.is_err());
+    }
+
+    // test of "merge_prefixed" function
+    #[test]
+    fn merge_prefixed() {
+        let mut telemetry = Argument::new("telemetry", "", "", "");
+        telemetry.add(Opt::new('e', "endpoint").takes(1));
+        telemetry.add(Opt::new('q', "-"));
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('e', "endpoint").takes(1));
+        argument_test_obj.merge_prefixed(&telemetry, "telemetry");
+        assert_eq!(argument_test_obj.options.len(), 4);
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-e".to_string(),
+            "a".to_string(),
+            "--telemetry-endpoint".to_string(),
+            "b".to_string(),
+            "--telemetry-q".to_string(),
+        ]));
+        assert_eq!(result_test_obj.get("e").unwrap().1, vec!["a"]);
+        assert_eq!(
+            result_test_obj.get("telemetry-endpoint").unwrap().1,
+            vec!["b"]
+        );
+        assert!(result_test_obj.get("telemetry-q").unwrap().0);
+    }
+
+    // test of "try_parse_args" function, with the kinds of errors
+    #[test]
+    fn error_kinds() {
+        use crate::{ErrorKind, Limits};
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('p', "port").takes(1));
+        argument_test_obj.add(Opt::new('u', "user").takes(1).required_if("p", "22"));
+        argument_test_obj.add(Opt::new('q', "quiet").duplicates(crate::Duplicates::Error));
+        argument_test_obj.add(Pos::new("FILE"));
+        let kind = |argument_test_obj: &mut Argument, list: &[&str]| {
+            argument_test_obj
+                .try_parse_args(Some(list.iter().map(|arg| arg.to_string()).collect()))
+                .unwrap_err()
+                .kind()
+        };
+
+        assert_eq!(
+            kind(&mut argument_test_obj, &["file", "-p"]),
+            ErrorKind::MissingOptionValue
+        );
+        assert_eq!(
+            kind(&mut argument_test_obj, &[]),
+            ErrorKind::MissingPositional
+        );
+        assert_eq!(
+            kind(&mut argument_test_obj, &["-p", "22", "file"]),
+            ErrorKind::MissingRequired
+        );
+        assert_eq!(
+            kind(&mut argument_test_obj, &["--quiet=yes", "file"]),
+            ErrorKind::UnexpectedValue
+        );
+        assert_eq!(
+            kind(&mut argument_test_obj, &["-q", "-q", "file"]),
+            ErrorKind::DuplicateOption
+        );
+        argument_test_obj.set_limits(Limits {
+            max_tokens: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(
+            kind(&mut argument_test_obj, &["-p", "1", "file"]),
+            ErrorKind::LimitExceeded
+        );
+    }
+
+    // test of "string_args" function, with arguments that aren't valid UTF-8
+    #[cfg(unix)]
+    #[test]
+    fn invalid_encoding() {
+        use crate::ErrorKind;
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+        let arguments = |last: Vec<u8>| {
+            vec![
+                OsString::from("hello"),
+                OsString::from("--name"),
+                OsString::from_vec(last),
+            ]
+        };
+        assert_eq!(
+            crate::parse::string_args(arguments(b"caf\xc3\xa9".to_vec())).unwrap(),
+            ["hello", "--name", "café"]
+        );
+        let err = crate::parse::string_args(arguments(b"caf\xe9".to_vec())).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding(2));
+        assert_eq!(
+            err.message(),
+            "Argument 2 isn't valid UTF-8: \"caf\u{FFFD}\""
+        );
+    }
+
+    // test of "try_parse_args" function
+    #[test]
+    fn try_parse_args() {
+        use std::error::Error;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('m', "mode").takes(1).choices(["a", "b"]));
+        argument_test_obj.add(Pos::new("FILE").infinite());
+        argument_test_obj.add(Opt::new('T', "files-from").takes(1));
+        argument_test_obj.add_file_expansion("FILE", Some("files-from"));
+
+        let result_test_obj =
+            argument_test_obj.try_parse_args(Some(vec!["-m".to_string(), "a".to_string()]));
+        assert_eq!(result_test_obj.unwrap().get("m").unwrap().1, vec!["a"]);
+
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["-m".to_string(), "c".to_string()]))
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Invalid value \"c\" for -m, expected one of: a, b"
+        );
+        assert!(err.source().is_none());
+        assert_eq!(err.kind(), crate::ErrorKind::InvalidValue);
+
+        let err = argument_test_obj
+            .try_parse_args(Some(vec![
+                "-T".to_string(),
+                "/nonexistent/taap-list".to_string(),
+            ]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Could not read file list \"/nonexistent/taap-list\""
+        );
+        assert!(err.source().unwrap().is::<std::io::Error>());
+    }
+
+    // test of "set_broken_pipe" function
+    #[test]
+    fn broken_pipe() {
+        use crate::{
+            output::{pipe_closed, set_broken_pipe, write_line},
+            BrokenPipe,
+        };
+        use std::io::{self, Write};
+
+        struct ClosedPipe;
+        impl Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        // with the default policy, a closed pipe is ignored instead of panicking
+        write_line(ClosedPipe, "Hello");
+        assert!(!pipe_closed());
+        let mut output = vec![];
+        write_line(&mut output, "Hello");
+        assert_eq!(output, b"Hello\n");
+
+        // exiting is left to the function the parser was called from, which ends with status 141
+        set_broken_pipe(BrokenPipe::Exit);
+        write_line(ClosedPipe, "Hello");
+        assert!(pipe_closed());
+        assert!(!pipe_closed());
+        set_broken_pipe(BrokenPipe::Ignore);
+    }
+
+    // test of "until" function
+    #[test]
+    fn until() {
+        use crate::OutputFormat;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('s', "set")
+                .infinite()
+                .until("end")
+                .help("Set a value"),
+        );
+        argument_test_obj.add(Opt::new('p', "pair").takes(2).until(";"));
+        argument_test_obj.add(Pos::new("FILE").infinite());
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "--set".to_string(),
+            "key".to_string(),
+            "-1".to_string(),
+            "end".to_string(),
+            "-p".to_string(),
+            "a".to_string(),
+            ";".to_string(),
+            "file".to_string(),
+        ]));
+        assert_eq!(result_test_obj.get("s").unwrap().1, vec!["key", "-1"]);
+        assert_eq!(result_test_obj.get("p").unwrap().1, vec!["a"]);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, vec!["file"]);
+
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["-s".to_string(), "key".to_string()]))
+            .unwrap_err();
+        assert_eq!(err.message(), "The values of -s have to end with \"end\"");
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("Set a value (end the values with \"end\")"));
+    }
+
+    // test of "required" function
+    #[test]
+    fn required() {
+        use crate::ErrorKind;
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('-', "host").takes(1).required());
+        argument_test_obj.add(Opt::new('-', "port").takes(1).required());
+        argument_test_obj.add(Opt::new('u', "").takes(1).required());
+        argument_test_obj.add(Opt::new('v', "verbose"));
+
+        let mut parse = |tokens: &[&str]| {
+            argument_test_obj
+                .try_parse_args(Some(tokens.iter().map(|token| token.to_string()).collect()))
+        };
+        let err = parse(&["-v"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequired);
+        assert_eq!(err.message(), "--host, --port and -u are required");
+        let err = parse(&["--port", "80"]).unwrap_err();
+        assert_eq!(err.message(), "--host and -u are required");
+        let err = parse(&["--port", "80", "-u", "me"]).unwrap_err();
+        assert_eq!(err.message(), "--host is required");
+        assert!(parse(&["--port", "80", "-u", "me", "--host", "a"]).is_ok());
+    }
+
+    // test of "required_if" function
+    #[test]
+    fn required_if() {
+        use crate::{HelpAnnotations, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Pos::new("MODE"));
+        argument_test_obj.add(Opt::new('H', "host").takes(1).required_if("MODE", "remote"));
+
+        let result_test_obj = argument_test_obj.try_parse_args(Some(vec!["local".to_string()]));
+        assert!(!result_test_obj.unwrap().get("H").unwrap().0);
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["remote".to_string()]))
+            .unwrap_err();
+        assert_eq!(err.message(), "--host is required when MODE is \"remote\"");
+        let result_test_obj = argument_test_obj.try_parse_args(Some(vec![
+            "remote".to_string(),
+            "-H".to_string(),
+            "example.com".to_string(),
+        ]));
+        assert!(result_test_obj.is_ok());
+
+        argument_test_obj.set_help_annotations(HelpAnnotations {
+            required: true,
+            ..Default::default()
+        });
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("(required if MODE is \"remote\")"));
+    }
+
+    // test of "validate" function
+    #[test]
+    fn validate() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Pos::new("FILES").infinite());
+        argument_test_obj.add(Pos::new("OUTPUT"));
+        argument_test_obj.add(Opt::new('-', "OUTPUT").required_if("MODE", "x"));
+        argument_test_obj
+            .add_subcommand("sub", "")
+            .add(Opt::new('m', "mode").takes(1).choices(["a"]).default("b"));
+        assert_eq!(
+            argument_test_obj.validate(),
+            Err(vec![
+                "--OUTPUT and OUTPUT are both stored as \"OUTPUT\"".to_string(),
+                "--OUTPUT is required depending on \"MODE\", which doesn't exist".to_string(),
+                "Hello sub: The default \"b\" of --mode isn't one of its choices".to_string(),
+            ])
+        );
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Pos::new("FILES").infinite());
+        argument_test_obj.add(Pos::new("OUTPUT"));
+        assert_eq!(
+            argument_test_obj.validate(),
+            Err(vec![
+                "OUTPUT can never get any values, since it follows the infinite FILES".to_string()
+            ])
+        );
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.debug_assert();
+    }
+
+    // test of "assert_help_snapshot" macro
+    #[test]
+    fn help_snapshot() {
+        let path = std::env::temp_dir().join("taap-help-snapshot-test.txt");
+        let mut argument_test_obj =
+            Argument::new("Hello", "\u{1b}[1mWorld\u{1b}[0m", "From", "TAAP");
+        std::fs::write(
+            &path,
+            "Usage: Hello [OPTIONS]\nWorld\n\nPositional Arguments:\n\nOptions:\n    \
+             -h\t--help\t\tUse this to print this help message\n\nFrom\nTAAP\n",
+        )
+        .unwrap();
+        crate::assert_help_snapshot!(argument_test_obj, &path);
+        // the language of the system doesn't change the snapshot
+        argument_test_obj.add_translations("xx", "description = Welt");
+        let system_language = std::env::var_os("LC_ALL");
+        std::env::set_var("LC_ALL", "xx");
+        let checked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            crate::assert_help_snapshot!(argument_test_obj, &path);
+        }));
+        match system_language {
+            Some(language) => std::env::set_var("LC_ALL", language),
+            None => std::env::remove_var("LC_ALL"),
+        };
+        assert!(checked.is_ok());
+
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        let checked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            crate::assert_help_snapshot!(argument_test_obj, &path);
+        }));
+        assert!(checked.is_err());
+    }
+
+    // test of "suggest" function
+    #[test]
+    fn suggest() {
+        use crate::suggest;
+
+        let candidates = ["status", "stash", "start"];
+        assert_eq!(suggest(candidates, "stauts"), Some("status".to_string()));
+        assert_eq!(suggest(candidates, "stsh"), Some("stash".to_string()));
+        assert_eq!(suggest(candidates, "commit"), None);
+        assert_eq!(suggest(["a", "b"], "c"), Some("a".to_string()));
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_subcommand("status", "");
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["help".to_string(), "stauts".to_string()]))
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Unknown subcommand \"stauts\", did you mean \"status\"?"
+        );
+    }
+
+    // test of "add_translations" function
+    #[test]
+    fn translations() {
+        use crate::OutputFormat;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose").help("Print more"));
+        argument_test_obj.add(Pos::new("FILE").help("The file"));
+        argument_test_obj.add_subcommand("remote", "Manage servers");
+        argument_test_obj.add_translations(
+            "de",
+            "# German\n\
+             description = Welt\n\
+             option.verbose = Mehr ausgeben\n\
+             positional.FILE = Die Datei\n\
+             subcommand.remote = Server verwalten\n\
+             remote.description = Verwaltet die Server\n",
+        );
+        argument_test_obj.set_language("de_AT.UTF-8");
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(help.contains("\nWelt\n"));
+        assert!(help.contains("Mehr ausgeben"));
+        assert!(help.contains("Die Datei"));
+        assert!(help.contains("Server verwalten"));
+        assert!(crate::subcommand::effective(&argument_test_obj, "remote")
+            .unwrap()
+            .render_help(OutputFormat::Plain)
+            .contains("Verwaltet die Server"));
+
+        argument_test_obj.set_language("fr");
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\nWorld\n"));
+    }
+
+    // test of "set_text_direction" function
+    #[test]
+    fn text_direction() {
+        use crate::{OutputFormat, TextDirection};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_translations("he", "option.help = עזרה");
+        argument_test_obj.set_language("he_IL.UTF-8");
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(help.contains(
+            "\n\u{200f}    \u{2068}-h\u{2069}\t\u{2068}--help\u{2069}\t\t\u{2068}עזרה\u{2069}\n"
+        ));
+        assert!(help.contains("\n\n\u{200f}\u{2068}From\u{2069}\n"));
+
+        argument_test_obj.set_text_direction(TextDirection::LeftToRight);
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\n    -h\t--help\t\tעזרה\n"));
+    }
+
+    // test of "short_circuit" function
+    #[test]
+    fn short_circuit() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('-', "list-formats").short_circuit());
+        argument_test_obj.add(Opt::new('o', "output").takes(1).required());
+        argument_test_obj.add(Pos::new("FILE").takes(2));
+
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["a".to_string()]))
+            .unwrap_err();
+        assert_eq!(err.message(), "--output is required");
+        let result_test_obj = argument_test_obj
+            .try_parse_args(Some(vec!["a".to_string(), "--list-formats".to_string()]))
+            .unwrap();
+        assert!(result_test_obj.get("list-formats").unwrap().0);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, vec!["a"]);
+    }
+
+    // test of "set_options_env" function
+    #[test]
+    fn options_env() {
+        use crate::{shell, Source};
+
+        assert_eq!(
+            shell::split(r#" -v --name 'a b' "c \"d\"" e\ f"#),
+            Some(vec![
+                "-v".to_string(),
+                "--name".to_string(),
+                "a b".to_string(),
+                "c \"d\"".to_string(),
+                "e f".to_string(),
+            ])
+        );
+        assert_eq!(shell::split("'a"), None);
+
+        std::env::set_var("TAAP_TEST_OPTS", "-c never -v");
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('c', "color").takes(1));
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.set_options_env("TAAP_TEST_OPTS");
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["-c".to_string(), "always".to_string()]));
+        assert_eq!(result_test_obj.get("c").unwrap().1, vec!["always"]);
+        assert_eq!(result_test_obj.source("c"), Some(&Source::CommandLine));
+        assert_eq!(
+            result_test_obj.source("v"),
+            Some(&Source::Environment("TAAP_TEST_OPTS".to_string()))
+        );
+    }
+
+    // test of "merge_strategy" function
+    #[test]
+    fn merge_strategy() {
+        std::env::set_var("TAAP_TEST_MERGE_OPTS", "-I /usr/include -L /usr/lib -D a");
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('I', "include")
+                .infinite()
+                .merge_strategy(MergeStrategy::Append),
+        );
+        argument_test_obj.add(
+            Opt::new('L', "library")
+                .infinite()
+                .merge_strategy(MergeStrategy::Prepend),
+        );
+        argument_test_obj.add(Opt::new('D', "define").infinite());
+        argument_test_obj.set_options_env("TAAP_TEST_MERGE_OPTS");
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-I".to_string(),
+            "include".to_string(),
+            "-L".to_string(),
+            "lib".to_string(),
+            "-D".to_string(),
+            "b".to_string(),
+        ]));
+        assert_eq!(
+            result_test_obj.get("I").unwrap().1,
+            vec!["/usr/include", "include"]
+        );
+        assert_eq!(result_test_obj.get("L").unwrap().1, vec!["lib", "/usr/lib"]);
+        assert_eq!(result_test_obj.get("D").unwrap().1, vec!["b"]);
+    }
+
+    // test of "add_show_config_option" function
+    #[test]
+    fn show_config() {
+        use crate::{show_config, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('p', "port").takes(1).default("80"));
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add(Pos::new("FILE"));
+        argument_test_obj.add_show_config_option();
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["a.txt".to_string()]));
+        assert_eq!(
+            show_config::render(&result_test_obj, OutputFormat::Plain),
+            "Argument\tValue\tSource\n\
+             p\t80\tdefault\n\
+             v\t\tunset\n\
+             FILE\ta.txt\tcommand line"
+        );
+        assert_eq!(
+            show_config::render(&result_test_obj, OutputFormat::Json),
+            "[{\"name\": \"p\", \"values\": [\"80\"], \"source\": \"default\"}, \
+             {\"name\": \"v\", \"values\": [], \"source\": null}, \
+             {\"name\": \"FILE\", \"values\": [\"a.txt\"], \"source\": \"command line\"}]"
+        );
+    }
+
+    // test of "set_exit_status_format" function
+    #[test]
+    fn exit_status_format() {
+        use crate::{ExitStatusCategory, ExitStatusFormat, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_exit_status(0x41, "Busy and locked");
+        argument_test_obj.add_exit_status_category(ExitStatusCategory::new("Device").range(
+            0x80,
+            0x8f,
+            "Device errors",
+        ));
+        argument_test_obj.set_exit_status_format(ExitStatusFormat::Hexadecimal);
+        argument_test_obj.add_translations(
+            "de",
+            "exit-status.65 = Belegt und gesperrt\nexit-category.Device = Gerät",
+        );
+        argument_test_obj.set_language("de");
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(help.contains(
+            "\n    0x41\tBelegt und gesperrt\n    Gerät:\n        0x80-0x8f\tDevice errors"
+        ));
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Json)
+            .contains("{\"status\": 65, \"description\": \"Belegt und gesperrt\"}"));
+    }
+
+    // test of "set_duplicates" function
+    #[test]
+    fn duplicates() {
+        use crate::Duplicates;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('o', "output").takes(1));
+        argument_test_obj.add(
+            Opt::new('n', "name")
+                .takes(1)
+                .duplicates(Duplicates::TakeFirst),
+        );
+        argument_test_obj.add(Opt::new('v', "verbose").duplicates(Duplicates::TakeLast));
+        argument_test_obj.add(Pos::new("FILE").infinite());
+        let arguments: Vec<String> = [
+            "-o",
+            "a",
+            "--output=b",
+            "-n",
+            "x",
+            "--name",
+            "y",
+            "-vv",
+            "f",
+        ]
+        .iter()
+        .map(|argument| argument.to_string())
+        .collect();
+
+        let result_test_obj = argument_test_obj.parse_args(Some(arguments.clone()));
+        assert_eq!(result_test_obj.get("o").unwrap().1, vec!["b"]);
+        assert_eq!(result_test_obj.get("n").unwrap().1, vec!["x"]);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, vec!["f"]);
+
+        argument_test_obj.set_duplicates(Duplicates::Error);
+        let err = argument_test_obj
+            .try_parse_args(Some(arguments.clone()))
+            .unwrap_err();
+        assert_eq!(err.message(), "--output can only be used once");
+        let result_test_obj = argument_test_obj
+            .try_parse_args(Some(arguments[3..].to_vec()))
+            .unwrap();
+        assert!(result_test_obj.get("v").unwrap().0);
+    }
+
+    // test of "subcommands" and "innermost" functions
+    #[test]
+    fn subcommand_chain() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('v', "verbose", "0", None);
+        let remote = argument_test_obj.add_subcommand("remote", "Manage remotes");
+        remote.add_option('f', "force", "0", None);
+        remote
+            .add_subcommand("add", "Add a remote")
+            .add_arg("NAME", "1", None);
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "-v".to_string(),
+            "remote".to_string(),
+            "-f".to_string(),
+            "add".to_string(),
+            "origin".to_string(),
+        ]));
+        let chain: Vec<&str> = result_test_obj
+            .subcommands()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(chain, vec!["remote", "add"]);
+        let (_, remote_result) = result_test_obj.subcommand().unwrap();
+        assert!(remote_result.get("f").unwrap().0);
+        assert!(remote_result.get("v").is_none());
+        assert_eq!(
+            result_test_obj.innermost().get("NAME").unwrap().1,
+            vec!["origin"]
+        );
+        assert_eq!(remote_result.innermost(), result_test_obj.innermost());
+    }
+
+    // test of "add_example" and "set_example_shell" functions
+    #[test]
+    fn examples() {
+        use crate::{ExampleShell, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_example(
+            "{bin} --config config{/}app.toml\n    --verbose",
+            "Run with a config file",
+        );
+        argument_test_obj.set_example_shell(ExampleShell::Posix);
+        assert!(argument_test_obj.render_help(OutputFormat::Plain).contains(
+            "\n\nExamples:\n    Run with a config file\n        \
+             Hello --config config/app.toml \\\n            --verbose\n\n"
+        ));
+        argument_test_obj.set_example_shell(ExampleShell::PowerShell);
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Markdown)
+            .contains(
+                "\n## Examples\n\nRun with a config file\n\n```powershell\n\
+             Hello --config config\\app.toml `\n    --verbose\n```\n"
+            ));
+        argument_test_obj.set_example_shell(ExampleShell::Cmd);
+        assert!(argument_test_obj.render_help(OutputFormat::Json).contains(
+            "\"examples\": [{\"description\": \"Run with a config file\", \
+             \"command\": \"Hello --config config\\\\app.toml ^\\n    --verbose\"}]"
+        ));
+    }
+
+    // test of "parse_args_or_exit_code" and "exit_code" functions
+    #[test]
+    fn exit_codes() {
+        use crate::ExitStatusCategory;
+        use std::process::ExitCode;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_arg("FILE", "1", None);
+        argument_test_obj.add_exit_status(2, "Not fine");
+        argument_test_obj.add_exit_status_category(ExitStatusCategory::new("Network").range(
+            10,
+            19,
+            "Network errors",
+        ));
+        assert_eq!(
+            argument_test_obj.parse_args_or_exit_code(Some(vec![])),
+            Err(ExitCode::FAILURE)
+        );
+        assert_eq!(
+            argument_test_obj.parse_args_or_exit_code(Some(vec!["--help".to_string()])),
+            Err(ExitCode::SUCCESS)
+        );
+        assert!(argument_test_obj
+            .parse_args_or_exit_code(Some(vec!["a.txt".to_string()]))
+            .is_ok());
+
+        assert_eq!(argument_test_obj.exit_code(0), ExitCode::SUCCESS);
+        assert_eq!(argument_test_obj.exit_code(2), ExitCode::from(2));
+        assert_eq!(argument_test_obj.exit_code(15), ExitCode::from(15));
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(
+            || argument_test_obj.exit_code(3)
+        ))
+        .is_err());
+    }
+
+    // test of "value_files" function
+    #[test]
+    fn value_files() {
+        use crate::Limits;
+
+        let path = std::env::temp_dir().join(format!("taap-value-file-{}", std::process::id()));
+        std::fs::write(&path, "  s3cr3t\n").unwrap();
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('t', "token").takes(1).value_files());
+        argument_test_obj.add(Opt::new('n', "name").takes(1));
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            format!("--token=file:{}", path.display()),
+            "-n".to_string(),
+            format!("@{}", path.display()),
+        ]));
+        assert_eq!(result_test_obj.get("t").unwrap().1, vec!["s3cr3t"]);
+        assert_eq!(
+            result_test_obj.value_files("t"),
+            [path.display().to_string()]
+        );
+        // only options with value_files read files
+        assert_eq!(
+            result_test_obj.get("n").unwrap().1,
+            vec![format!("@{}", path.display())]
+        );
+
+        std::fs::write(&path, "x".repeat(1000)).unwrap();
+        argument_test_obj.set_limits(Limits {
+            max_value_length: Some(200),
+            ..Default::default()
+        });
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["-t".to_string(), format!("@{}", path.display())]))
+            .unwrap_err();
+        assert!(err
+            .message()
+            .ends_with("is longer than the limit of 200 bytes"));
+        // a file without an end is only read up to the limit
+        #[cfg(unix)]
+        assert!(argument_test_obj
+            .try_parse_args(Some(vec!["-t".to_string(), "@/dev/zero".to_string()]))
+            .unwrap_err()
+            .message()
+            .ends_with("is longer than the limit of 200 bytes"));
+        std::fs::remove_file(&path).unwrap();
+        let err = argument_test_obj
+            .try_parse_args(Some(vec!["-t".to_string(), format!("@{}", path.display())]))
+            .unwrap_err();
+        assert!(err
+            .message()
+            .starts_with("Could not read the value of -t from"));
+    }
+
+    // test of "secret" function
+    #[test]
+    fn secret_args() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('t', "token").takes(1).secret().global());
+        argument_test_obj.add(Opt::new('p', "pair").takes(2).secret());
+        argument_test_obj.add(Opt::new('n', "name").takes(1));
+        argument_test_obj.add_subcommand("push", "Push");
+        let result_test_obj = argument_test_obj.parse_args(Some(
+            [
+                "-n",
+                "me",
+                "--token=abc",
+                "-p",
+                "a",
+                "b",
+                "push",
+                "-t",
+                "def",
+            ]
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect(),
+        ));
+        // 0 is the name of the program
+        assert_eq!(result_test_obj.secret_args(), [3, 5, 6, 9]);
+        assert_eq!(result_test_obj.raw_args()[9], "def");
+    }
+
+    // test of "add_external_subcommand" and "discover_external_subcommands" functions
+    #[cfg(unix)]
+    #[test]
+    fn external_subcommands() {
+        use crate::external;
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = std::env::temp_dir().join(format!("taap-external-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for (file, mode) in [
+            ("hello-deploy", 0o755),
+            ("hello-init", 0o755),
+            ("hello-notes", 0o644),
+        ] {
+            let path = directory.join(file);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        let found = external::discover("hello-", directory.as_os_str());
+        assert_eq!(found.keys().collect::<Vec<&String>>(), ["deploy", "init"]);
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_subcommand("init", "Create a project");
+        argument_test_obj.add_external_subcommands("hello-", found);
+        let result_test_obj = argument_test_obj.parse_args(Some(
+            ["deploy", "--force", "prod"]
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+        ));
+        let (name, deploy) = result_test_obj.subcommand().unwrap();
+        assert_eq!(name, "deploy");
+        let program = directory.join("hello-deploy").display().to_string();
+        assert_eq!(
+            deploy.external(),
+            Some((
+                program.as_str(),
+                &["--force".to_string(), "prod".to_string()][..]
+            ))
+        );
+        let help = argument_test_obj.render_help(crate::OutputFormat::Plain);
+        assert!(help.contains("init\t\t\tCreate a project"));
+        assert!(help.contains("deploy\t\t\tRun hello-deploy"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    // test of "allow_empty" function
+    #[test]
+    fn allow_empty() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('n', "name").takes(1).allow_empty(false));
+        argument_test_obj.add(Opt::new('c', "color").takes(1).choices(["red", ""]));
+        argument_test_obj.add(Opt::new('t', "title").takes(1));
+        argument_test_obj.add(Pos::new("FILE").allow_empty(false));
+        let arguments = |arguments: &[&str]| -> Option<Vec<String>> {
+            Some(
+                arguments
+                    .iter()
+                    .map(|argument| argument.to_string())
+                    .collect(),
+            )
+        };
+
+        let result_test_obj = argument_test_obj.parse_args(arguments(&["-t", "", "a.txt"]));
+        assert_eq!(result_test_obj.get("t").unwrap().1, vec![""]);
+        let err = argument_test_obj
+            .try_parse_args(arguments(&["--name=", "a.txt"]))
+            .unwrap_err();
+        assert_eq!(err.message(), "--name can't be empty");
+        let err = argument_test_obj
+            .try_parse_args(arguments(&["-c", "", "a.txt"]))
+            .unwrap_err();
+        assert_eq!(err.message(), "-c can't be empty");
+        let err = argument_test_obj
+            .try_parse_args(arguments(&[""]))
+            .unwrap_err();
+        assert_eq!(err.message(), "FILE can't be empty");
+    }
+
+    // test of help texts with several paragraphs
+    #[test]
+    fn help_paragraphs() {
+        use crate::{HelpStyle, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('n', "name")
+                .takes(1)
+                .help("The name,\n  as it's shown.\n\n\nUsed in greetings."),
+        );
+        assert!(argument_test_obj.render_help(OutputFormat::Plain).contains(
+            "\n    -n\t--name\tThe name, as it's shown.\n\n      \t      \tUsed in greetings.\n"
+        ));
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Markdown)
+            .contains(
+                "| `-n`, `--name` | 1 | The name, as it's shown.<br><br>Used in greetings. |"
+            ));
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Json)
+            .contains("\"help\": \"The name, as it's shown.\\n\\nUsed in greetings.\""));
+        argument_test_obj.set_help_style(HelpStyle::Compact);
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .ends_with(
+                "\n  -n, --name  The name, as it's shown.\n\n              Used in greetings."
+            ));
+    }
+
+    // test of "add_section" function
+    #[test]
+    fn sections() {
+        use crate::OutputFormat;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_section("Environment", "HELLO_HOME\tThe data\n\nMore text");
+        argument_test_obj.add_section("Files", "~/.hello");
+        argument_test_obj.add_exit_status(1, "Not fine");
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .ends_with(
+                "\n    1\tNot fine\n\nEnvironment:\n    HELLO_HOME\tThe data\n\n    More text\n\n\
+             Files:\n    ~/.hello\n\nFrom\nTAAP"
+            ));
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Markdown)
+            .contains("\n## Files\n\n~/.hello\n\nFrom\n"));
+        argument_test_obj.add_section("Files", "~/.config/hello");
+        assert!(argument_test_obj.render_help(OutputFormat::Json).contains(
+            "\"sections\": [{\"title\": \"Environment\", \"text\": \"HELLO_HOME\\tThe data\\n\\nMore text\"}, \
+             {\"title\": \"Files\", \"text\": \"~/.config/hello\"}]"
+        ));
+    }
+
+    // test of "negatable" and "presence" functions
+    #[test]
+    fn negatable_presence() {
+        use crate::{OutputFormat, ParsedArgs, Presence, Source};
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('c', "color").negatable());
+        argument_test_obj.add(Opt::new('q', "quiet"));
+        let mut parse = |tokens: &[&str]| {
+            argument_test_obj
+                .try_parse_args(Some(tokens.iter().map(|token| token.to_string()).collect()))
+        };
+        let result_test_obj = parse(&["--no-color"]).unwrap();
+        assert_eq!(result_test_obj.presence("c"), Presence::Disabled);
+        assert_eq!(result_test_obj.source("c"), Some(&Source::CommandLine));
+        assert_eq!(result_test_obj.get("c").unwrap(), &(false, vec![]));
+        assert_eq!(result_test_obj.presence("q"), Presence::Unset);
+        assert_eq!(result_test_obj.source("q"), None);
+        let presence = |parsed: Result<ParsedArgs, _>| parsed.unwrap().presence("c");
+        assert_eq!(presence(parse(&["--no-color", "-c"])), Presence::Enabled);
+        assert_eq!(presence(parse(&["-c", "--no-color"])), Presence::Disabled);
+        // only negatable flags have a --no-NAME
+        assert_eq!(
+            parse(&["--no-quiet"]).unwrap().presence("q"),
+            Presence::Unset
+        );
+        let err = parse(&["--no-color=yes"]).unwrap_err();
+        assert_eq!(err.message(), "--no-color doesn't take a value");
+
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("--[no-]color"));
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            argument_test_obj.add(Opt::new('n', "name").takes(1).negatable());
+        }))
+        .is_err());
+    }
+
+    // test of "new_static" functions
+    #[test]
+    fn static_specs() {
+        use crate::Pos;
+        static OPTIONS: [Opt; 2] = [
+            Opt::new_static('v', "-", "Verbose"),
+            Opt::new_static('-', "output", "Output").takes(1).required(),
+        ];
+        const FILES: Pos = Pos::new_static("FILES", "Files").infinite();
+        assert_eq!(OPTIONS[0], Opt::new('v', "").help("Verbose"));
+        assert_eq!(
+            OPTIONS[1],
+            Opt::new(' ', "output").takes(1).help("Output").required()
+        );
+        assert_eq!(FILES, Pos::new("FILES").help("Files").infinite());
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        for option in OPTIONS.iter() {
+            argument_test_obj.add(option.clone());
+        }
+        argument_test_obj.add(FILES);
+        let result_test_obj = argument_test_obj.parse_args(Some(
+            ["--output", "out", "a", "b"]
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+        ));
+        assert_eq!(result_test_obj.get("output").unwrap().1, ["out"]);
+        assert_eq!(result_test_obj.get("FILES").unwrap().1, ["a", "b"]);
+    }
+
+    // test of "parse_args_os" function
+    #[cfg(unix)]
+    #[test]
+    fn parse_args_os() {
+        use crate::display_os;
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('o', "output").takes(1));
+        argument_test_obj.add_arg("FILE", "1", None);
+        let file = OsStr::from_bytes(b"caf\xe9.txt");
+        let output = OsStr::from_bytes(b"--output=\xff");
+        let result_test_obj =
+            argument_test_obj.parse_args_os(Some(vec![file.to_owned(), output.to_owned()]));
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, ["caf\u{fffd}.txt"]);
+        assert_eq!(result_test_obj.values_os("FILE"), [file]);
+        assert_eq!(result_test_obj.values_os("o"), [OsStr::from_bytes(b"\xff")]);
+        assert_eq!(result_test_obj.raw_args()[1], "caf\u{fffd}.txt");
+        assert_eq!(display_os(OsStr::from_bytes(b"a\x1b[31m\xff")), "a\u{fffd}");
+
+        // arguments with the same lossy copy keep their own originals
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_arg("SRC", "+", None);
+        argument_test_obj.add(Opt::new('o', "output").takes(1));
+        let sources = [
+            OsStr::from_bytes(b"a\xff"),
+            OsStr::from_bytes(b"a\xfe"),
+            OsStr::new("a\u{fffd}"),
+        ];
+        let mut arglist: Vec<_> = sources.iter().map(|source| source.to_os_string()).collect();
+        arglist.extend(["-o".into(), "a\u{fffd}".into()]);
+        let result_test_obj = argument_test_obj.parse_args_os(Some(arglist));
+        assert_eq!(result_test_obj.values_os("SRC"), sources);
+        assert_eq!(result_test_obj.values_os("o"), [OsStr::new("a\u{fffd}")]);
+    }
+
+    // test of the hint to use "--"
+    #[test]
+    fn dash_hint() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Pos::new("SOURCES").infinite());
+        argument_test_obj.add(Pos::new("DEST"));
+        let mut parse = |tokens: &[&str]| {
+            argument_test_obj
+                .try_parse_args(Some(tokens.iter().map(|token| token.to_string()).collect()))
+                .unwrap_err()
+                .message()
+                .to_string()
+        };
+        assert_eq!(
+            parse(&["a", "-b"]),
+            "DEST requires 1 arguments, \"-b\" was skipped as an unknown option (use \"--\" \
+             before arguments that begin with '-')"
+        );
+        assert_eq!(parse(&["-b"]), "DEST requires 1 arguments");
+        #[cfg(feature = "fs-hints")]
+        {
+            // relative to the directory of the crate, where the tests are run
+            std::fs::write("-taap-file", "").unwrap();
+            let message = parse(&["-taap-file"]);
+            std::fs::remove_file("-taap-file").unwrap();
+            assert!(message.ends_with("begin with '-')"));
+        }
+    }
+
+    // test of "env" function
+    #[test]
+    fn env() {
+        use crate::{Outcome, OutputFormat, Source};
+        std::env::set_var("TAAP_TEST_ENV_PORT", "8080");
+        std::env::set_var("TAAP_TEST_ENV_DEBUG", "Yes");
+        std::env::set_var("TAAP_TEST_ENV_TAGS", "a 'b c'");
+        std::env::set_var("TAAP_TEST_ENV_EMPTY", "");
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('p', "port")
+                .takes(1)
+                .default("80")
+                .env("TAAP_TEST_ENV_PORT")
+                .help("The port"),
+        );
+        argument_test_obj.add(
+            Opt::new('d', "debug")
+                .negatable()
+                .env("TAAP_TEST_ENV_DEBUG"),
+        );
+        argument_test_obj.add(
+            Opt::new('t', "tags")
+                .arity(Arity::Infinite)
+                .env("TAAP_TEST_ENV_TAGS"),
+        );
+        argument_test_obj.add(
+            Opt::new('n', "name")
+                .takes(1)
+                .required()
+                .env("TAAP_TEST_ENV_EMPTY"),
+        );
+
+        let mut parse = |tokens: &[&str]| {
+            argument_test_obj
+                .try_parse_args(Some(tokens.iter().map(|token| token.to_string()).collect()))
+        };
+        let result_test_obj = parse(&["-n", "me"]).unwrap();
+        assert_eq!(result_test_obj.get("p").unwrap().1, vec!["8080"]);
+        assert_eq!(
+            result_test_obj.source("p"),
+            Some(&Source::Environment("TAAP_TEST_ENV_PORT".to_string()))
+        );
+        assert!(result_test_obj.is_present("d"));
+        assert_eq!(result_test_obj.get("t").unwrap().1, vec!["a", "b c"]);
+        let result_test_obj = parse(&["-n", "me", "-p", "1", "--no-debug"]).unwrap();
+        assert_eq!(result_test_obj.get("p").unwrap().1, vec!["1"]);
+        assert!(!result_test_obj.is_present("d"));
+        // an empty variable counts as unset
+        assert_eq!(parse(&[]).unwrap_err().message(), "--name is required");
+
+        std::env::set_var("TAAP_TEST_ENV_DEBUG", "maybe");
+        assert_eq!(
+            parse(&["-n", "me"]).unwrap_err().message(),
+            "TAAP_TEST_ENV_DEBUG is \"maybe\", but --debug can only be turned on (1, true, yes or on) \
+             or off (0, false, no or off)"
+        );
+        std::env::set_var("TAAP_TEST_ENV_DEBUG", "off");
+        assert!(!parse(&["-n", "me"]).unwrap().is_present("d"));
+
+        // matching a command line doesn't read the environment
+        match argument_test_obj
+            .match_tokens(vec!["-n".to_string(), "me".to_string()])
+            .outcome
+        {
+            Outcome::Parsed(parsed) => assert_eq!(parsed.get("p").unwrap().1, vec!["80"]),
+            outcome => panic!("{:?}", outcome),
+        };
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\t--port\tThe port [env: TAAP_TEST_ENV_PORT]\n"));
+    }
+
+    // test of "value_source" function
+    #[test]
+    fn value_source() {
+        use crate::{Source, ValueSource};
+        struct Store;
+        impl ValueSource for Store {
+            fn name(&self) -> String {
+                "the store".to_string()
+            }
+
+            fn fetch(&self, key: &str) -> Result<Option<Vec<String>>, String> {
+                match key {
+                    "token" => Ok(Some(vec![" s3cret ".to_string()])),
+                    "pair" => Ok(Some(vec!["1".to_string()])),
+                    "broken" => Err("locked".to_string()),
+                    _ => Ok(None),
+                }
+            }
+        }
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('t', "token")
+                .takes(1)
+                .required()
+                .normalize(|value| value.trim().to_string())
+                .value_source(Store, "token"),
+        );
+        argument_test_obj.add(
+            Opt::new('u', "user")
+                .takes(1)
+                .default("nobody")
+                .value_source(Store, "user"),
+        );
+        let args = |list: &[&str]| Some(list.iter().map(|arg| arg.to_string()).collect());
+
+        let result_test_obj = argument_test_obj.parse_args(args(&[]));
+        assert_eq!(result_test_obj.get("t").unwrap().1, ["s3cret"]);
+        assert_eq!(
+            result_test_obj.source("t"),
+            Some(&Source::External("the store".to_string()))
+        );
+        assert_eq!(result_test_obj.get("u").unwrap().1, ["nobody"]);
+
+        let result_test_obj = argument_test_obj.parse_args(args(&["-t", "given"]));
+        assert_eq!(result_test_obj.get("t").unwrap().1, ["given"]);
+
+        argument_test_obj.add(Opt::new('p', "pair").takes(2).value_source(Store, "pair"));
+        assert_eq!(
+            argument_test_obj
+                .try_parse_args(args(&[]))
+                .unwrap_err()
+                .message(),
+            "--pair got 1 values from the store, which doesn't match the amount it takes"
+        );
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('k', "key").takes(1).value_source(Store, "broken"));
+        assert_eq!(
+            argument_test_obj
+                .try_parse_args(args(&[]))
+                .unwrap_err()
+                .message(),
+            "Can't get --key from the store: locked"
+        );
+    }
+
+    // test of "enables" function
+    #[test]
+    fn enables() {
+        use crate::{OutputFormat, Presence};
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('a', "all").enables(["lint", "f", "extra"]));
+        argument_test_obj.add(Opt::new('l', "lint").negatable());
+        argument_test_obj.add(Opt::new('f', "format"));
+        argument_test_obj.add(Opt::new('x', "extra").enables(["docs"]));
+        argument_test_obj.add(Opt::new('d', "docs").negatable());
+        let args = |list: &[&str]| Some(list.iter().map(|arg| arg.to_string()).collect());
+
+        let result_test_obj = argument_test_obj.parse_args(args(&["--all"]));
+        for name in ["l", "f", "x", "d"] {
+            assert!(result_test_obj.get(name).unwrap().0);
+        }
+        // turning a flag off wins, wherever it is
+        for list in [["--no-lint", "-a"], ["-a", "--no-lint"]] {
+            let result_test_obj = argument_test_obj.parse_args(args(&list));
+            assert_eq!(result_test_obj.presence("l"), Presence::Disabled);
+            assert!(result_test_obj.get("d").unwrap().0);
+        }
+        let result_test_obj = argument_test_obj.parse_args(args(&["-x", "--no-docs"]));
+        assert!(!result_test_obj.get("d").unwrap().0);
+        assert!(!result_test_obj.get("f").unwrap().0);
+
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\t--all\t\t(same as --lint --format --extra)\n"));
+    }
+
+    // test of "new" and "help" functions of the specs, keeping static strs as they are
+    #[test]
+    fn spec_cow() {
+        use std::borrow::Cow;
+        let option = Opt::new('c', "count").help("How many");
+        assert!(matches!(option.long, Some(Cow::Borrowed("count"))));
+        assert!(matches!(
+            option.help,
+            Text::Static(Cow::Borrowed("How many"))
+        ));
+        let option = Opt::new('-', format!("level-{}", 1)).help(String::from("Level"));
+        assert!(matches!(option.long.as_deref(), Some("level-1")));
+        assert!(Opt::new('c', "--").long.is_none());
+        let positional = Pos::new("FILE").help("The file");
+        assert!(matches!(positional.placeholder, Cow::Borrowed("FILE")));
+        assert!(matches!(
+            positional.help,
+            Text::Static(Cow::Borrowed("The file"))
+        ));
+    }
+
+    // test of "get_one" function
+    #[test]
+    fn get_one() {
+        use crate::{ErrorKind, Opt};
+
+        let mut argument_test_obj = Argument::new("Name", "Description", "Epilog", "Credits");
+        argument_test_obj.add(Opt::new('p', "port").takes(1).default("8080"));
+        argument_test_obj.add(Opt::new('-', "ratio").takes(1));
+        argument_test_obj.add(Opt::new('-', "dry-run"));
+        argument_test_obj.add_arg("NUMBERS", "+", None);
+
+        let parsed = argument_test_obj.parse_args(Some(vec![
+            "--dry-run".to_string(),
+            "1".to_string(),
+            "2".to_string(),
+        ]));
+        assert_eq!(parsed.get_one::<u16>("p").unwrap(), 8080);
+        assert_eq!(parsed.get_many::<u8>("NUMBERS").unwrap(), vec![1, 2]);
+        assert!(parsed.is_present("dry-run"));
+        // the value of a flag is whether it was used
+        assert!(parsed.get_one::<bool>("dry-run").unwrap());
+        assert!(!parsed.get_one::<bool>("h").unwrap());
+        assert!(!parsed.is_present("ratio"));
+        assert!(parsed.get_many::<f64>("ratio").unwrap().is_empty());
+        let err = parsed.get_one::<f64>("ratio").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequired);
+        assert_eq!(err.message(), "ratio didn't get a value");
+
+        let parsed = argument_test_obj.parse_args(Some(vec![
+            "-p".to_string(),
+            "http".to_string(),
+            "1".to_string(),
+        ]));
+        let err = parsed.get_one::<u16>("p").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+        assert_eq!(
+            err.message(),
+            "Invalid value \"http\" for p: invalid digit found in string"
+        );
+    }
+
+    // test of "set_version" function
+    #[test]
+    fn set_version() {
+        use crate::OutputFormat;
+        use std::process::ExitCode;
+
+        let mut argument_test_obj = Argument::new("Hello", "{version}", "From", "TAAP");
+        argument_test_obj.add(Pos::new("FILE"));
+        argument_test_obj.set_version("1.2.3");
+        argument_test_obj.set_version("1.2.4");
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("-V\t--version\t\tPrint the version and exit"));
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("1.2.4"));
+        assert_eq!(
+            argument_test_obj.parse_args_or_exit_code(Some(vec!["--version".to_string()])),
+            Err(ExitCode::SUCCESS)
+        );
+        assert_eq!(
+            argument_test_obj.parse_args_or_exit_code(Some(vec!["-V".to_string()])),
+            Err(ExitCode::SUCCESS)
+        );
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('V', "verify"));
+        argument_test_obj.set_version("1.2.3");
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["-V".to_string()]));
+        assert!(result_test_obj.is_present("V"));
+        assert!(!result_test_obj.is_present("version"));
+    }
+
+    // test of "argument_keys" macro
+    #[test]
+    fn argument_keys() {
+        crate::argument_keys! {
+            mod keys {
+                VERBOSE = "v",
+                PORT = "port",
+                FILE = "FILE",
+            }
+        }
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add(Opt::new('-', "port").takes(1));
+        argument_test_obj.add(Pos::new("FILE"));
+        keys::check(&argument_test_obj);
+        assert_eq!(keys::ALL, &["v", "port", "FILE"]);
+
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["a.txt".to_string()]));
+        assert_eq!(result_test_obj.get(keys::FILE).unwrap().1, vec!["a.txt"]);
+        assert!(!result_test_obj.is_present(keys::VERBOSE));
+
+        crate::argument_keys! {
+            mod wrong {
+                VERBOSE = "verbose",
+                PORT = "prot",
+            }
+        }
+
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            wrong::check(&argument_test_obj)
+        }))
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<String>().unwrap(),
+            "Error! The keys of \"Hello\" don't match its arguments:\n\
+             wrong::VERBOSE is \"verbose\", but the argument is stored as \"v\"\n\
+             wrong::PORT is \"prot\", which isn't the name of an argument"
+        );
+    }
+
+    // test of "parse_args" function, with spaces around the = attaching a value
+    #[test]
+    fn equals_spacing() {
+        use crate::matcher::Outcome;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('-', "name").takes(1));
+        argument_test_obj.add(Opt::new('-', "tag").takes(1).allow_empty(true));
+        argument_test_obj.add(Opt::new('-', "dry-run"));
+        argument_test_obj.add(Pos::new("REST").arity(Arity::Infinite));
+        let tokens = |tokens: &[&str]| tokens.iter().map(|token| token.to_string()).collect();
+
+        assert_eq!(
+            argument_test_obj
+                .try_parse_args(Some(tokens(&["--name=", "foo"])))
+                .unwrap_err()
+                .message(),
+            "Unexpected space after \"=\" in --name= foo, use --name=foo"
+        );
+
+        // = is a value like any other, so a space before it is only warned about
+        let mut matched = |list: &[&str]| {
+            let matches = argument_test_obj.match_tokens(tokens(list));
+            match matches.outcome {
+                Outcome::Parsed(parsed) => (
+                    parsed.get("name").unwrap().1.to_owned(),
+                    parsed.get("REST").unwrap().1.to_owned(),
+                    matches.warnings,
+                ),
+                outcome => panic!("{:?}", outcome),
+            }
+        };
+        assert_eq!(
+            matched(&["--name", "=foo"]),
+            (
+                vec!["=foo".to_string()],
+                vec![],
+                vec![
+                    "\"--name =foo\" gives --name the value \"=foo\", use --name=foo for \"foo\""
+                        .to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            matched(&["--name", "=", "foo"]),
+            (
+                vec!["=".to_string()],
+                vec!["foo".to_string()],
+                vec![
+                    "\"--name = foo\" gives --name the value \"=\", use --name=foo for \"foo\""
+                        .to_string()
+                ]
+            )
+        );
+        assert_eq!(matched(&["--name", "=="]).0, ["=="]);
+        assert_eq!(
+            matched(&["--name", "="]),
+            (vec!["=".to_string()], vec![], vec![])
+        );
+
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(tokens(&["--name==foo", "--tag=", "bar"])));
+        assert_eq!(result_test_obj.get("name").unwrap().1, vec!["=foo"]);
+        assert_eq!(result_test_obj.get("tag").unwrap().1, vec![""]);
+        assert_eq!(result_test_obj.get("REST").unwrap().1, vec!["bar"]);
+
+        let result_test_obj = argument_test_obj.parse_args(Some(tokens(&["--dry-run", "=x"])));
+        assert_eq!(result_test_obj.get("REST").unwrap().1, vec!["=x"]);
+    }
+
+    // test of "parse_args_str" and "spans" functions
+    #[test]
+    fn spans() {
+        use crate::Span;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('o', "output").takes(1));
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add(Opt::new('-', "level").takes(1).default("1"));
+        argument_test_obj.add(Pos::new("FILES").arity(Arity::Infinite));
+        argument_test_obj
+            .add_subcommand("run", "Run it")
+            .add(Pos::new("TASK"));
+
+        let line = "a.txt \"b c\"  --output 'my file' -v run build";
+        let result_test_obj = argument_test_obj.parse_args_str(line);
+        let shown = |name: &str| -> Vec<&str> {
+            result_test_obj
+                .spans(name)
+                .into_iter()
+                .map(|span| &line[span.bytes])
+                .collect()
+        };
+        assert_eq!(shown("o"), vec!["--output", "'my file'"]);
+        assert_eq!(shown("v"), vec!["-v"]);
+        assert_eq!(shown("FILES"), vec!["a.txt", "\"b c\""]);
+        assert!(shown("level").is_empty());
+        assert_eq!(
+            result_test_obj.spans("o"),
+            vec![
+                Span {
+                    index: 3,
+                    bytes: 13..21
+                },
+                Span {
+                    index: 4,
+                    bytes: 22..31
+                }
+            ]
+        );
+        let (_, run) = result_test_obj.subcommand().unwrap();
+        assert_eq!(
+            run.spans("TASK"),
+            vec![Span {
+                index: 7,
+                bytes: 39..44
+            }]
+        );
+
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["-vo".to_string(), "out".to_string()]));
+        assert_eq!(
+            result_test_obj.spans("o"),
+            vec![
+                Span {
+                    index: 1,
+                    bytes: 0..3
+                },
+                Span {
+                    index: 2,
+                    bytes: 4..7
+                }
+            ]
+        );
+        assert_eq!(
+            failure(argument_test_obj.run_str("'a")).message(),
+            "The quote at column 1 of the command line isn't closed: \"'a\""
+        );
+    }
+
+    // test of "set_quote_dialect" function
+    #[test]
+    fn quote_dialect() {
+        use crate::QuoteDialect;
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('m', "message").takes(1));
+        argument_test_obj.add(Pos::new("FILES").arity(Arity::Infinite));
+
+        let result_test_obj =
+            argument_test_obj.parse_args_str(r#"-m "fix the bug" 'a b.txt' c\ d"#);
+        assert_eq!(result_test_obj.get("m").unwrap().1, vec!["fix the bug"]);
+        assert_eq!(
+            result_test_obj.get("FILES").unwrap().1,
+            vec!["a b.txt", "c d"]
+        );
+        let mut unclosed = |line: &str| {
+            failure(argument_test_obj.run_str(line))
+                .message()
+                .to_string()
+        };
+        assert_eq!(
+            unclosed(r#"'a' -m "b 'c"#),
+            r#"The quote at column 8 of the command line isn't closed: "'a' -m "b 'c""#
+        );
+        assert_eq!(
+            unclosed("é 'a"),
+            "The quote at column 3 of the command line isn't closed: \"é 'a\""
+        );
+
+        argument_test_obj.set_quote_dialect(QuoteDialect::Windows);
+        let result_test_obj = argument_test_obj
+            .parse_args_str(r#"--message="say ""hi""" "C:\My Files\\" a\\\"b 'c d' e\\\\"f g""#);
+        assert_eq!(result_test_obj.get("m").unwrap().1, vec!["say \"hi\""]);
+        assert_eq!(
+            result_test_obj.get("FILES").unwrap().1,
+            vec!["C:\\My Files\\", "a\\\"b", "'c", "d'", "e\\\\f g"]
+        );
+        let mut unclosed = |line: &str| {
+            failure(argument_test_obj.run_str(line))
+                .message()
+                .to_string()
+        };
+        assert_eq!(
+            unclosed(r#"a "b c"#),
+            r#"The quote at column 3 of the command line isn't closed: "a "b c""#
+        );
+
+        argument_test_obj.set_quote_dialect(QuoteDialect::PowerShell);
+        let result_test_obj = argument_test_obj
+            .parse_args_str(r#"-m 'it''s done' "C:\My Files\" "say ""hi"" `"now`"" a` b x`ty"#);
+        assert_eq!(result_test_obj.get("m").unwrap().1, vec!["it's done"]);
+        assert_eq!(
+            result_test_obj.get("FILES").unwrap().1,
+            vec!["C:\\My Files\\", "say \"hi\" \"now\"", "a b", "x\ty"]
+        );
+        let mut unclosed = |line: &str| {
+            failure(argument_test_obj.run_str(line))
+                .message()
+                .to_string()
+        };
+        assert_eq!(
+            unclosed(r#"a "b`" c"#),
+            r#"The quote at column 3 of the command line isn't closed: "a "b`" c""#
+        );
+    }
+
+    // test of "set_strict" function
+    #[test]
+    fn strict() {
+        use crate::ErrorKind;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add(Opt::new('-', "color").negatable());
+        argument_test_obj.add(Pos::new("FILE"));
+        argument_test_obj.add_subcommand("run", "Run it");
+
+        let tokens = |tokens: &[&str]| -> Option<Vec<String>> {
+            Some(tokens.iter().map(|token| token.to_string()).collect())
+        };
+        let result_test_obj = argument_test_obj.parse_args(tokens(&["--verbos", "a.txt"]));
+        assert!(!result_test_obj.is_present("v"));
+
+        argument_test_obj.set_strict(true);
+        let err = argument_test_obj
+            .try_parse_args(tokens(&["--verbos=1", "a.txt"]))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownOption);
+        assert_eq!(
+            err.message(),
+            "Unknown option --verbos, did you mean \"--verbose\"?"
+        );
+        let message = |arguments: &mut Argument, list: &[&str]| {
+            arguments
+                .try_parse_args(tokens(list))
+                .unwrap_err()
+                .message()
+                .to_string()
+        };
+        assert_eq!(
+            message(&mut argument_test_obj, &["-x", "a.txt"]),
+            "Unknown option -x"
+        );
+        assert_eq!(
+            message(&mut argument_test_obj, &["-verbose", "a.txt"]),
+            "Unknown option -e in -verbose, did you mean \"--verbose\"?"
+        );
+        assert_eq!(
+            message(&mut argument_test_obj, &["run", "--quiet"]),
+            "Unknown option --quiet"
+        );
+        let result_test_obj = argument_test_obj.parse_args(tokens(&["--no-color", "--", "-x"]));
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, vec!["-x"]);
+    }
+
+    // test of "misspelled" function, suggesting options for typos
+    #[test]
+    fn misspelled() {
+        use crate::parse::misspelled;
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add(Opt::new('-', "color").takes(1));
+
+        assert_eq!(
+            misspelled(&argument_test_obj, "hlep"),
+            Some("--help".to_string())
+        );
+        assert_eq!(
+            misspelled(&argument_test_obj, "colour=red"),
+            Some("--color".to_string())
+        );
+        assert_eq!(misspelled(&argument_test_obj, "output"), None);
+
+        // the typo is skipped with a warning, like any other unknown option
+        let result_test_obj = argument_test_obj.parse_args(Some(vec!["--verbos".to_string()]));
+        assert!(!result_test_obj.is_present("v"));
+    }
+
+    // test of "set_reserve_positionals" function
+    #[test]
+    fn reserve_positionals() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_option('i', "include", "+", None);
+        argument_test_obj.add_option('v', "verbose", "0", None);
+        argument_test_obj.add_arg("FILE", "1", None);
+        argument_test_obj.add_arg("OUTPUT", "1", None);
+        let tokens = |tokens: &[&str]| -> Option<Vec<String>> {
+            Some(tokens.iter().map(|token| token.to_string()).collect())
+        };
+
+        assert!(argument_test_obj
+            .try_parse_args(tokens(&["-i", "a", "b", "in", "out"]))
+            .is_err());
+
+        argument_test_obj.set_reserve_positionals(true);
+        let result_test_obj = argument_test_obj.parse_args(tokens(&["-i", "a", "b", "in", "out"]));
+        assert_eq!(result_test_obj.get("i").unwrap().1, vec!["a", "b"]);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, vec!["in"]);
+        assert_eq!(result_test_obj.get("OUTPUT").unwrap().1, vec!["out"]);
+
+        let result_test_obj =
+            argument_test_obj.parse_args(tokens(&["in", "--include", "a", "b", "out"]));
+        assert_eq!(result_test_obj.get("i").unwrap().1, vec!["a", "b"]);
+        assert_eq!(result_test_obj.get("OUTPUT").unwrap().1, vec!["out"]);
+
+        // values that don't reach the end are all taken
+        let result_test_obj =
+            argument_test_obj.parse_args(tokens(&["-i", "a", "b", "-v", "in", "out"]));
+        assert_eq!(result_test_obj.get("i").unwrap().1, vec!["a", "b"]);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, vec!["in"]);
+    }
+
+    // test of "set_arity_notation" function
+    #[test]
+    fn arity_notation() {
+        use crate::{ArityNotation, HelpStyle, OutputFormat};
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_arg("SOURCE", "1", Some("Copied"));
+        argument_test_obj.add_arg("PAIR", "2", Some("Two of them"));
+        argument_test_obj.add_arg("FILES", "+", Some("All of them"));
+
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(help.starts_with("Usage: Hello <SOURCE> <PAIR> <PAIR> [FILES]... [OPTIONS]\n"));
+        assert!(help.contains(
+            "\n    <SOURCE>\t\tCopied\n    <PAIR> <PAIR>\tTwo of them\n    [FILES]...\t\tAll of them\n"
+        ));
+
+        argument_test_obj.set_help_style(HelpStyle::Compact);
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .contains("\n  <PAIR> <PAIR>  Two of them\n"));
+
+        argument_test_obj.set_help_style(HelpStyle::Full);
+        argument_test_obj.set_arity_notation(ArityNotation::Stars);
+        assert!(argument_test_obj
+            .render_help(OutputFormat::Plain)
+            .starts_with("Usage: Hello SOURCE PAIR*2 FILES*∞ [OPTIONS]\n"));
+    }
+
+    // test of "match_tokens" function
+    #[test]
+    fn match_tokens() {
+        use crate::{ErrorKind, Outcome};
+
+        std::env::set_var("TAAP_TEST_MATCH_OPTS", "-v");
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add(Opt::new('-', "color").takes(1));
+        argument_test_obj.add(Pos::new("FILE"));
+        argument_test_obj.add_renamed("colour", "color");
+        argument_test_obj.set_options_env("TAAP_TEST_MATCH_OPTS");
+        argument_test_obj.set_version("1.2.3");
+
+        let tokens = |tokens: &[&str]| -> Vec<String> {
+            tokens.iter().map(|token| token.to_string()).collect()
+        };
+        let matches = argument_test_obj.match_tokens(tokens(&["--colour", "red", "a.txt"]));
+        match matches.outcome {
+            Outcome::Parsed(parsed) => {
+                assert_eq!(parsed.get("color").unwrap().1, vec!["red"]);
+                assert!(!parsed.is_present("v"));
+            }
+            outcome => panic!("{:?}", outcome),
+        };
+        assert_eq!(
+            matches.warnings,
+            vec!["--colour is deprecated, use --color instead"]
+        );
+
+        let matches = argument_test_obj.match_tokens(tokens(&["--version"]));
+        assert!(matches!(matches.outcome, Outcome::Exited(0)));
+        assert_eq!(matches.output, vec!["Hello 1.2.3"]);
+
+        let matches = argument_test_obj.match_tokens(tokens(&["--help"]));
+        assert!(matches!(matches.outcome, Outcome::Exited(0)));
+        assert!(matches.output[0].starts_with("Usage: Hello <FILE> [OPTIONS]"));
+
+        let matches = argument_test_obj.match_tokens(tokens(&[]));
+        match matches.outcome {
+            Outcome::Failed(err) => assert_eq!(err.kind(), ErrorKind::MissingPositional),
+            outcome => panic!("{:?}", outcome),
+        };
+    }
+
+    // test of "new_raw" function
+    #[test]
+    fn new_raw() {
+        use crate::OutputFormat;
+        let mut argument_test_obj = Argument::new_raw("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('h', "host").takes(1).help("The host"));
+        argument_test_obj.add(Pos::new("ARGS").arity(Arity::Infinite).command());
+        argument_test_obj
+            .add_subcommand("run", "Run it")
+            .add(Pos::new("ARGS").arity(Arity::Infinite).command());
+
+        let mut parse = |tokens: &[&str]| {
+            argument_test_obj
+                .try_parse_args(Some(tokens.iter().map(|token| token.to_string()).collect()))
+        };
+        let result_test_obj = parse(&["-h", "example.com", "ls", "--help", "-h"]).unwrap();
+        assert_eq!(result_test_obj.get("h").unwrap().1, vec!["example.com"]);
+        assert_eq!(
+            result_test_obj.get("ARGS").unwrap().1,
+            vec!["ls", "--help", "-h"]
+        );
+        let result_test_obj = parse(&["--help"]).unwrap();
+        assert!(result_test_obj.get("ARGS").unwrap().1.is_empty());
+        let result_test_obj = parse(&["run", "--help"]).unwrap();
+        let (_, run) = result_test_obj.subcommand().unwrap();
+        assert!(run.get("ARGS").unwrap().1.is_empty());
+        assert!(run.get("h").is_none());
+
+        let help = argument_test_obj.render_help(OutputFormat::Plain);
+        assert!(help.contains("\n    -h\t--host\tThe host\n"));
+        assert!(!help.contains("--help"));
+        assert_eq!(
+            Argument::new("Hello", "World", "From", "TAAP").render_help(OutputFormat::Plain),
+            {
+                let mut argument = Argument::new_raw("Hello", "World", "From", "TAAP");
+                argument.add_option(
+                    'h',
+                    "help",
+                    "0",
+                    Some("Use this to print this help message"),
+                );
+                argument.render_help(OutputFormat::Plain)
+            }
+        );
+    }
+
+    // test of "add_exclusive" function
+    #[test]
+    fn add_exclusive() {
+        use crate::ErrorKind;
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Pos::new("FILE"));
+        argument_test_obj.add(Opt::new('s', "stdin"));
+        argument_test_obj.add(Opt::new('f', "format").takes(1).default("text"));
+        argument_test_obj.add(Opt::new('j', "json"));
+        argument_test_obj.add_exclusive(["FILE", "stdin"]);
+        argument_test_obj.add_exclusive(["format", "j"]);
+        let args = |list: &[&str]| Some(list.iter().map(|arg| arg.to_string()).collect());
+
+        let result_test_obj = argument_test_obj.parse_args(args(&["--stdin"]));
+        assert!(result_test_obj.get("FILE").unwrap().1.is_empty());
+        // the default of --format doesn't count
+        let result_test_obj = argument_test_obj.parse_args(args(&["file", "-j"]));
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, ["file"]);
+
+        let err = argument_test_obj
+            .try_parse_args(args(&["file", "--stdin"]))
+            .unwrap_err();
+        assert_eq!(err.message(), "FILE can't be used together with --stdin");
+        assert_eq!(err.kind(), ErrorKind::ConflictingArguments);
+        assert_eq!(
+            argument_test_obj
+                .try_parse_args(args(&["-j", "-f", "csv", "-s"]))
+                .unwrap_err()
+                .message(),
+            "--format can't be used together with --json"
+        );
+        assert_eq!(
+            argument_test_obj
+                .try_parse_args(args(&[]))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingPositional
+        );
+    }
+
+    // test of "add_renamed" and "add_renamed_fn" functions
+    #[test]
+    fn add_renamed() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('c', "color").takes(1));
+        argument_test_obj.add(Opt::new('-', "include").infinite());
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add_arg("FILE", "1", None);
+        argument_test_obj.add_renamed_fn("--colour", "c", |value| match value {
+            "yes" => "always".to_string(),
+            value => value.to_string(),
+        });
+        argument_test_obj.add_renamed("includes", "include");
+        argument_test_obj.add_renamed("loud", "verbose");
+        let args = |list: &[&str]| Some(list.iter().map(|arg| arg.to_string()).collect());
+
+        let result_test_obj = argument_test_obj.parse_args(args(&["--colour=yes", "file"]));
+        assert_eq!(result_test_obj.get("c").unwrap().1, ["always"]);
+        let result_test_obj = argument_test_obj.parse_args(args(&["--colour", "yes", "file"]));
+        assert_eq!(result_test_obj.get("c").unwrap().1, ["always"]);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, ["file"]);
+        let result_test_obj =
+            argument_test_obj.parse_args(args(&["--includes", "a", "b", "--loud", "file"]));
+        assert_eq!(result_test_obj.get("include").unwrap().1, ["a", "b"]);
+        assert!(result_test_obj.get("v").unwrap().0);
+        // after -- it's a value like any other
+        let result_test_obj = argument_test_obj.parse_args(args(&["--", "--loud"]));
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, ["--loud"]);
+
+        assert!(!argument_test_obj
+            .render_help(crate::OutputFormat::Plain)
+            .contains("colour"));
+    }
+
+    // test of "add_renamed" function together with "secret_args" and "spans" functions
+    #[test]
+    fn renamed_positions() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('t', "token").takes(1).secret());
+        argument_test_obj.add(Opt::new('l', "level").takes(1));
+        argument_test_obj.add_arg("FILE", "1", None);
+        argument_test_obj.add_renamed("old", "level");
+        let result_test_obj = argument_test_obj.parse_args(Some(
+            ["file", "--token", "s3cret", "--old", "x"]
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+        ));
+        // 0 is the name of the program
+        assert_eq!(result_test_obj.secret_args(), [3]);
+        let index = |name: &str| {
+            result_test_obj
+                .spans(name)
+                .iter()
+                .map(|span| span.index)
+                .collect::<Vec<usize>>()
+        };
+        assert_eq!(index("FILE"), [1]);
+        assert_eq!(index("l"), [4, 5]);
+    }
+
+    // test of "default_template" function
+    #[test]
+    fn default_template() {
+        use crate::Source;
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('o', "output")
+                .takes(1)
+                .default_template("{INPUT}.out"),
+        );
+        argument_test_obj.add(
+            Opt::new('l', "log")
+                .takes(1)
+                .default_template("{{{output}}}.log"),
+        );
+        argument_test_obj.add(Opt::new('t', "tag").takes(1).default_template("{suffix}"));
+        argument_test_obj.add(Opt::new('s', "suffix").takes(1));
+        argument_test_obj.add(Pos::new("INPUT"));
+        let args = |list: &[&str]| Some(list.iter().map(|arg| arg.to_string()).collect());
+
+        let result_test_obj = argument_test_obj.parse_args(args(&["data"]));
+        assert_eq!(result_test_obj.get("o").unwrap().1, ["data.out"]);
+        assert_eq!(result_test_obj.get("l").unwrap().1, ["{data.out}.log"]);
+        assert_eq!(result_test_obj.source("o"), Some(&Source::Default));
+        // an argument without a value leaves the option unused
+        assert!(!result_test_obj.get("t").unwrap().0);
+
+        let result_test_obj = argument_test_obj.parse_args(args(&["-o", "x", "data"]));
+        assert_eq!(result_test_obj.get("l").unwrap().1, ["{x}.log"]);
+    }
+
+    // test of "default_template" function, with defaults referring to each other
+    #[test]
+    #[should_panic(expected = "--a -> --b -> --a refer to each other")]
+    fn default_template_cycle() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('-', "a").takes(1).default_template("{b}"));
+        argument_test_obj.add(Opt::new('-', "b").takes(1).default_template("{a}"));
+        argument_test_obj.parse_args(Some(vec![]));
+    }
+
+    // test of "since", "removed" and "whats_new" functions
+    #[test]
+    fn whats_new() {
+        use crate::version::compare;
+        use std::cmp::Ordering;
+        assert_eq!(compare("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare("v2.0", "2"), Ordering::Equal);
+        assert_eq!(compare("2.0.1", "2.0.1-rc1"), Ordering::Less);
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('j', "jobs").takes(1).since("2.10"));
+        argument_test_obj.add(Opt::new('-', "legacy").since("1.0").removed("2.2"));
+        argument_test_obj.add(Pos::new("FILE").since("2.9"));
+        assert_eq!(
+            argument_test_obj.whats_new("2.0"),
+            "2.2\t--legacy was removed\n2.9\tFILE was added\n2.10\t--jobs was added"
+        );
+        assert_eq!(argument_test_obj.whats_new("2.10"), "");
+
+        // the options keep working outside of the compatibility level
+        argument_test_obj.set_compatibility("2.5");
+        let result_test_obj = argument_test_obj.parse_args(Some(
+            ["--legacy", "-j", "4", "file"]
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+        ));
+        assert!(result_test_obj.get("legacy").unwrap().0);
+        assert_eq!(result_test_obj.get("j").unwrap().1, ["4"]);
+    }
+
+    // test of "render_help_matching" function
+    #[test]
+    fn render_help_matching() {
+        use crate::OutputFormat;
+        let mut argument_test_obj = Argument::new("Hello", "World", "", "");
+        argument_test_obj.add(Opt::new('o', "output").takes(1).help("Where to write"));
+        argument_test_obj.add(Opt::new('v', "verbose").help("Print more OUTPUT"));
+        argument_test_obj.add(Opt::new('q', "quiet"));
+        argument_test_obj.add(Pos::new("FILE").help("The input"));
+        argument_test_obj.add_section("Environment", "HELLO_OUTPUT");
+        argument_test_obj.add_section("Files", "~/.hello");
+        let help = argument_test_obj.render_help_matching(OutputFormat::Markdown, "output");
+        assert!(help.contains("`--output`") && help.contains("`--verbose`"));
+        assert!(help.contains("## Environment"));
+        assert!(!help.contains("`--quiet`"));
+        assert!(!help.contains("`FILE`"));
+        assert!(!help.contains("## Files"));
+        // the usage line stays complete
+        assert!(help.contains("Hello <FILE> [OPTIONS]"));
+    }
+
+    // test of "render_man_pages" function
+    #[test]
+    fn render_man_pages() {
+        use crate::ManLayout;
+        let mut argument_test_obj = Argument::new("hello", "World", "", "");
+        argument_test_obj.add(Opt::new('o', "out-file").takes(1).help("Where\n\n.Really"));
+        argument_test_obj
+            .add_subcommand("remote", "Remotes")
+            .add_subcommand("add", "Add a remote");
+        let pages = argument_test_obj.render_man_pages(ManLayout::PerSubcommand);
+        let names: Vec<&str> = pages.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["hello.1", "hello-remote.1", "hello-remote-add.1"]);
+        assert!(pages[0]
+            .1
+            .starts_with(".TH \"HELLO\" \"1\"\n.SH NAME\nhello \\- World\n"));
+        assert!(pages[0].1.contains(
+            ".TP\n\\fB\\-o\\fR, \\fB\\-\\-out\\-file\\fR \\fIVALUE\\fR\nWhere\n.IP\n\\&.Really\n"
+        ));
+        assert!(pages[1]
+            .1
+            .contains(".SH \"SEE ALSO\"\n\\fBhello\\fR(1), \\fBhello\\-remote\\-add\\fR(1)\n"));
+
+        let pages = argument_test_obj.render_man_pages(ManLayout::Consolidated);
+        assert_eq!(pages.len(), 1);
+        assert!(pages[0].1.contains(".SH \"HELLO REMOTE ADD\"\n"));
+        assert!(!pages[0].1.contains("SEE ALSO"));
+    }
+
+    // test of "render_man" function
+    #[test]
+    fn render_man() {
+        let mut argument_test_obj = Argument::new("hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose").help("Loud"));
+        argument_test_obj.add(Pos::new("FILE").help("The file"));
+        argument_test_obj.add_exit_status(3, "Bad file");
+        let page = argument_test_obj.render_man();
+        assert!(page.starts_with(".TH \"HELLO\" \"1\"\n.SH NAME\nhello \\- World\n"));
+        assert!(page.contains(".SH DESCRIPTION\n.PP\nWorld\n.PP\nFrom\n"));
+        assert!(page.contains(".SH ARGUMENTS\n.TP\n<FILE>\nThe file\n"));
+        assert!(page.contains(".TP\n\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\nLoud\n"));
+        assert!(page.contains(".SH \"EXIT STATUS\"\n.TP\n3\nBad file\n"));
+        assert!(page.ends_with(".SH AUTHORS\n.PP\nTAAP\n"));
+    }
+
+    // test of "long" and "short" functions
+    #[test]
+    fn long_short() {
+        assert_eq!(
+            Opt::long("foo").short('f').takes(2).help("Two").required(),
+            Opt::new('f', "foo").takes(2).help("Two").required()
+        );
+        assert_eq!(Opt::long("verbose"), Opt::new('-', "verbose"));
+        assert_eq!(
+            Opt::new('v', "verbose").short(' '),
+            Opt::new('-', "verbose")
+        );
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::long("foo").short('f').takes(1));
+        let result_test_obj =
+            argument_test_obj.parse_args(Some(vec!["-f".to_string(), "a".to_string()]));
+        assert_eq!(result_test_obj.get("f").unwrap().1, vec!["a"]);
+    }
+
+    // test of "option" function
+    #[test]
+    fn option_handle() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj
+            .option("foo")
+            .short('f')
+            .takes(2)
+            .help("Two")
+            .required();
+        argument_test_obj
+            .option("token")
+            .takes(1)
+            .with(|option| option.secret());
+        argument_test_obj.option("verbose");
+
+        let mut expected_obj = Argument::new("Hello", "World", "From", "TAAP");
+        expected_obj.add(Opt::new('f', "foo").takes(2).help("Two").required());
+        expected_obj.add(Opt::long("token").takes(1).secret());
+        expected_obj.add(Opt::long("verbose"));
+        assert_eq!(argument_test_obj.options, expected_obj.options);
+    }
+
+    // test of "add_options" and "add_args" functions
+    #[test]
+    fn add_options_args() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_options(
+            ['a', 'b']
+                .iter()
+                .map(|short| Opt::new(*short, "").help("Level")),
+        );
+        argument_test_obj.add_args(["SOURCE", "DEST"].into_iter().map(Pos::new));
+        let mut expected_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        expected_test_obj.add(Opt::new('a', "").help("Level"));
+        expected_test_obj.add(Opt::new('b', "").help("Level"));
+        expected_test_obj.add(Pos::new("SOURCE"));
+        expected_test_obj.add(Pos::new("DEST"));
+        assert_eq!(argument_test_obj, expected_test_obj);
+    }
+
+    // test of "add_audit_hook" function
+    #[test]
+    fn audit_hooks() {
+        use crate::Source;
+        use std::sync::{Arc, Mutex};
+        let records: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(vec![]));
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('f', "force"));
+        argument_test_obj.add(Opt::new('q', "quiet"));
+        argument_test_obj.add(Opt::new('-', "level").takes(1).default("1"));
+        argument_test_obj.add(Pos::new("FILES").infinite());
+        let recorded = Arc::clone(&records);
+        argument_test_obj.add_audit_hook(move |name, values, source| {
+            recorded.lock().unwrap().push(format!(
+                "{} {:?} {}",
+                name,
+                values,
+                source == &Source::Default
+            ));
+        });
+        argument_test_obj.parse_args(Some(vec!["-f".to_string()]));
+        assert_eq!(
+            *records.lock().unwrap(),
+            ["f [] false", "level [\"1\"] true"]
+        );
+    }
+
+    // test of "parse_bootstrap" function
+    #[test]
+    fn parse_bootstrap() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('c', "config").takes(1).required());
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        argument_test_obj.add_arg("FILE", "1", None);
+        let tokens: Vec<String> = ["--plugin-option", "x", "-h", "file", "-c", "app.toml"]
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        let bootstrap = argument_test_obj.parse_bootstrap(&["config"], Some(tokens.clone()));
+        assert_eq!(
+            bootstrap.get("c").unwrap(),
+            &(true, vec!["app.toml".to_string()])
+        );
+        assert!(bootstrap.get("v").is_none());
+        assert!(bootstrap.get("FILE").is_none());
+
+        // the options registered after the first stage are part of the full parse
+        argument_test_obj.add(Opt::new('-', "plugin-option").takes(1));
+        let mut tokens = tokens;
+        tokens.remove(2);
+        let result_test_obj = argument_test_obj.parse_args(Some(tokens));
+        assert_eq!(result_test_obj.get("plugin-option").unwrap().1, ["x"]);
+        assert_eq!(result_test_obj.get("FILE").unwrap().1, ["file"]);
+
+        // the options the first stage doesn't know aren't typos of the ones it does
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('-', "host").takes(1));
+        argument_test_obj.add(Opt::new('-', "port").takes(1));
+        let tokens = vec!["--port".to_string(), "80".to_string()];
+        let matches = crate::output::transcribe(|| {
+            Ok(argument_test_obj.parse_bootstrap(&["host"], Some(tokens.clone())))
+        });
+        assert!(matches.warnings.is_empty());
+        assert!(matches.output.is_empty());
+        let mut first_stage = Argument::new_raw("Hello", "World", "From", "TAAP");
+        first_stage.add(Opt::new('-', "host").takes(1));
+        assert_eq!(
+            first_stage.match_tokens(tokens).warnings,
+            ["Skipped the unknown option --port, did you mean \"--host\"?"]
+        );
+    }
+
+    // test of "add_file_expansion" function
+    #[test]
+    fn file_expansion() {
+        let list_path = std::env::temp_dir().join("taap-file-expansion-test.txt");
+        std::fs::write(&list_path, "b.txt\n\nc.txt\n").unwrap();
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.add_arg("FILE", "+", None);
+        argument_test_obj.add_option('T', "files-from", "1", None);
+        argument_test_obj.add_file_expansion("FILE", Some("files-from"));
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            "a.txt".to_string(),
+            "--files-from".to_string(),
+            list_path.to_string_lossy().into_owned(),
+        ]));
+
+        assert_eq!(
+            result_test_obj.get("FILE").unwrap().1,
+            vec!["a.txt", "b.txt", "c.txt"]
+        );
+    }
+
+    // test of "set_limits" function
+    #[test]
+    fn limits() {
+        use crate::{ErrorKind, Limits};
+
+        let list_path = std::env::temp_dir().join(format!("taap-limits-{}", std::process::id()));
+        std::fs::write(&list_path, "b.txt\nc.txt\nd.txt\n").unwrap();
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_arg("FILE", "+", None);
+        argument_test_obj.add_option('T', "files-from", "1", None);
+        argument_test_obj.add_file_expansion("FILE", Some("files-from"));
+        argument_test_obj.set_limits(Limits {
+            max_tokens: Some(3),
+            max_value_length: Some(100),
+            max_expansion: Some(3),
+        });
+        let error = |argument_test_obj: &mut Argument, list: Vec<String>| {
+            argument_test_obj.try_parse_args(Some(list)).unwrap_err()
+        };
+
+        // right at the limits still parses
+        let result_test_obj = argument_test_obj
+            .try_parse_args(Some(vec!["a".repeat(100), "b".to_string()]))
+            .unwrap();
+        assert_eq!(result_test_obj.get("FILE").unwrap().1.len(), 2);
+
+        let err = error(
+            &mut argument_test_obj,
+            vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string(),
+            ],
+        );
+        assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+        assert_eq!(err.message(), "Too many arguments (4), the limit is 3");
+
+        let err = error(&mut argument_test_obj, vec!["a".repeat(101)]);
+        assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+        assert!(err
+            .message()
+            .ends_with("is too long (101 bytes), the limit is 100"));
+
+        // the values of the list file count towards the values of the expansion
+        let err = error(
+            &mut argument_test_obj,
+            vec![
+                "a.txt".to_string(),
+                "-T".to_string(),
+                list_path.display().to_string(),
+            ],
+        );
+        assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+        assert_eq!(err.message(), "Expansion produced more than 3 values");
+        std::fs::remove_file(&list_path).unwrap();
+    }
+
+    // test of "set_collect_stats" function
+    #[test]
+    fn stats() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.add_option('f', "foo", "1", None);
+        argument_test_obj.add_option('b', "bar", "0", None);
+        argument_test_obj.add_arg("BAZ", "1", None);
+        assert_eq!(argument_test_obj.stats(), None);
+
+        argument_test_obj.set_collect_stats(true);
+        argument_test_obj.parse_args(Some(vec![
+            "baz".to_string(),
+            "-f".to_string(),
+            "foo".to_string(),
+        ]));
+        let stats = argument_test_obj.stats().unwrap();
+
+        assert_eq!(stats.tokens, 3);
+        assert_eq!(stats.options_matched, 1);
+        assert_eq!(stats.positionals_matched, 1);
+    }
+
+    // test of "parse_flags" function
+    #[test]
+    fn flags() {
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.add_option('f', "foo", "0", None);
+        argument_test_obj.add_option('b', "bar", "0", None);
+        argument_test_obj.add_option('-', "baz", "0", None);
+        argument_test_obj.add_option('q', "qux", "0", None);
+        let result_test_obj = argument_test_obj.parse_flags(["-fx", "--baz", "positional"]);
+
+        assert!(result_test_obj.is_set("f"));
+        assert!(result_test_obj.is_set("foo"));
+        assert!(result_test_obj.is_set("baz"));
+        assert!(!result_test_obj.is_set("b"));
+        assert!(!result_test_obj.is_set("q"));
+        assert!(!result_test_obj.is_set("x"));
+
+        // after -- nothing is a flag
+        let result_test_obj = argument_test_obj.parse_flags(["-f", "--", "-b", "--help"]);
+        assert!(result_test_obj.is_set("f"));
+        assert!(!result_test_obj.is_set("b"));
+    }
+
+    // test of the date/time value parser
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn datetime() {
+        use crate::datetime::parse_datetime_from;
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 0).unwrap();
+
+        assert_eq!(
+            parse_datetime_from("2024-01-31T10:00:00+02:00", now),
+            Ok(Utc.with_ymd_and_hms(2024, 1, 31, 8, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_datetime_from("2024-01-31", now),
+            Ok(Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_datetime_from("2 days ago", now),
+            Ok(Utc.with_ymd_and_hms(2024, 3, 13, 12, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_datetime_from("yesterday", now),
+            Ok(Utc.with_ymd_and_hms(2024, 3, 14, 0, 0, 0).unwrap())
+        );
+        assert!(parse_datetime_from("2 fortnights ago", now).is_err());
+        assert!(parse_datetime_from("31/01/2024", now).is_err());
+    }
+
+    // test of the sanitizing of user input in error messages
+    #[test]
+    fn sanitize() {
+        use crate::sanitize::sanitize;
+
+        assert_eq!(sanitize("plain value"), "plain value");
+        assert_eq!(sanitize("\u{1b}[31mred\u{1b}[0m"), "red");
+        assert_eq!(sanitize("\u{1b}]0;title\u{7}text"), "text");
+        assert_eq!(sanitize("line\nbreak\u{7}"), "line\\nbreak\\u{7}");
+        assert_eq!(
+            sanitize(&"a".repeat(100)),
+            format!("{}... (100 characters in total)", "a".repeat(64))
+        );
+    }
+
+    // test of loading an Argument from data
+    #[cfg(feature = "serde")]
+    #[test]
+    fn definition() {
+        let argument_test_obj: Argument = serde_json::from_str(
+            r#"{
+                "name": "Hello",
+                "description": "World",
+                "credits": "TAAP",
+                "exit_statuses": [[0, "Fine"], [1, "Not fine"]],
+                "options": [
+                    {"short": "p", "long": "port", "values": 1, "default": ["8080"]},
+                    {"long": "mode", "values": "1", "choices": ["fast", "slow"], "global": true}
+                ],
+                "positionals": [{"placeholder": "FILES", "values": "+", "help": "Some files"}],
+                "subcommands": [{"name": "run", "description": "Run it", "options": [{"short": "n"}]}]
+            }"#,
+        )
+        .unwrap();
+
+        let mut expected_test_obj = Argument::new("Hello", "World", "", "TAAP");
+        expected_test_obj.add_exit_status(0, "Fine");
+        expected_test_obj.add_exit_status(1, "Not fine");
+        expected_test_obj.add(Opt::new('p', "port").takes(1).default("8080"));
+        expected_test_obj.add(
+            Opt::new('-', "mode")
+                .takes(1)
+                .choices(["fast", "slow"])
+                .global(),
+        );
+        expected_test_obj.add(crate::Pos::new("FILES").infinite().help("Some files"));
+        expected_test_obj
+            .add_subcommand("run", "Run it")
+            .add(Opt::new('n', ""));
+
+        assert_eq!(argument_test_obj, expected_test_obj);
+        assert!(serde_json::from_str::<Argument>(
+            r#"{"name": "Hello", "options": [{"values": 1}]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Argument>(
+            r#"{"name": "Hello", "positionals": [{"placeholder": "A", "values": "x"}]}"#
+        )
+        .is_err());
+
+        // a definition with problems fails to load instead of panicking once it's parsed
+        let error = |json: &str| {
+            serde_json::from_str::<Argument>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error(r#"{"name": "Hello", "options": [{"long": "all", "enables": ["nope"]}]}"#),
+            "--all enables \"nope\", which isn't a flag"
+        );
+        assert_eq!(
+            error(
+                r#"{"name": "Hello", "subcommands": [{"name": "run", "options": [
+                    {"short": "u", "values": 1, "required_if": [["port", "22"]]}
+                ]}]}"#
+            ),
+            "Hello run: -u is required depending on \"port\", which doesn't exist"
+        );
+        assert_eq!(
+            error(
+                r#"{"name": "Hello", "options": [
+                    {"long": "port", "values": 1}, {"short": "p", "long": "port"}
+                ]}"#
+            ),
+            "--port is defined twice in \"Hello\""
+        );
+    }
+
+    // test of "parse_args_async" function
+    #[cfg(feature = "async")]
+    #[test]
+    fn parse_args_async() {
+        use crate::{AsyncValueSource, BoxFuture, Source};
+        use std::{
+            future::Future,
+            pin::pin,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            task::{Context, Poll, Waker},
+        };
+        // the futures in here are ready straight away, so they don't need a runtime
+        fn block_on<T>(future: impl Future<Output = T>) -> T {
+            let mut future = pin!(future);
+            match future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()))
+            {
+                Poll::Ready(output) => output,
+                Poll::Pending => panic!("the future isn't ready"),
+            }
+        }
+        struct Store;
+        impl AsyncValueSource for Store {
+            fn name(&self) -> String {
+                "the store".to_string()
+            }
+
+            fn fetch<'a>(
+                &'a self,
+                key: &'a str,
+            ) -> BoxFuture<'a, Result<Option<Vec<String>>, String>> {
+                Box::pin(async move { Ok(Some(vec![format!("{}-value", key)])) })
+            }
+        }
+        // a store counting how often it's asked
+        struct Counted(Arc<AtomicUsize>);
+        impl AsyncValueSource for Counted {
+            fn name(&self) -> String {
+                "the counted store".to_string()
+            }
+
+            fn fetch<'a>(
+                &'a self,
+                key: &'a str,
+            ) -> BoxFuture<'a, Result<Option<Vec<String>>, String>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move { Ok(Some(vec![format!("{}-value", key)])) })
+            }
+        }
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('t', "token")
+                .takes(1)
+                .async_value_source(Store, "token"),
+        );
+        argument_test_obj.add(
+            Opt::new('r', "region")
+                .takes(1)
+                .async_choices(|| Box::pin(async { vec!["eu".to_string(), "us".to_string()] })),
+        );
+        let args = |list: &[&str]| Some(list.iter().map(|arg| arg.to_string()).collect());
+
+        let result_test_obj = block_on(argument_test_obj.parse_args_async(args(&["-r", "eu"])));
+        assert_eq!(result_test_obj.get("t").unwrap().1, ["token-value"]);
+        assert_eq!(
+            result_test_obj.source("t"),
+            Some(&Source::External("the store".to_string()))
+        );
+        let result_test_obj = block_on(argument_test_obj.parse_args_async(args(&["--token=x"])));
+        assert_eq!(result_test_obj.get("t").unwrap().1, ["x"]);
+
+        assert_eq!(
+            failure(block_on(crate::asynchronous::parse(
+                &mut argument_test_obj,
+                args(&["-r", "asia"])
+            )))
+            .message(),
+            "Invalid value \"asia\" for -r, expected one of: eu, us"
+        );
+        // the async parts are kept for the next parse, and the blocking parser refuses them
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            argument_test_obj.parse_args(args(&[]))
+        }))
+        .is_err());
+
+        // nothing is fetched for the help, or for an option set by its environment variable
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(
+            Opt::new('t', "token")
+                .takes(1)
+                .env("TAAP_TEST_ASYNC_TOKEN")
+                .async_value_source(Counted(fetched.clone()), "token"),
+        );
+        assert!(matches!(
+            block_on(crate::asynchronous::parse(
+                &mut argument_test_obj,
+                args(&["--help"])
+            )),
+            Err(Stop::Exited(0))
+        ));
+        std::env::set_var("TAAP_TEST_ASYNC_TOKEN", "from-env");
+        let result_test_obj = block_on(argument_test_obj.parse_args_async(args(&[])));
+        std::env::remove_var("TAAP_TEST_ASYNC_TOKEN");
+        assert_eq!(result_test_obj.get("t").unwrap().1, ["from-env"]);
+        assert_eq!(fetched.load(Ordering::SeqCst), 0);
+
+        // the options of the subcommand that's used are awaited as well, the others aren't
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add_subcommand("push", "Push").add(
+            Opt::new('r', "region")
+                .takes(1)
+                .async_choices(|| Box::pin(async { vec!["eu".to_string()] })),
+        );
+        argument_test_obj.add_subcommand("pull", "Pull").add(
+            Opt::new('t', "token")
+                .takes(1)
+                .async_value_source(Counted(fetched.clone()), "token"),
+        );
+        let result_test_obj =
+            block_on(argument_test_obj.parse_args_async(args(&["push", "-r", "eu"])));
+        assert_eq!(
+            result_test_obj.subcommand().unwrap().1.get("r").unwrap().1,
+            ["eu"]
+        );
+        assert_eq!(fetched.load(Ordering::SeqCst), 0);
+        let result_test_obj = block_on(argument_test_obj.parse_args_async(args(&["pull"])));
+        assert_eq!(
+            result_test_obj.subcommand().unwrap().1.get("t").unwrap().1,
+            ["token-value"]
+        );
+        assert_eq!(fetched.load(Ordering::SeqCst), 1);
+    }
+
+    // test of the tracing instrumentation of the parser
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{field, span, Event, Metadata, Subscriber};
+
+        // records the spans entered and the messages of the events
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+        struct Message(String);
+        impl field::Visit for Message {
+            fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{:?}", value);
+                };
+            }
+        }
+        impl Subscriber for Recorder {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                metadata.target() == "taap"
+            }
+            fn new_span(&self, attributes: &span::Attributes<'_>) -> span::Id {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(attributes.metadata().name().to_string());
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+            fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut message = Message(String::new());
+                event.record(&mut message);
+                self.0.lock().unwrap().push(message.0);
+            }
+            fn enter(&self, _span: &span::Id) {}
+            fn exit(&self, _span: &span::Id) {}
+        }
+
+        let recorded = Arc::new(Mutex::new(vec![]));
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+        argument_test_obj.add(Opt::new('v', "verbose"));
+        tracing::subscriber::with_default(Recorder(Arc::clone(&recorded)), || {
+            argument_test_obj.parse_args(Some(vec!["-v".to_string(), "--other".to_string()]));
+        });
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            [
+                "parse",
+                "tokenize",
+                "tokenized the command line",
+                "match",
+                "matched an option",
+                "skipped an unknown option",
+                "validate"
+            ]
+        );
+    }
+
+    // test of "add_file_expansion" function, using glob patterns
+    #[cfg(feature = "glob")]
+    #[test]
+    fn file_expansion_glob() {
+        let directory = std::env::temp_dir().join("taap-file-expansion-glob-test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("a.log"), "").unwrap();
+        std::fs::write(directory.join("b.log"), "").unwrap();
+
+        let mut argument_test_obj = Argument::new("Hello", "World", "From", "TAAP");
+
+        argument_test_obj.add_arg("FILE", "+", None);
+        argument_test_obj.add_file_expansion("FILE", None);
+        let result_test_obj = argument_test_obj.parse_args(Some(vec![
+            directory.join("*.log").to_string_lossy().into_owned(),
+            "missing-*.txt".to_string(),
+        ]));
+
+        assert_eq!(
+            result_test_obj.get("FILE").unwrap().1,
+            vec![
+                directory.join("a.log").to_string_lossy().into_owned(),
+                directory.join("b.log").to_string_lossy().into_owned(),
+                "missing-*.txt".to_string(),
+            ]
+        );
+    }
+}
+
+/// The struct that actually contains all the info, and acts like the container for all commands
+/// needed
+///
+/// The Argument struct contains all info, e.g. the name, the description, all arguments added,
+/// etc.
+/// The Argument implementations are also what we use to create and modify our args!
+///
+/// An example of the Argument struct in use:
+/// ```no_run
+///     let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits and year");
+///     // Add some arguments and options
+///     // ...
+///     let parsed_args = arguments.parse_args(None);
+///     // Do something with the parsed args
+///     // ...
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Argument {
+    name: String,
+    description: Text,
+    exit_statuses: BTreeMap<u16, String>,
+    // keyed by the first and the last status of the range
+    exit_status_ranges: BTreeMap<(u16, u16), String>,
+    exit_status_categories: Vec<ExitStatusCategory>,
+    exit_status_section: ExitStatusSection,
+    // the command and the description of every usage example
+    examples: Vec<(String, String)>,
+    example_shell: ExampleShell,
+    // the sections of the help page after the exit statuses, by title
+    sections: Vec<(String, Text)>,
+    exit_status_format: ExitStatusFormat,
+    epilog: Text,
+    credits: String,
+    options: Vec<OptionSpec>,
+    positionals: Vec<PositionalSpec>,
+    // the ids (indices) of the options, by short and long name
+    shorts: HashMap<char, usize>,
+    longs: HashMap<String, usize>,
+    limits: Limits,
+    collect_stats: bool,
+    stats: Option<ParseStats>,
+    subcommands: Vec<Argument>,
+    // the program run by an external subcommand
+    external: Option<String>,
+    pre_parse_hooks: Vec<PreParseHook>,
+    post_parse_hooks: Vec<PostParseHook>,
+    audit_hooks: Vec<AuditHook>,
+    // the version of the program scripts rely on, see set_compatibility
+    compatibility: Option<String>,
+    // long options that were renamed, see add_renamed
+    renamed: Vec<Renamed>,
+    // groups of arguments (by name) of which only one can be used, see add_exclusive
+    exclusive: Vec<Vec<String>>,
+    profiles_file: Option<String>,
+    options_env: Option<String>,
+    aliases_file: Option<String>,
+    terminator: Option<String>,
+    number_option: Option<String>,
+    placeholders: BTreeMap<String, String>,
+    // the translations of the help, by language
+    translations: BTreeMap<String, locale::Bundle>,
+    language: Option<String>,
+    text_direction: TextDirection,
+    help_style: HelpStyle,
+    arity_notation: ArityNotation,
+    help_annotations: HelpAnnotations,
+    quote_dialect: QuoteDialect,
+    decorations: Decorations,
+    output_format: OutputFormat,
+    format_option: bool,
+    show_config_option: bool,
+    // if unknown options are an error instead of being skipped, see set_strict
+    strict: bool,
+    // if no options are added automatically and help is never printed, see new_raw
+    raw: bool,
+    // if only some of the options are known, so unknown options aren't worth a warning, see
+    // parse_bootstrap
+    bootstrap: bool,
+    // if infinite options leave values to the positional arguments, see set_reserve_positionals
+    reserve_positionals: bool,
+    // the version printed by --version, see set_version
+    version: Option<String>,
+    broken_pipe: BrokenPipe,
+    duplicates: Duplicates,
+}
+
+impl Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{name: {}, description: {}, epilog: {}, credits: {}}}",
+            self.name,
+            self.description.render(),
+            self.epilog.render(),
+            self.credits
+        )
+    }
+}
+
+/// Implementation for Argument struct
+///
+/// Code example available in te top of the documentation, and at the home page
+impl Argument {
+    /// Returns a new Argument instance
+    ///
+    /// A functon called `new` which creates and returns an instance of the Argument struct, with
+    /// the values you input.
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // do something with arguments
+    ///
+    /// ```
+    ///
+    /// | Parameter   | Type | Description                                                          |
+    /// |-------------|------|----------------------------------------------------------------------|
+    /// | name        | &str | The name of the program                                              |
+    /// | description | &str | The description of the program                                       |
+    /// | epilog      | &str | The text at the bottom of the help                                   |
+    /// | credits     | &str | The credits at the bottom of the help (often your name and the year) |
+    ///
+    pub fn new(name: &str, description: &str, epilog: &str, credits: &str) -> Self {
+        let mut argument = Self {
+            name: name.to_string(),
+            description: Text::from(description),
+            epilog: Text::from(epilog),
+            credits: credits.to_string(),
+            ..Default::default()
+        };
+        argument.add_option(
+            'h',
+            "help",
+            "0",
+            Some("Use this to print this help message"),
+        );
+        argument
+    }
+
+    /// Creates a new Argument instance without any options added automatically
+    ///
+    /// A function that works like `new`, for programs that need every argument they're given,
+    /// like proxies handing the command line to another program. No help option is added, and
+    /// the help is never printed while parsing, so "-h" and "--help" are passed through like
+    /// any other unknown option, or can be options of your own (e.g. -h for --host). The help
+    /// can still be shown when your program decides to, using `print_help` or `render_help`.
+    /// Subcommands added to it are raw as well
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new_raw("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(taap::Opt::new('h', "host").takes(1).help("The host to forward to"));
+    /// arguments.add(taap::Pos::new("ARGS").arity(taap::Arity::Infinite).command());
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// if parsed_arguments.get("ARGS").unwrap().1.is_empty() {
+    ///     arguments.print_help();
+    /// }
+    /// ```
+    ///
+    /// | Parameter   | Type | Description                                                          |
+    /// |-------------|------|----------------------------------------------------------------------|
+    /// | name        | &str | The name of the program                                              |
+    /// | description | &str | The description of the program                                       |
+    /// | epilog      | &str | The text at the bottom of the help                                   |
+    /// | credits     | &str | The credits at the bottom of the help (often your name and the year) |
+    ///
+    pub fn new_raw(name: &str, description: &str, epilog: &str, credits: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: Text::from(description),
+            epilog: Text::from(epilog),
+            credits: credits.to_string(),
+            raw: true,
+            ..Default::default()
+        }
+    }
+
+    /// Set a closure producing the description, called only when the help is rendered
+    ///
+    /// A function that takes a closure returning a String, which replaces the description
+    /// passed to `new`
+    ///
+    /// This is useful when the description includes something that takes time to find out, e.g.
+    /// a list of installed plugins, since the work is only done when the help is actually shown
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_description_with(|| {
+    ///     format!("Description, running on {}", std::env::consts::OS)
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type                                 | Description                      |
+    /// |-------------|--------------------------------------|----------------------------------|
+    /// | description | impl Fn() -> String + Send + Sync    | The closure producing the text   |
+    ///
+    pub fn set_description_with(
+        &mut self,
+        description: impl Fn() -> String + Send + Sync + 'static,
+    ) {
+        self.description = Text::lazy(description);
+    }
+
+    /// Set a closure producing the epilog, called only when the help is rendered
+    ///
+    /// A function that takes a closure returning a String, which replaces the epilog passed to
+    /// `new`
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "", "Credits");
+    /// arguments.set_epilog_with(|| {
+    ///     let config = std::env::var("HOME").unwrap_or_default() + "/.config/name";
+    ///     format!("The configuration is read from {}", config)
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type                                 | Description                      |
+    /// |-----------|--------------------------------------|----------------------------------|
+    /// | epilog    | impl Fn() -> String + Send + Sync    | The closure producing the text   |
+    ///
+    pub fn set_epilog_with(&mut self, epilog: impl Fn() -> String + Send + Sync + 'static) {
+        self.epilog = Text::lazy(epilog);
+    }
+
+    /// Add a section to the help page, like the sections of a man page
+    ///
+    /// A function that takes a title and a text, and adds them as a section of the help page
+    /// after the exit statuses and before the epilog, e.g. to describe the environment
+    /// variables or the files the program uses. The sections are shown in the order they were
+    /// added, and the lines of the text are kept as they are. Adding a section with the title
+    /// of another one replaces its text
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_section("Environment", "NAME_HOME\tWhere the data is stored");
+    /// arguments.add_section("Files", "~/.config/name/config.toml\tThe configuration");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description              |
+    /// |-----------|------|--------------------------|
+    /// | title     | &str | The title of the section |
+    /// | text      | &str | The text of the section  |
+    ///
+    pub fn add_section(&mut self, title: &str, text: &str) {
+        match self.sections.iter_mut().find(|(other, _)| other == title) {
+            Some((_, existing)) => *existing = Text::from(text),
+            None => self.sections.push((title.to_string(), Text::from(text))),
+        };
+    }
+
+    /// Make arguments exclusive, so only one of them can be used at a time
+    ///
+    /// A function that takes the names of options and positional arguments, e.g. FILE and
+    /// --stdin for a program reading either files or stdin. Parsing fails if more than one of
+    /// them is used, where an option with a default only counts when it's actually used, and a
+    /// positional argument when it gets values. A positional argument missing its values is
+    /// fine when an option of the group is used, so FILE doesn't have to be given with --stdin.
+    /// An argument can be part of several groups
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt, Pos};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Pos::new("FILE").help("The file to read"));
+    /// arguments.add(Opt::new('-', "stdin").help("Read from stdin instead"));
+    /// arguments.add_exclusive(["FILE", "stdin"]);
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type                                   | Description                                       |
+    /// |-----------|----------------------------------------|---------------------------------------------------|
+    /// | names     | impl IntoIterator\<Item: AsRef\<str\>\> | The names of the options and positional arguments |
+    ///
+    pub fn add_exclusive<I, S>(&mut self, names: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.exclusive.push(
+            names
+                .into_iter()
+                .map(|name| name.as_ref().to_string())
+                .collect(),
+        );
+    }
+
+    /// Accept the old name of a renamed long option
+    ///
+    /// A function for renaming an option without breaking the scripts using its old name. The
+    /// old name is still accepted, with a warning that it's deprecated, and it's used like the
+    /// new option, which can be given by any of its names. The old name isn't shown in the help
+    /// page, the completion scripts or the suggestions for misspelled options. Use
+    /// `add_renamed_fn` if the values changed as well
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('-', "color").takes(1));
+    /// // "--colour always" is used like "--color always"
+    /// arguments.add_renamed("colour", "color");
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type | Description                           |
+    /// |-----------|------|---------------------------------------|
+    /// | old       | &str | The old long name, with or without -- |
+    /// | new       | &str | A name of the option it's now         |
+    ///
+    pub fn add_renamed(&mut self, old: &str, new: &str) {
+        self.renamed.push(Renamed::new(old, new, None));
+    }
+
+    /// Accept the old name of a renamed long option, with values that have to be translated
+    ///
+    /// A function that works just like `add_renamed`, but passes every value given to the old
+    /// name through a function, turning it into a value of the new option
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('-', "color").takes(1).choices(["always", "auto", "never"]));
+    /// // --colour took "yes" and "no"
+    /// arguments.add_renamed_fn("colour", "color", |value| match value {
+    ///     "yes" => "always".to_string(),
+    ///     "no" => "never".to_string(),
+    ///     value => value.to_string(),
+    /// });
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type                | Description                                    |
+    /// |-----------|---------------------|------------------------------------------------|
+    /// | old       | &str                | The old long name, with or without --          |
+    /// | new       | &str                | A name of the option it's now                  |
+    /// | map       | Fn(&str) -\> String | Turns a value of the old option into a new one |
+    ///
+    pub fn add_renamed_fn(
+        &mut self,
+        old: &str,
+        new: &str,
+        map: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) {
+        self.renamed
+            .push(Renamed::new(old, new, Some(renamed::value_map(map))));
+    }
+
+    /// Set the version of the program that scripts using it rely on
+    ///
+    /// A function that sets the compatibility level, e.g. from an environment variable set by
+    /// the scripts. Using an option added after this version (see `OptionSpec::since`), or one
+    /// removed in this version or before it (see `OptionSpec::removed`), prints a warning, so
+    /// the scripts can be checked against the version they were written for. Subcommands use the
+    /// level of their parent, unless they have one of their own
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('j', "jobs").takes(1).since("2.1"));
+    /// if let Ok(level) = std::env::var("NAME_COMPAT") {
+    ///     arguments.set_compatibility(&level);
+    /// }
+    /// // with NAME_COMPAT=2.0, using --jobs warns that it was added in 2.1
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type | Description                              |
+    /// |-----------|------|------------------------------------------|
+    /// | version   | &str | The version the scripts were written for |
+    ///
+    pub fn set_compatibility(&mut self, version: &str) {
+        self.compatibility = Some(version.to_string());
+    }
+
+    /// Returns the arguments added or removed after a version, one per line
+    ///
+    /// A function that lists the arguments with a `since` or `removed` version newer than the
+    /// given one, oldest first, as "VERSION\tNAME was added" or "VERSION\tNAME was removed". It's
+    /// meant for a "What's New" section of the help page, or for release notes
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('j', "jobs").takes(1).since("2.1"));
+    /// arguments.add(Opt::new('-', "legacy").removed("2.0"));
+    /// let whats_new = arguments.whats_new("1.0");
+    /// arguments.add_section("What's New", &whats_new);
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type | Description                            |
+    /// |-----------|------|----------------------------------------|
+    /// | after     | &str | The version the changes are newer than |
+    ///
+    pub fn whats_new(&self, after: &str) -> String {
+        version::whats_new(self, after)
+    }
+
+    /// Add a subcommand, and return it so arguments can be added to it
+    ///
+    /// A function that adds a subcommand, which is an Argument of its own. When the subcommand
+    /// is used, the rest of the command line is parsed as the subcommand, and its result can be
+    /// found using `ParsedArgs::subcommand`. Subcommands can have subcommands too.
+    ///
+    /// Both `mytool help remote add` and `mytool remote add --help` print the help of the
+    /// subcommand, which includes the global options (see `OptionSpec::global`) of its parents,
+    /// and has the full path in its usage line
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('v', "verbose").help("Print more").global());
+    /// // add a subcommand, and a subcommand to it
+    /// let remote = arguments.add_subcommand("remote", "Manage remotes");
+    /// let add = remote.add_subcommand("add", "Add a remote");
+    /// add.add_arg("NAME", "1", Some("The name of the remote"));
+    ///
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter   | Type | Description                       |
+    /// |-------------|------|-----------------------------------|
+    /// | name        | &str | The name of the subcommand        |
+    /// | description | &str | The description of the subcommand |
+    ///
+    pub fn add_subcommand(&mut self, name: &str, description: &str) -> &mut Argument {
+        let subcommand = match self.raw {
+            true => Argument::new_raw(name, description, "", &self.credits),
+            false => Argument::new(name, description, "", &self.credits),
+        };
+        match self.subcommands.iter().position(|sub| sub.name == name) {
+            Some(id) => {
+                self.subcommands[id] = subcommand;
+                &mut self.subcommands[id]
+            }
+            None => {
+                self.subcommands.push(subcommand);
+                self.subcommands.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Add an external subcommand, which runs another program
+    ///
+    /// A function that adds a subcommand which runs another program, like git runs git-NAME
+    /// for commands it doesn't have itself. It's listed with the other subcommands, and when
+    /// it's used the rest of the command line isn't parsed, but handed to the program as it was
+    /// given (see `ParsedArgs::external`). A subcommand with the same name is replaced
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_external_subcommand("deploy", "Deploy the project", "/usr/libexec/mytool/deploy");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type | Description                      |
+    /// |-------------|------|-----------------------------------|
+    /// | name        | &str | The name of the subcommand        |
+    /// | description | &str | The description of the subcommand |
+    /// | program     | &str | The path of the program to run    |
+    ///
+    pub fn add_external_subcommand(&mut self, name: &str, description: &str, program: &str) {
+        self.add_subcommand(name, description).external = Some(program.to_string());
+    }
+
+    /// Add the external subcommands found on the PATH, i.e. the executables starting with a prefix
+    ///
+    /// A function that looks for executables named PREFIXNAME (e.g. "mytool-deploy" for the
+    /// prefix "mytool-") in the directories of the PATH environment variable, and adds each of
+    /// them as the external subcommand NAME (see `add_external_subcommand`), so they're listed on
+    /// the help page and suggested for typos like any other subcommand. The subcommands that were
+    /// already added take precedence over the ones found
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_subcommand("init", "Create a new project");
+    /// arguments.discover_external_subcommands("mytool-");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                 |
+    /// |-----------|------|---------------------------------------------|
+    /// | prefix    | &str | The start of the names of the executables   |
+    ///
+    pub fn discover_external_subcommands(&mut self, prefix: &str) {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        self.add_external_subcommands(prefix, external::discover(prefix, &path));
+    }
+
+    // Adds the external subcommands that don't have the name of a subcommand yet
+    fn add_external_subcommands(
+        &mut self,
+        prefix: &str,
+        found: BTreeMap<String, std::path::PathBuf>,
+    ) {
+        for (name, program) in found {
+            if !self.subcommands.iter().any(|sub| sub.name == name) {
+                self.add_external_subcommand(
+                    &name,
+                    &format!("Run {}{}", prefix, name),
+                    &program.to_string_lossy(),
+                );
+            };
+        }
+    }
+
+    /// Add an exit status to the help page
+    ///
+    /// A function that takes an u16 and a &str as input and adds it to the help page as an exit
+    /// status
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add our exit status, first the code, then the help text
+    /// arguments.add_exit_status(0, "Everything went well!");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | code      | u16  | The exit code                                          |
+    /// | help      | &str | The help message on the help page fot that exit status |
+    ///
+    pub fn add_exit_status(&mut self, code: u16, help: &str) {
+        self.exit_statuses.insert(code, help.to_string());
+    }
+
+    /// Add a range of exit statuses to the help page
+    ///
+    /// A function that takes the first and the last code of the range (both included) and a &str,
+    /// and adds it to the help page as one exit status, e.g. "64-78"
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_exit_status(0, "Everything went well!");
+    /// // Add a range of exit statuses, following sysexits.h
+    /// arguments.add_exit_status_range(64, 78, "Usage and system errors");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                          |
+    /// |-----------|------|------------------------------------------------------|
+    /// | first     | u16  | The first exit code of the range                     |
+    /// | last      | u16  | The last exit code of the range                      |
+    /// | help      | &str | The help message on the help page for the exit codes |
+    ///
+    pub fn add_exit_status_range(&mut self, first: u16, last: u16, help: &str) {
+        exit_status::check_range(first, last);
+        self.exit_status_ranges
+            .insert((first, last), help.to_string());
+    }
+
+    /// Add a category of exit statuses to the help page
+    ///
+    /// A function that takes an ExitStatusCategory, a title with exit statuses and ranges of its
+    /// own, and adds it to the help page below the other exit statuses
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::ExitStatusCategory;
+    ///
+    /// fn main() {
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_exit_status(0, "Everything went well!");
+    /// arguments.add_exit_status_category(
+    ///     ExitStatusCategory::new("Network errors")
+    ///         .status(10, "The host couldn't be reached")
+    ///         .range(11, 19, "The connection was interrupted"),
+    /// );
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type                | Description                          |
+    /// |-----------|---------------------|--------------------------------------|
+    /// | category  | ExitStatusCategory  | The category to add to the help page |
+    ///
+    pub fn add_exit_status_category(&mut self, category: ExitStatusCategory) {
+        self.exit_status_categories.push(category);
+    }
+
+    /// Add a usage example to the help page
+    ///
+    /// A function that takes a command and a description, and adds them to the Examples section
+    /// of the help page. The command is written once and rendered for the shell of the platform
+    /// (see `set_example_shell`): every "\n" starts a new line, joined to the previous one using
+    /// the line continuation of the shell, and {/} is the path separator. The other placeholders
+    /// (see `set_placeholder`), like {bin}, work as well
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // "name --config config/app.toml \" and "    --verbose" in a POSIX shell
+    /// arguments.add_example("{bin} --config config{/}app.toml\n    --verbose", "Run with a config file");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type | Description                           |
+    /// |-------------|------|---------------------------------------|
+    /// | command     | &str | The command, with one line per "\n"   |
+    /// | description | &str | What the command does                 |
+    ///
+    pub fn add_example(&mut self, command: &str, description: &str) {
+        self.examples
+            .push((command.to_string(), description.to_string()));
+    }
+
+    /// Add a positional argument
+    ///
+    /// A function that takes a placeholder &str, the amount of arguments as a &str, and a help
+    /// &str of the type Option<&str>
+    ///
+    /// The reason for the amount of args being a &str is because it doesn't only take positive
+    /// integers, it can also take "+" as an amount of arguments.
+    /// The "+" is equal to an unspecified amount of arguments.
+    ///
+    /// The last argument is an Option<&str> because it's optional, which means you can pass None
+    /// if you don't want a help text for the argument
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add a positonal argument to the Argument instance
+    /// arguments.add_arg("BAR", "1", Some("Some Help"));
+    /// // Add another positional argument, but this time it's "infinite"
+    /// arguments.add_arg("FOO", "+", None);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type         | Description                                                         |
+    /// |-------------|--------------|---------------------------------------------------------------------|
+    /// | placeholder | &str         | The placeholder of the positional argument, meant for the help page |
+    /// | args        | &str         | The amount of arguments, can either be a positive integer or a "+"  |
+    /// | help        | Option<&str> | The help text, can either be None or Some(&str)                     |
+    ///
+    pub fn add_arg(&mut self, placeholder: &str, args: &str, help: Option<&str>) {
+        let arity = match Arity::parse(args) {
+            Some(arity) => arity,
+            None => {
+                panic!("Error! \"args\" parameter must be either a positive integer, 0 or +");
+            }
+        };
+        self.add(
+            PositionalSpec::new(placeholder.to_string())
+                .arity(arity)
+                .help(help.unwrap_or_default().to_string()),
+        );
+    }
+
+    /// Add an optional argument
+    ///
+    /// A function that takes a short name as a char, a long name as a &str, the amount of arguments as a &str,
+    /// and a help &str of the type Option<&str>
+    ///
+    /// The short name can be a space (' ') or a dash ('-') if you only want a long name
+    ///
+    /// The long name can be an empty str (""), a space (" ") or a single/double dash ("-"/"--")
+    /// if you only want a short name
+    ///
+    /// The reason for the amount of args being a &str is because it doesn't only take positive
+    /// integers, it can also take "+" as an amount of arguments.
+    /// The "+" is equal to an unspecified amount of arguments.
+    ///
+    /// The last argument is an Option<&str> because it's optional, which means you can pass None
+    /// if you don't want a help text for the argument
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add some optional arguments
+    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
+    /// arguments.add_option('-', "boo", "2", Some("I only have a long name"));
+    /// arguments.add_option('a', "-", "0", Some("I only have a short name"));
+    /// arguments.add_option('n', "no-help", "0", None);
+    ///
+    ///
+    /// // More code...
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type         | Description                                                        |
+    /// |-----------|--------------|--------------------------------------------------------------------|
+    /// | short     | char         | The short name of the optional argument                            |
+    /// | long      | &str         | The long name of the optional argument
+    /// | args      | &str         | The amount of arguments, can either be a positive integer or a "+" |
+    /// | help      | Option<&str> | The help text, can either be None or Some(&str)                    |
+    ///
+    pub fn add_option(&mut self, short: char, long: &str, parameters: &str, help: Option<&str>) {
+        let arity = match Arity::parse(parameters) {
+            Some(arity) => arity,
+            None => {
+                panic!("Error! \"parameters\" parameter must be either a positive integer, 0 or +");
+            }
+        };
+        self.add(
+            OptionSpec::new(short, long.to_string())
+                .arity(arity)
+                .help(help.unwrap_or_default().to_string()),
+        );
+    }
+
+    /// Add an optional or a positional argument, built using chained function calls
+    ///
+    /// A function that takes an OptionSpec (also called Opt) or a PositionalSpec (also called
+    /// Pos), and adds it to the Argument instance
+    ///
+    /// This is the same as `add_option` and `add_arg`, but the builders make it easy to see what
+    /// every value means, and allow more settings, e.g. making an option required
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt, Pos};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add some optional arguments
+    /// arguments.add(Opt::new('f', "foo").takes(2).help("I take two values!").required());
+    /// arguments.add(Opt::new('-', "boo").infinite().help("I only have a long name"));
+    /// // Add a positional argument
+    /// arguments.add(Pos::new("BAR").help("Some help"));
+    ///
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type                | Description                                     |
+    /// |-----------|---------------------|-------------------------------------------------|
+    /// | spec      | impl Into\<ArgSpec\> | The optional or positional argument to be added |
+    ///
+    pub fn add(&mut self, spec: impl Into<ArgSpec>) {
+        match spec.into() {
+            ArgSpec::Option(option) => self.insert_option(option),
+            ArgSpec::Positional(positional) => self.insert_positional(positional),
+        };
+    }
+
+    /// Add an optional argument by its long name, setting the rest on the returned handle
+    ///
+    /// A function that works like `add` with `Opt::long`, but the option is defined in place:
+    /// every setting is chained to the handle, and the option is added once the statement ends
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.option("foo").short('f').takes(2).help("I take two values!").required();
+    /// arguments.option("verbose").short('v').help("Print more details");
+    ///
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                            |
+    /// |-----------|------|----------------------------------------|
+    /// | long      | &str | The long name of the optional argument |
+    ///
+    pub fn option(&mut self, long: &str) -> OptionHandle<'_> {
+        OptionHandle::new(self, long)
+    }
+
+    /// Add all optional arguments of an OptionSet
+    ///
+    /// A function that adds every option in the set, just like calling `add` for each of them.
+    /// The same set can be added to several Arguments and subcommands
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt, OptionSet};
+    ///
+    /// fn main() {
+    /// let connection: OptionSet = vec![
+    ///     Opt::new('H', "host").takes(1).help("The host to connect to"),
+    ///     Opt::new('p', "port").takes(1).help("The port to connect to"),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option_set(&connection);
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type       | Description               |
+    /// |-----------|------------|---------------------------|
+    /// | set       | &OptionSet | The set of options to add |
+    ///
+    pub fn add_option_set(&mut self, set: &OptionSet) {
+        for option in set.options.iter() {
+            self.insert_option(option.clone());
+        }
+    }
+
+    /// Add several optional arguments at once
+    ///
+    /// A function that adds every option of an iterator, just like calling `add` for each of
+    /// them, e.g. for a large command line defined by a table or by generated code
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// let levels = [('1', "fast"), ('9', "best")];
+    /// arguments.add_options(
+    ///     levels
+    ///         .into_iter()
+    ///         .map(|(short, long)| Opt::new(short, long).help("Set the compression level")),
+    /// );
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type                                   | Description        |
+    /// |-----------|----------------------------------------|--------------------|
+    /// | options   | impl IntoIterator\<Item = OptionSpec\> | The options to add |
+    ///
+    pub fn add_options(&mut self, options: impl IntoIterator<Item = OptionSpec>) {
+        for option in options {
+            self.insert_option(option);
+        }
+    }
+
+    /// Add several positional arguments at once
+    ///
+    /// A function that adds every positional argument of an iterator, in order, just like
+    /// calling `add` for each of them
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Pos};
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_args(["SOURCE", "DEST"].into_iter().map(Pos::new));
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter   | Type                                       | Description                     |
+    /// |-------------|--------------------------------------------|---------------------------------|
+    /// | positionals | impl IntoIterator\<Item = PositionalSpec\> | The positional arguments to add |
+    ///
+    pub fn add_args(&mut self, positionals: impl IntoIterator<Item = PositionalSpec>) {
+        for positional in positionals {
+            self.insert_positional(positional);
+        }
+    }
+
+    /// Merge the arguments of another Argument into this one
+    ///
+    /// A function that adds the optional and positional arguments, and the exit statuses, of
+    /// another Argument, e.g. one provided by a library crate, to this one. Arguments that are
+    /// exactly the same in both (like the built-in help option) are only kept once.
+    ///
+    /// The name, description, epilog, credits and subcommands of the other Argument are ignored.
+    ///
+    /// This function panics if an argument or an exit status of the other Argument conflicts
+    /// with one of this Argument, i.e. it reuses a name, a placeholder or an exit code for
+    /// something else
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// // in a library crate
+    /// fn telemetry_arguments() -> Argument {
+    ///     let mut arguments = Argument::new("telemetry", "", "", "");
+    ///     arguments.add(Opt::new('-', "no-telemetry").help("Don't send any usage statistics"));
+    ///     arguments
+    /// }
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.merge(&telemetry_arguments());
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type      | Description                     |
+    /// |-----------|-----------|---------------------------------|
+    /// | other     | &Argument | The Argument to merge into this |
+    ///
+    pub fn merge(&mut self, other: &Argument) {
+        for option in other.options.iter() {
+            let existing = option
+                .short
+                .and_then(|short| self.shorts.get(&short))
+                .into_iter()
+                .chain(option.long.as_deref().and_then(|long| self.longs.get(long)))
+                .map(|id| &self.options[*id])
+                .find(|existing| *existing != option);
+            if let Some(existing) = existing {
+                panic!(
+                    "Error! Can't merge \"{}\", it conflicts with \"{}\"",
+                    option.display_name(),
+                    existing.display_name()
+                );
+            };
+        }
+        for positional in other.positionals.iter() {
+            if self.positionals.iter().any(|existing| {
+                existing.placeholder == positional.placeholder && existing != positional
+            }) {
+                panic!(
+                    "Error! Can't merge \"{}\", the placeholder is already used",
+                    positional.placeholder
+                );
+            };
+        }
+        for (code, help) in other.exit_statuses.iter() {
+            if self
+                .exit_statuses
+                .get(code)
+                .is_some_and(|existing| existing != help)
+            {
+                panic!(
+                    "Error! Can't merge the exit status {}, it's already used",
+                    code
+                );
+            };
+        }
+
+        for option in other.options.iter() {
+            self.insert_option(option.clone());
+        }
+        for positional in other.positionals.iter() {
+            self.insert_positional(positional.clone());
+        }
+        self.exit_statuses.extend(other.exit_statuses.clone());
+        self.exit_status_ranges
+            .extend(other.exit_status_ranges.clone());
+        for category in other.exit_status_categories.iter() {
+            if !self.exit_status_categories.contains(category) {
+                self.exit_status_categories.push(category.clone());
+            };
+        }
+    }
+
+    /// Merge the arguments of another Argument into this one, prefixing its options
+    ///
+    /// Works like `merge`, but every optional argument of the other Argument is moved into a
+    /// namespace first (see `OptionSet::prefixed`), e.g. "--endpoint" becomes
+    /// "--telemetry-endpoint" and is stored under "telemetry-endpoint" in the parse result.
+    /// The built-in help option isn't prefixed, and positional arguments are merged as they are
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// let mut telemetry = Argument::new("telemetry", "", "", "");
+    /// telemetry.add(Opt::new('-', "endpoint").takes(1).help("Where to send usage statistics"));
+    ///
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // adds --telemetry-endpoint
+    /// arguments.merge_prefixed(&telemetry, "telemetry");
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type      | Description                               |
+    /// |-----------|-----------|-------------------------------------------|
+    /// | other     | &Argument | The Argument to merge into this           |
+    /// | prefix    | &str      | The prefix of the options of the Argument |
+    ///
+    pub fn merge_prefixed(&mut self, other: &Argument, prefix: &str) {
+        let mut prefixed = Argument {
+            options: vec![],
+            shorts: HashMap::new(),
+            longs: HashMap::new(),
+            ..other.clone()
+        };
+        let help = self.option_id("h").map(|id| &self.options[id]);
+        for option in other.options.iter() {
+            match help == Some(option) {
+                true => prefixed.insert_option(option.clone()),
+                false => prefixed.insert_option(option.prefixed(prefix)),
+            };
+        }
+        self.merge(&prefixed);
+    }
+
+    /// Turn an infinite positional argument into an expanded file list
+    ///
+    /// A function that takes the placeholder of an already added infinite ("+") positional
+    /// argument, and the name of an optional argument of the type Option<&str>
+    ///
+    /// When parsing, every value of the positional argument is expanded as a glob pattern
+    /// (only when the "glob" feature is enabled), just like a shell would. Patterns without any
+    /// matches are kept as they are.
+    ///
+    /// If you pass the name of an optional argument (the short or the long name), every file
+    /// passed to that option is read, and each non-empty line is added as another path. Passing
+    /// "-" to the option reads the list from stdin instead.
+    ///
+    /// Everything ends up in one single list, stored under the placeholder of the positional
+    /// argument
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add an infinite positional argument and an option taking a file
+    /// arguments.add_arg("FILE", "+", Some("The files to process"));
+    /// arguments.add_option('T', "files-from", "1", Some("Read more files from this list"));
+    /// // Expand FILE using globs and the lists passed to --files-from
+    /// arguments.add_file_expansion("FILE", Some("files-from"));
+    ///
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// // Every path is now available in one list
+    /// let files = &parsed_arguments.get("FILE").unwrap().1;
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type         | Description                                                   |
+    /// |-------------|--------------|---------------------------------------------------------------|
+    /// | placeholder | &str         | The placeholder of an infinite positional argument            |
+    /// | files_from  | Option<&str> | The name of an option taking file lists, can either be None or Some(&str) |
+    ///
+    pub fn add_file_expansion(&mut self, placeholder: &str, files_from: Option<&str>) {
+        let files_from = files_from.map(|option| match self.option_id(option) {
+            Some(id) => self.options[id].key(),
+            None => panic!("Error! Option \"{}\" doesn't exist", option),
+        });
+        match self
+            .positionals
+            .iter_mut()
+            .find(|positional| positional.placeholder == placeholder)
+        {
+            Some(positional) if positional.arity == Arity::Infinite => {
+                positional.file_expansion = Some(FileExpansion { files_from });
+            }
+            Some(_) => panic!(
+                "Error! Positional argument \"{}\" must take an unspecified amount of arguments (+) to be expanded",
+                placeholder
+            ),
+            None => panic!("Error! Positional argument \"{}\" doesn't exist", placeholder),
+        };
+    }
+
+    /// Add a hook transforming the command line before it's parsed
+    ///
+    /// A function that takes a closure, which gets the command line arguments (without the name
+    /// of the program) and returns the arguments to parse instead. Hooks are run in the order
+    /// they were added, each getting the result of the previous one
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('v', "verbose", "0", None);
+    /// // treat the old "-verbose" spelling as "--verbose"
+    /// arguments.add_pre_parse_hook(|arguments| {
+    ///     arguments
+    ///         .into_iter()
+    ///         .map(|argument| if argument == "-verbose" { "--verbose".to_string() } else { argument })
+    ///         .collect()
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type                                                    | Description                            |
+    /// |-----------|---------------------------------------------------------|----------------------------------------|
+    /// | hook      | impl Fn(Vec\<String\>) -> Vec\<String\> + Send + Sync | The closure transforming the arguments |
+    ///
+    pub fn add_pre_parse_hook(
+        &mut self,
+        hook: impl Fn(Vec<String>) -> Vec<String> + Send + Sync + 'static,
+    ) {
+        self.pre_parse_hooks.push(Callback(Arc::new(hook)));
+    }
+
+    /// Add a hook inspecting or adjusting the result after parsing
+    ///
+    /// A function that takes a closure, which gets mutable access to the result of parse_args
+    /// before it's returned. Hooks are run in the order they were added
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('v', "verbose", "0", None);
+    /// arguments.add_option('d', "debug", "0", None);
+    /// // --debug implies --verbose
+    /// arguments.add_post_parse_hook(|parsed| {
+    ///     if parsed.get("d").unwrap().0 {
+    ///         parsed.get_mut("v").unwrap().0 = true;
+    ///     }
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type                                   | Description                      |
+    /// |-----------|----------------------------------------|----------------------------------|
+    /// | hook      | impl Fn(&mut ParsedArgs) + Send + Sync | The closure adjusting the result |
+    ///
+    pub fn add_post_parse_hook(&mut self, hook: impl Fn(&mut ParsedArgs) + Send + Sync + 'static) {
+        self.post_parse_hooks.push(Callback(Arc::new(hook)));
+    }
+
+    /// Add a hook called once for every argument that was used, e.g. to write an audit record
+    ///
+    /// A function that takes a closure, which gets the name, the values and the source of every
+    /// argument that was used, after parsing is done and the post-parse hooks have run. The
+    /// arguments come in the order they were added. Options filled in by their default are
+    /// passed as well, with `Source::Default`, so the record shows every value in effect.
+    /// Subcommands have hooks of their own
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('f', "force", "0", None);
+    /// arguments.add_arg("FILE", "1", None);
+    /// arguments.add_audit_hook(|name, values, source| {
+    ///     eprintln!("audit: {} = {:?} (from {:?})", name, values, source);
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type                                              | Description                    |
+    /// |-----------|---------------------------------------------------|--------------------------------|
+    /// | hook      | impl Fn(&str, &\[String\], &Source) + Send + Sync | The closure recording the uses |
+    ///
+    pub fn add_audit_hook(
+        &mut self,
+        hook: impl Fn(&str, &[String], &Source) + Send + Sync + 'static,
+    ) {
+        self.audit_hooks.push(Callback(Arc::new(hook)));
+    }
+
+    /// Add a plugin
+    ///
+    /// A function that lets the plugin add its options and hooks to the Argument, see `Plugin`
+    ///
+    /// | Parameter | Type        | Description       |
+    /// |-----------|-------------|-------------------|
+    /// | plugin    | impl Plugin | The plugin to add |
+    ///
+    pub fn add_plugin(&mut self, plugin: impl Plugin) {
+        plugin.register(self);
+    }
+
+    /// Enable profiles, named bundles of arguments loaded from a file with --profile NAME
+    ///
+    /// A function that adds the global option --profile, which takes the name of a profile in
+    /// the given file and loads its arguments. The file is only read when --profile is used
+    ///
+    /// The file is made of sections, each starting with the name of a profile in brackets,
+    /// followed by the arguments of the profile, split like a shell would (so 'a b' is a single
+    /// argument). Empty lines and lines starting with # are ignored. A profile can load other
+    /// profiles using --profile, as long as it doesn't end up loading itself:
+    /// ```text
+    /// [base]
+    /// --host example.com
+    ///
+    /// [prod]
+    /// --profile base
+    /// --port 443
+    /// ```
+    ///
+    /// The arguments of a profile are placed before the rest of the command line, so arguments
+    /// passed directly always take precedence. `ParsedArgs::source` tells if a value came from
+    /// a profile. Profiles are loaded by the Argument the file is set on, also when --profile is
+    /// used after a subcommand, and subcommands don't load them again. A --profile after "--",
+    /// the terminator or in a command tail belongs to another program, so it isn't loaded
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('-', "host", "1", Some("The host to connect to"));
+    /// arguments.add_option('p', "port", "1", Some("The port to connect to"));
+    /// arguments.set_profiles_file("/etc/name/profiles");
+    ///
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// if let Some(taap::Source::Profile(name)) = parsed_arguments.source("host") {
+    ///     println!("Using the host from the profile {}", name);
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type | Description                        |
+    /// |-----------|------|------------------------------------|
+    /// | path      | &str | The path of the file with profiles |
+    ///
+    pub fn set_profiles_file(&mut self, path: &str) {
+        self.profiles_file = Some(path.to_string());
+        self.add(
+            Opt::new('-', profiles::PROFILE_OPTION)
+                .takes(1)
+                .help(format!("Load the arguments of a profile from {}", path))
+                .global(),
+        );
+    }
+
+    /// Enable aliases defined by the end user in a file, like the aliases of git
+    ///
+    /// A function that makes the parser read aliases from the given file. When the first
+    /// argument is the name of an alias, it's replaced by the arguments the alias expands to
+    /// before parsing. An alias can expand to another alias, as long as it doesn't end up
+    /// expanding to itself. Aliases can't replace subcommands, and a missing file simply means
+    /// there are no aliases
+    ///
+    /// The file has one alias per line, written as the name of the alias, an equals sign and
+    /// the arguments it expands to, separated by whitespace and quoted like in a shell. Empty
+    /// lines and lines starting with # are ignored:
+    /// ```text
+    /// st = status -s
+    /// sst = st --short-branch
+    /// wip = commit -m 'work in progress'
+    /// ```
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_subcommand("status", "Show the status").add_option('s', "short", "0", None);
+    /// let config = std::env::var("HOME").unwrap_or_default() + "/.config/name/aliases";
+    /// arguments.set_aliases_file(&config);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                       |
+    /// |-----------|------|-----------------------------------|
+    /// | path      | &str | The path of the file with aliases |
+    ///
+    pub fn set_aliases_file(&mut self, path: &str) {
+        self.aliases_file = Some(path.to_string());
+    }
+
+    /// Make -NUMBER a shorthand for an option taking a number, like "head -5"
+    ///
+    /// A function that makes the parser read a dash followed by digits (e.g. -5) as the given
+    /// option with the digits as its value (--lines 5), which is how many classic Unix tools
+    /// work. The option has to take exactly one value. This takes precedence over short options
+    /// that are digits, and over negative numbers
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('n', "lines", "1", Some("The amount of lines to print, or -NUMBER"));
+    /// arguments.set_number_option("lines");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                          |
+    /// |-----------|------|------------------------------------------------------|
+    /// | option    | &str | The name of an option taking one value (key or long) |
+    ///
+    pub fn set_number_option(&mut self, option: &str) {
+        match self.option_id(option) {
+            Some(id) if self.options[id].arity == Arity::Exactly(1) => {
+                self.number_option = Some(self.options[id].key());
+            }
+            Some(_) => panic!("Error! Option \"{}\" must take exactly one value", option),
+            None => panic!("Error! Option \"{}\" doesn't exist", option),
+        };
+    }
+
+    /// Returns the values an option accepts, e.g. for a completion script
+    ///
+    /// A function that returns the choices of an option (see `OptionSpec::choices` and
+    /// `OptionSpec::choices_with`), calling the closure of dynamic choices, so the result always
+    /// reflects the current state of the system. Returns None if the option doesn't exist or
+    /// accepts any value
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('d', "device").takes(1).choices_with(|| {
+    ///     std::fs::read_dir("/sys/class/net")
+    ///         .map(|entries| {
+    ///             entries
+    ///                 .filter_map(|entry| entry.ok())
+    ///                 .map(|entry| entry.file_name().to_string_lossy().into_owned())
+    ///                 .collect()
+    ///         })
+    ///         .unwrap_or_default()
+    /// }));
+    ///
+    /// // print the candidates for the completion of --device
+    /// for device in arguments.option_choices("device").unwrap_or_default() {
+    ///     println!("{}", device);
+    /// }
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                   |
+    /// |-----------|------|-----------------------------------------------|
+    /// | name      | &str | The name of the option (its key or long name) |
+    ///
+    pub fn option_choices(&self, name: &str) -> Option<Vec<String>> {
+        let option = &self.options[self.option_id(name)?];
+        option
+            .choices
+            .as_ref()
+            .map(|choices| choices.resolve().into_owned())
+    }
+
+    /// Set a token that stops the parsing, like the ";" of find -exec
+    ///
+    /// A function that sets an additional terminator besides "--": everything after it is left
+    /// unparsed and returned as it is by `ParsedArgs::rest`. This is useful for tools wrapping
+    /// other commands, like clones of timeout or env. The terminator only counts as a token of
+    /// its own, and also ends infinite arguments and the positional arguments after "--"
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('t', "timeout", "1", Some("Seconds to wait"));
+    /// arguments.set_terminator("---");
+    ///
+    /// // name -t 5 --- some-command --its-own-option
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// let command = parsed_arguments.rest();
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter  | Type | Description                      |
+    /// |------------|------|----------------------------------|
+    /// | terminator | &str | The token that stops the parsing |
+    ///
+    pub fn set_terminator(&mut self, terminator: &str) {
+        if terminator.is_empty() || terminator == "--" {
+            panic!("Error! The terminator can't be empty or \"--\"");
+        };
+        self.terminator = Some(terminator.to_string());
+    }
+
+    /// Set limits for the input the parser accepts
+    ///
+    /// A function that takes a Limits struct, containing the maximum amount of arguments, the
+    /// maximum length of a single argument and the maximum amount of values an expansion (e.g.
+    /// a file list) may produce
+    ///
+    /// Every limit is unlimited by default. When a limit is exceeded while parsing, a clear error
+    /// is printed and the program exits with status 1
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Only accept up to 100 arguments, each at most 1024 bytes long
+    /// arguments.set_limits(taap::Limits {
+    ///     max_tokens: Some(100),
+    ///     max_value_length: Some(1024),
+    ///     max_expansion: None,
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type   | Description                         |
+    /// |-----------|--------|-------------------------------------|
+    /// | limits    | Limits | The limits to enforce while parsing |
+    ///
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Enable or disable the collection of parse statistics
+    ///
+    /// A function that takes a bool, which decides if statistics (the amount of arguments, the
+    /// options matched, an estimate of the allocations made and the time it took) are collected
+    /// when parsing
+    ///
+    /// The statistics of the last parse can be retrieved using the `stats` function
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_collect_stats(true);
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// // Print how long it took to parse the arguments
+    /// let stats = arguments.stats().unwrap();
+    /// eprintln!("Parsed {} arguments in {:?}", stats.tokens, stats.elapsed);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                  |
+    /// |-----------|------|----------------------------------------------|
+    /// | collect   | bool | If statistics should be collected or not     |
+    ///
+    pub fn set_collect_stats(&mut self, collect: bool) {
+        self.collect_stats = collect;
+    }
+
+    /// Returns the statistics of the last parse
+    ///
+    /// Returns None if the collection of statistics isn't enabled (see `set_collect_stats`), or
+    /// if no arguments have been parsed yet
+    pub fn stats(&self) -> Option<&ParseStats> {
+        self.stats.as_ref()
+    }
+
+    /// Set a placeholder that's replaced in the help texts, like {version}
+    ///
+    /// A function that defines a placeholder, which is replaced by its value when the help is
+    /// rendered, in the description, the epilog, the help texts of the arguments and the
+    /// descriptions of subcommands and exit statuses. This keeps the help accurate when e.g. the
+    /// version changes, and lets help texts be shared between tools. There are also two built-in
+    /// placeholders: {bin} is the name of the program, and {default} is the default of the
+    /// option whose help text it's in. Placeholders that aren't defined are left as they are
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "{bin} {version}, does things", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_placeholder("version", env!("CARGO_PKG_VERSION"));
+    /// arguments.add(taap::Opt::new('p', "port").takes(1).default("8080").help("The port, {default} if not given"));
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                             |
+    /// |-----------|------|-----------------------------------------|
+    /// | name      | &str | The name of the placeholder, without {} |
+    /// | value     | &str | What the placeholder is replaced by     |
+    ///
+    pub fn set_placeholder(&mut self, name: &str, value: &str) {
+        self.placeholders
+            .insert(name.to_string(), value.to_string());
+    }
+
+    /// Write the Markdown help into a section of a file, e.g. the usage section of a README
+    ///
+    /// A function that replaces everything between the lines `<!-- taap:SECTION:start -->` and
+    /// `<!-- taap:SECTION:end -->` in the file with the Markdown help, so the documentation
+    /// stays in sync with the program. Returns true if the file changed, which a CI job can use
+    /// to check that the documentation is up to date. The file must already contain the
+    /// markers. See the gen-docs example for a program doing this
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
+    ///
+    /// if arguments.update_help_section("README.md", "usage").unwrap() {
+    ///     println!("Updated the usage in README.md");
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type               | Description                                  |
+    /// |-----------|--------------------|----------------------------------------------|
+    /// | path      | impl AsRef\<Path\> | The file containing the section              |
+    /// | section   | &str               | The name of the section, used in the markers |
+    ///
+    pub fn update_help_section(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        section: &str,
+    ) -> std::io::Result<bool> {
+        docs::update_section(
+            path.as_ref(),
+            section,
+            &self.render_help(OutputFormat::Markdown),
+        )
+    }
+
+    /// Add the translations of the help page to a language
+    ///
+    /// A function that takes the name of a language (e.g. "de" or "pt_BR") and a bundle of
+    /// translations, usually embedded in the program using include_str!. When the help is
+    /// printed, the language is chosen using the environment variables LC_ALL, LC_MESSAGES or
+    /// LANG (see `set_language`), falling back to the untranslated texts.
+    ///
+    /// The bundle has one translation per line, written as "key = text". Empty lines and lines
+    /// starting with # are skipped, and "\\n" starts a new line. The keys are:
+    /// - "description" and "epilog"
+    /// - "option.NAME" for the help of an option, using its long name (or its short name if it
+    ///   has no long name)
+    /// - "positional.PLACEHOLDER" for the help of a positional argument
+    /// - "subcommand.NAME" for the description of a subcommand in the list of subcommands
+    /// - "exit-status.CODE" for an exit status, or e.g. "exit-status.64-78" for a range, using
+    ///   decimal codes
+    /// - "exit-category.TITLE" for the title of an exit status category
+    /// - "section.TITLE" for the text of a section added using `add_section`
+    ///
+    /// The keys of a subcommand start with its name, e.g. "remote.option.verbose". This function
+    /// panics if a line isn't a translation
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('v', "verbose", "0", Some("Print more"));
+    /// // usually include_str!("../i18n/de.txt")
+    /// arguments.add_translations("de", "description = Beschreibung\noption.verbose = Mehr ausgeben");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                 |
+    /// |-----------|------|---------------------------------------------|
+    /// | language  | &str | The language of the translations            |
+    /// | bundle    | &str | The translations, one "key = text" per line |
+    ///
+    pub fn add_translations(&mut self, language: &str, bundle: &str) {
+        self.translations
+            .entry(language.to_string())
+            .or_default()
+            .extend(locale::parse_bundle(language, bundle));
+    }
+
+    /// Set the language of the help page
+    ///
+    /// A function that chooses the language of the translations added using
+    /// `add_translations`, instead of using the environment variables LC_ALL, LC_MESSAGES or
+    /// LANG. A language like "de_AT" falls back to "de" if there are no translations for it
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_translations("de", "description = Beschreibung");
+    /// arguments.set_language("de");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description              |
+    /// |-----------|------|--------------------------|
+    /// | language  | &str | The language of the help |
+    ///
+    pub fn set_language(&mut self, language: &str) {
+        self.language = Some(language.to_string());
+    }
+
+    /// Set the direction the help page is laid out in
+    ///
+    /// A function that decides if the plain help page is laid out from left to right, or from
+    /// right to left (see `TextDirection`). By default it's right to left if the language used
+    /// for the translations is written from right to left, like Arabic or Hebrew
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_text_direction(taap::TextDirection::LeftToRight);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type          | Description                    |
+    /// |-----------|---------------|--------------------------------|
+    /// | direction | TextDirection | The direction of the help page    |
+    ///
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.text_direction = direction;
+    }
+
+    /// Set the style of the help page
+    ///
+    /// A function that selects how the help page is rendered, which can be decided at runtime.
+    /// The compact style only has the usage line and one line per argument, using nothing but
+    /// ASCII and spaces, for primitive terminals (see `HelpStyle`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_help_style(taap::HelpStyle::Compact);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type      | Description                |
+    /// |-----------|-----------|----------------------------|
+    /// | style     | HelpStyle | The style of the help page |
+    ///
+    pub fn set_help_style(&mut self, style: HelpStyle) {
+        self.help_style = style;
+    }
+
+    /// Set how the amount of values of the positional arguments is shown in the help
+    ///
+    /// A function that selects between the default notation of man pages, like \<FILE\>
+    /// \<FILE\>, with the arguments that can go without values in square brackets, and the
+    /// amount after the name, like FILE*2 (see `ArityNotation`). Subcommands use the same
+    /// notation
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_arity_notation(taap::ArityNotation::Stars);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type          | Description                         |
+    /// |-----------|---------------|-------------------------------------|
+    /// | notation  | ArityNotation | How the amount of values is written |
+    ///
+    pub fn set_arity_notation(&mut self, notation: ArityNotation) {
+        self.arity_notation = notation;
+    }
+
+    /// Set the quoting rules `parse_args_str` splits command lines by
+    ///
+    /// A function that selects between the rules of a POSIX shell (the default), the ones of
+    /// Windows programs and the ones of PowerShell, so programs accepting command lines as a
+    /// string split them the way their users expect (see `QuoteDialect`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_quote_dialect(taap::QuoteDialect::Windows);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type         | Description                          |
+    /// |-----------|--------------|--------------------------------------|
+    /// | dialect   | QuoteDialect | The rules command lines are split by |
+    ///
+    pub fn set_quote_dialect(&mut self, dialect: QuoteDialect) {
+        self.quote_dialect = dialect;
+    }
+
+    /// Set which annotations are shown after the help text of the options
+    ///
+    /// A function that selects what the help page shows next to every option, like its default
+    /// and if it's required ("\[default: 8080\] (required)"), so users can see at a glance what
+    /// happens when they leave an option out. Every annotation is off by default (see
+    /// `HelpAnnotations`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_help_annotations(taap::HelpAnnotations {
+    ///     defaults: true,
+    ///     required: true,
+    ///     ..Default::default()
+    /// });
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type            | Description                 |
+    /// |-------------|-----------------|-----------------------------|
+    /// | annotations | HelpAnnotations | The annotations to be shown |
+    ///
+    pub fn set_help_annotations(&mut self, annotations: HelpAnnotations) {
+        self.help_annotations = annotations;
+    }
+
+    /// Set when the help page keeps its decorations, like colors in the help texts
+    ///
+    /// A function that decides if ANSI escape sequences (e.g. colors) in the help are printed.
+    /// By default they're only printed when the standard output is a terminal, so piping the
+    /// help into another program always gives plain text (see `Decorations`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_decorations(taap::Decorations::Never);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter   | Type        | Description                      |
+    /// |-------------|-------------|----------------------------------|
+    /// | decorations | Decorations | When the decorations are printed |
+    ///
+    pub fn set_decorations(&mut self, decorations: Decorations) {
+        self.decorations = decorations;
+    }
+
+    /// Set when the help page shows the Exit Statuses section
+    ///
+    /// A function that decides if the exit statuses are shown on the help page. By default they're
+    /// shown as soon as anything besides a lone exit status 0 is registered (see
+    /// `ExitStatusSection`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_exit_status(0, "Everything went well!");
+    /// arguments.set_exit_status_section(taap::ExitStatusSection::Always);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type              | Description               |
+    /// |-----------|-------------------|---------------------------|
+    /// | section   | ExitStatusSection | When the section is shown       |
+    ///
+    pub fn set_exit_status_section(&mut self, section: ExitStatusSection) {
+        self.exit_status_section = section;
+    }
+
+    /// Set the shell the usage examples are rendered for
+    ///
+    /// A function that chooses how the examples added using `add_example` are rendered. By
+    /// default they're PowerShell commands on Windows and POSIX shell commands everywhere else
+    /// (see `ExampleShell`). The subcommands use the same shell
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_example_shell(taap::ExampleShell::Cmd);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type         | Description                             |
+    /// |-----------|--------------|-----------------------------------------|
+    /// | shell     | ExampleShell | The shell the examples are written for  |
+    ///
+    pub fn set_example_shell(&mut self, shell: ExampleShell) {
+        self.example_shell = shell;
+    }
+
+    /// Set how the codes of the exit statuses are written on the help page
+    ///
+    /// A function that chooses between decimal codes (the default) and hexadecimal codes like
+    /// "0x41", which are easier to read for exit statuses made of bit flags. The subcommands use
+    /// the same format
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_exit_status(0x41, "The device is busy and locked");
+    /// arguments.set_exit_status_format(taap::ExitStatusFormat::Hexadecimal);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type             | Description                   |
+    /// |-----------|------------------|-------------------------------|
+    /// | format    | ExitStatusFormat | How the codes are written     |
+    ///
+    pub fn set_exit_status_format(&mut self, format: ExitStatusFormat) {
+        self.exit_status_format = format;
+    }
+
+    /// Set the environment variable holding arguments for every run of the program
+    ///
+    /// A function that takes the name of an environment variable (like GREP_OPTIONS or
+    /// RUSTFLAGS), whose contents are added in front of the command line when parsing, so the
+    /// command line overrides them. The contents are split into arguments like a POSIX shell
+    /// would do it (using quotes and backslashes), without expanding anything.
+    /// `ParsedArgs::source` returns `Source::Environment` for the arguments coming from it.
+    ///
+    /// Profiles (see `set_profiles_file`) can't be loaded from the environment variable
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('c', "color", "1", Some("When to use colors"));
+    /// // MYAPP_OPTS="--color never" is the same as adding "--color never" to the command line
+    /// arguments.set_options_env("MYAPP_OPTS");
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                          |
+    /// |-----------|------|--------------------------------------|
+    /// | variable  | &str | The name of the environment variable |
+    ///
+    pub fn set_options_env(&mut self, variable: &str) {
+        self.options_env = Some(variable.to_string());
+    }
+
+    /// Set the format of the help page and the error messages
+    ///
+    /// A function that selects if the help page and the error messages printed while parsing are
+    /// plain text (the default), Markdown or JSON. Errors in JSON are printed as
+    /// `{"error": "message"}`
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_output_format(taap::OutputFormat::Json);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type         | Description              |
+    /// |-----------|--------------|--------------------------|
+    /// | format    | OutputFormat | The format of the output |
+    ///
+    pub fn set_output_format(&mut self, format: OutputFormat) {
+        self.output_format = format;
+    }
+
+    /// Set what happens when an option is used more than once
+    ///
+    /// A function that decides what happens when the same option is used more than once on the
+    /// command line (or in the same profile or environment variable). By default the last use
+    /// silently replaces the earlier ones, but the earlier use can also be kept, or it can be
+    /// reported as a warning or an error (see `Duplicates`). Single options can override it
+    /// using `OptionSpec::duplicates`, and the subcommands use the same policy
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_duplicates(taap::Duplicates::Error);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter  | Type       | Description                                   |
+    /// |------------|------------|-----------------------------------------------|
+    /// | duplicates | Duplicates | What happens when an option is used twice     |
+    ///
+    pub fn set_duplicates(&mut self, duplicates: Duplicates) {
+        self.duplicates = duplicates;
+    }
+
+    /// Set if options that don't exist are an error
+    ///
+    /// A function that turns options that don't exist into an error like "Unknown option
+    /// --verbos, did you mean "--verbose"?" (see `ErrorKind::UnknownOption`), so typos aren't
+    /// silently ignored. By default they're skipped, which lets programs pass options on to
+    /// other programs. Subcommands of a strict Argument are strict as well
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_strict(true);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                            |
+    /// |-----------|------|----------------------------------------|
+    /// | strict    | bool | If unknown options are an error or not |
+    ///
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Set if options taking an infinite amount of values leave values to the positional
+    /// arguments
+    ///
+    /// A function that makes an option taking an infinite amount of values ("+") leave the last
+    /// values of the command line to the positional arguments that are still missing them, so
+    /// e.g. `name -i a b c FILE` gives -i the values a, b and c, and FILE the value FILE. By
+    /// default the option takes every value up to the next argument starting with -, so FILE
+    /// would be missing. This only happens when the values of the option reach the end of the
+    /// command line, and the subcommands do the same
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('i', "include", "+", Some("The directories to search"));
+    /// arguments.add_arg("FILE", "1", Some("The file to compile"));
+    /// arguments.set_reserve_positionals(true);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                     |
+    /// |-----------|------|-------------------------------------------------|
+    /// | reserve   | bool | If the positional arguments get the last values |
+    ///
+    pub fn set_reserve_positionals(&mut self, reserve: bool) {
+        self.reserve_positionals = reserve;
+    }
+
+    /// Set what happens when the help or an error can't be printed because the pipe is closed
+    ///
+    /// A function that decides what happens when e.g. `name --help | head -1` closes the pipe
+    /// early. By default the rest of the output is dropped, but the program can also exit with
+    /// status 141 like a program killed by SIGPIPE (see `BrokenPipe`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_broken_pipe(taap::BrokenPipe::Exit);
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type       | Description                          |
+    /// |-----------|------------|--------------------------------------|
+    /// | policy    | BrokenPipe | What happens when the pipe is closed |
+    ///
+    pub fn set_broken_pipe(&mut self, policy: BrokenPipe) {
+        self.broken_pipe = policy;
+    }
+
+    /// Add the option --format, letting the user choose the format of the help and the errors
+    ///
+    /// A function that adds the global option --format, which takes "plain", "markdown" or
+    /// "json", so e.g. `name --help --format=json` prints the help page as JSON. The value
+    /// overrides the format set with `set_output_format`
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_format_option();
+    /// // ...
+    /// ```
+    ///
+    pub fn add_format_option(&mut self) {
+        self.format_option = true;
+        self.add(
+            Opt::new('-', output::FORMAT_OPTION)
+                .takes(1)
+                .help("The format of the help and the errors: plain, markdown or json")
+                .global(),
+        );
+    }
+
+    /// Checks the whole definition for mistakes, returning every problem found
+    ///
+    /// A function that checks the Argument and all of its subcommands for mistakes that would
+    /// only show up when the program is used, like two options using the same name, an option
+    /// and a positional argument stored under the same name, a positional argument that can
+    /// never get any values, or a condition (see `OptionSpec::required_if`) naming an argument
+    /// that doesn't exist. See `debug_assert` for using it in a test
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_arg("FILES", "+", Some("The files"));
+    /// arguments.add_arg("OUTPUT", "1", Some("Never gets a value"));
+    ///
+    /// if let Err(problems) = arguments.validate() {
+    ///     for problem in problems {
+    ///         eprintln!("{}", problem);
+    ///     }
+    /// };
+    /// // ...
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let problems = validate::problems(self);
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(problems),
+        }
+    }
+
+    /// Panics if the definition has any problems, listing all of them
+    ///
+    /// A function meant to be called from a unit test of your program, so mistakes in the
+    /// definition are caught before it's released (see `validate`)
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// fn arguments() -> taap::Argument {
+    ///     let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    ///     // ...
+    ///     arguments
+    /// }
+    ///
+    /// #[test]
+    /// fn valid_arguments() {
+    ///     arguments().debug_assert();
+    /// }
+    /// ```
+    ///
+    pub fn debug_assert(&self) {
+        if let Err(problems) = self.validate() {
+            panic!(
+                "Error! The definition of \"{}\" has problems:\n{}",
+                self.name,
+                problems.join("\n")
+            );
+        };
+    }
+
+    /// Add the option --show-effective-config, printing the final value of every argument
+    ///
+    /// A function that adds the global option --show-effective-config. When it's used, the
+    /// program prints every argument with its value after parsing, and where the value came from
+    /// (the command line, a profile, the environment variable set with `set_options_env`, or the
+    /// default), and exits. It's printed as a table, or as JSON or Markdown in the format of the
+    /// help page. Missing required arguments aren't an error when the option is used, which
+    /// makes it handy for users asking for support
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_show_config_option();
+    /// // ...
+    /// ```
+    ///
+    pub fn add_show_config_option(&mut self) {
+        self.show_config_option = true;
+        self.add(
+            Opt::new('-', show_config::SHOW_CONFIG_OPTION)
+                .help("Print the value of every argument and where it came from, then exit")
+                .short_circuit()
+                .global(),
+        );
+    }
+
+    /// Set the version of the program, adding the option -V/--version that prints it
+    ///
+    /// A function that adds the option --version, which prints the name and the version of the
+    /// program (like "Name 1.2.3") and exits, just like --help prints the help page. It's also
+    /// -V, unless -V is already taken. The version is used for the {version} placeholder as well,
+    /// unless that's set with `set_placeholder`. Missing required arguments aren't an error when
+    /// --version is used
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.set_version(env!("CARGO_PKG_VERSION"));
+    /// // ...
+    /// ```
+    ///
+    /// | Parameter | Type | Description                |
+    /// |-----------|------|----------------------------|
+    /// | version   | &str | The version of the program |
+    ///
+    pub fn set_version(&mut self, version: &str) {
+        if self.version.is_none() {
+            let short = match self.shorts.contains_key(&'V') {
+                true => '-',
+                false => 'V',
+            };
+            self.add(
+                Opt::new(short, "version")
+                    .help("Print the version and exit")
+                    .short_circuit(),
+            );
+        };
+        // the placeholder follows the version, unless it was set to something else
+        if self.placeholders.get("version") == self.version.as_ref() {
+            self.set_placeholder("version", version);
+        };
+        self.version = Some(version.to_string());
+    }
+
+    /// Prints the help page for your program
+    ///
+    /// Call this function to print the help page for your program.
+    ///
+    /// The function takes no arguments
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add some optional arguments
+    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
+    /// arguments.add_option('-', "boo", "2", Some("I only have a long name"));
+    /// arguments.add_option('a', "-", "0", Some("I only have a short name"));
+    /// arguments.add_option('n', "no-help", "0", None);
+    ///
+    /// // print the help
+    /// arguments.print_help();
+    /// ```
+    ///
+    /// Most of the time printing the help manually is unnecessesary since the program already
+    /// adds the optional argument 'h' and "help" automatically
+    ///
+    pub fn print_help(&self) {
+        output::set_broken_pipe(self.broken_pipe);
+        help::print(self, self.output_format);
+        if output::pipe_closed() {
+            std::process::exit(141);
+        };
+    }
+
+    /// Returns the help page for your program in the given format
+    ///
+    /// A function that renders the help page as plain text (in the style set with
+    /// `set_help_style`), Markdown or JSON, e.g. to generate documentation or to hand it to
+    /// another program. All formats show the same information
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
+    ///
+    /// std::fs::write("USAGE.md", arguments.render_help(taap::OutputFormat::Markdown)).unwrap();
+    /// ```
+    ///
+    /// | Parameter | Type         | Description                 |
+    /// |-----------|--------------|-----------------------------|
+    /// | format    | OutputFormat | The format of the help page |
+    ///
+    pub fn render_help(&self, format: OutputFormat) -> String {
+        help::render(self, format)
+    }
+
+    /// Returns the help page with only the entries mentioning a word, in the given format
+    ///
+    /// A function that renders the help page like `render_help`, but only keeps the arguments,
+    /// subcommands, examples, exit statuses and sections whose names or texts contain the query,
+    /// ignoring case. This is what `mytool --help output` prints
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('o', "output", "1", Some("Where to write the result"));
+    ///
+    /// println!("{}", arguments.render_help_matching(taap::OutputFormat::Plain, "output"));
+    /// ```
+    ///
+    /// | Parameter | Type         | Description                       |
+    /// |-----------|--------------|-----------------------------------|
+    /// | format    | OutputFormat | The format of the help page       |
+    /// | query     | &str         | The word the entries must contain |
+    ///
+    pub fn render_help_matching(&self, format: OutputFormat, query: &str) -> String {
+        help::render_matching(self, format, query).0
+    }
+
+    /// Returns the man pages of the program, as the file names and the contents (in roff)
+    ///
+    /// A function that renders the same information as the help page as man pages, meant to be
+    /// written to files by a build script or a release task. The program can be described on a
+    /// single page, or with a page for every subcommand (see `ManLayout`). Man pages are in
+    /// section 1
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_subcommand("add", "Add a file");
+    ///
+    /// // writes mytool.1 and mytool-add.1
+    /// for (file_name, page) in arguments.render_man_pages(taap::ManLayout::PerSubcommand) {
+    ///     std::fs::write(file_name, page).unwrap();
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type      | Description                      |
+    /// |-----------|-----------|----------------------------------|
+    /// | layout    | ManLayout | How the subcommands are laid out |
+    ///
+    pub fn render_man_pages(&self, layout: ManLayout) -> Vec<(String, String)> {
+        man::pages(self, layout)
+    }
+
+    /// Returns the man page of the program (in roff)
+    ///
+    /// A function that renders the program as a single man page, with the name, description,
+    /// synopsis, positional arguments, options, subcommands, exit statuses, epilog and credits,
+    /// like `render_man_pages` using `ManLayout::Consolidated`. Since it's rendered from the same
+    /// Argument the program parses with, it can be called from a build script (build.rs) that
+    /// shares the function defining the arguments with the program
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // in build.rs, this function would come from a file shared with the program, e.g. using
+    /// // #[path = "src/cli.rs"] mod cli;
+    /// fn arguments() -> taap::Argument {
+    ///     let mut arguments = taap::Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    ///     arguments.add(taap::Opt::new('v', "verbose").help("Tell what's going on"));
+    ///     arguments
+    /// }
+    ///
+    /// fn main() {
+    /// let out_dir = std::env::var("OUT_DIR").unwrap();
+    /// let page = arguments().render_man();
+    /// std::fs::write(std::path::Path::new(&out_dir).join("mytool.1"), page).unwrap();
+    /// }
+    /// ```
+    ///
+    pub fn render_man(&self) -> String {
+        man::pages(self, ManLayout::Consolidated).remove(0).1
+    }
+
+    /// Parses a command line containing only flags, without allocating
+    ///
+    /// A function that takes anything iterable over strings (e.g. `std::env::args().skip(1)`,
+    /// or a slice of &str), and returns a Flags struct telling which flags were used
+    ///
+    /// This is a fast path for tools where every argument is a flag taking no parameters, and
+    /// which don't have any positional arguments. Tools that are run millions of times, e.g. in
+    /// shell loops, can use it to avoid the allocations parse_args makes for its result. Calling
+    /// it on any other kind of Argument panics.
+    ///
+    /// Unknown flags are skipped, just like with parse_args, and the help is printed when 'h'
+    /// or "help" is used. The arguments after "--" aren't flags, so they're skipped as well.
+    /// Statistics are not collected on this path.
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add some flags
+    /// arguments.add_option('v', "verbose", "0", Some("Print more"));
+    /// arguments.add_option('q', "quiet", "0", Some("Print less"));
+    ///
+    /// // Parse the flags, skipping the name of the program
+    /// let flags = arguments.parse_flags(std::env::args().skip(1));
+    /// if flags.is_set("v") {
+    ///     println!("Verbose mode enabled!");
+    /// };
+    /// ```
+    ///
+    /// | Parameter | Type                                 | Description                |
+    /// |-----------|--------------------------------------|----------------------------|
+    /// | arglist   | impl IntoIterator\<Item: AsRef\<str\>\> | The arguments to be parsed |
+    ///
+    pub fn parse_flags<I, S>(&self, arglist: I) -> Flags<'_>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        output::set_broken_pipe(self.broken_pipe);
+        let flags = flags::parse_flags(self, arglist);
+        if output::pipe_closed() {
+            output::exit(Stop::Exited(141));
+        };
+        flags.unwrap_or_else(|stop| output::exit(stop))
+    }
+
+    /// Returns a ParsedArgs containing the parsed arguments
+    ///
+    /// A function that takes an Option\<Vec\<String\>\> value, parses arguments passed to the program and
+    /// returns a ParsedArgs, which can be used like a BTreeMap\<String, (bool, Vec\<String\>)\>
+    /// containing the parsed arguments, and also holds the result of the subcommand used (if any)
+    ///
+    /// The untouched command line is kept as well, see `ParsedArgs::raw_args`
+    ///
+    /// | Parameter      | Type                | Description                                                              |
+    /// |----------------|---------------------|--------------------------------------------------------------------------|
+    /// | custom_arglist | Option\<Vec\<String\>\> | A custom argument-list you can use instead of the command line arguments |
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// // Add a positonal argument
+    /// arguments.add_arg("BAR", "0", None);
+    /// // Add some optional arguments
+    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
+    /// arguments.add_option('-', "boo", "2", Some("I only have a long name"));
+    /// arguments.add_option('a', "-", "0", Some("I only have a short name"));
+    /// arguments.add_option('n', "no-help", "0", None);
+    ///
+    /// // Now parse the arguments and save the result in a variable
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// // Do something with the parsed arguments
+    /// // ...
+    /// ```
+    ///
+    pub fn parse_args(&mut self, custom_arglist: Option<Vec<String>>) -> ParsedArgs {
+        self.run(custom_arglist)
+            .unwrap_or_else(|stop| output::exit(stop))
     }
-}
 
-/// Implementation for Argument struct
-///
-/// Code example available in te top of the documentation, and at the home page
-impl Argument {
-    /// Returns a new Argument instance
+    /// Returns a ParsedArgs containing the parsed arguments, splitting a command line given as
+    /// a single string into arguments first
     ///
-    /// A functon called `new` which creates and returns an instance of the Argument struct, with
-    /// the values you input.
+    /// A function that works just like `parse_args`, for command lines typed into an
+    /// interactive shell or an editor of your program. The line is split the way a POSIX shell
+    /// does it, without any expansions: arguments are separated by whitespace, single quotes keep
+    /// everything literal, and a backslash escapes the next character (inside double quotes only
+    /// ", \\, $ and `). Quoted words with spaces stay a single argument, also as the value of an
+    /// option. `set_quote_dialect` switches to the rules of Windows programs or PowerShell. A
+    /// quote that isn't closed is an error telling the column it's at. `ParsedArgs::spans` tells
+    /// where every argument is in the line, e.g. for highlighting
+    ///
+    /// | Parameter | Type | Description                                |
+    /// |-----------|------|--------------------------------------------|
+    /// | line      | &str | The command line, without the program name |
     ///
     /// Code Example:
     /// ```no_run
-    /// fn main () {
+    /// // first initialize a new Argument instance using the "new" function
     /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
-    /// // do something with arguments
-    /// 
-    /// }
-    /// ```
+    /// arguments.add_arg("FILE", "1", None);
     ///
-    /// | Parameter   | Type | Description                                                          |
-    /// |-------------|------|----------------------------------------------------------------------|
-    /// | name        | &str | The name of the program                                              |
-    /// | description | &str | The description of the program                                       |
-    /// | epilog      | &str | The text at the bottom of the help                                   |
-    /// | credits     | &str | The credits at the bottom of the help (often your name and the year) |
+    /// let parsed_arguments = arguments.parse_args_str("'my file.txt'");
+    /// // Do something with the parsed arguments
+    /// // ...
+    /// ```
     ///
-    pub fn new(name: &str, description: &str, epilog: &str, credits: &str) -> Self {
-        let mut args: (
-            BTreeMap<String, (String, isize)>,
-            BTreeMap<char, (String, isize, String)>,
-        ) = (BTreeMap::new(), BTreeMap::new());
-        let exit_statuses: BTreeMap<u16, String> = BTreeMap::new();
-        args.1.insert(
-            'h',
-            (
-                "help".to_string(),
-                0,
-                "Use this to print this help message".to_string(),
-            ),
-        );
-        Self {
-            name: name.to_string(),
-            description: description.to_string(),
-            exit_statuses,
-            epilog: epilog.to_string(),
-            credits: credits.to_string(),
-            args,
-        }
+    pub fn parse_args_str(&mut self, line: &str) -> ParsedArgs {
+        self.run_str(line).unwrap_or_else(|stop| output::exit(stop))
     }
 
-    /// Add an exit status to the help page
+    /// Returns a ParsedArgs containing the parsed arguments, or the error found while parsing
     ///
-    /// A function that takes an u16 and a &str as input and adds it to the help page as an exit
-    /// status
+    /// A function that works just like `parse_args`, but returns an Error instead of printing
+    /// the error message and exiting, e.g. to handle it using `?`. `Error::kind` tells what went
+    /// wrong, like `ErrorKind::MissingOptionValue`. The help page is still printed (exiting the
+    /// program) when it's asked for
+    ///
+    /// | Parameter      | Type                | Description                                                              |
+    /// |----------------|---------------------|--------------------------------------------------------------------------|
+    /// | custom_arglist | Option\<Vec\<String\>\> | A custom argument-list you can use instead of the command line arguments |
     ///
     /// Code Example:
     /// ```no_run
-    /// fn main() {
+    /// fn main() -> Result<(), taap::Error> {
     /// // first initialize a new Argument instance using the "new" function
     /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
-    /// // Add our exit status, first the code, then the help text
-    /// arguments.add_exit_status(0, "Everything went well!");
+    /// arguments.add_arg("BAR", "1", None);
+    ///
+    /// let parsed_arguments = arguments.try_parse_args(None)?;
+    /// // Do something with the parsed arguments
     /// // ...
+    /// Ok(())
     /// }
     /// ```
     ///
-    /// | Parameter | Type | Description                                            |
-    /// |-----------|------|--------------------------------------------------------|
-    /// | code      | u16  | The exit code                                          |
-    /// | help      | &str | The help message on the help page fot that exit status |
-    ///
-
-    pub fn add_exit_status(&mut self, code: u16, help: &str) {
-        self.exit_statuses.insert(code, help.to_string());
+    pub fn try_parse_args(
+        &mut self,
+        custom_arglist: Option<Vec<String>>,
+    ) -> Result<ParsedArgs, Error> {
+        match self.run(custom_arglist) {
+            Ok(parsed) => Ok(parsed),
+            Err(Stop::Failed(err)) => Err(err),
+            Err(stop) => output::exit(stop),
+        }
     }
 
-    /// Add a positional argument
-    ///
-    /// A function that takes a placeholder &str, the amount of arguments as a &str, and a help
-    /// &str of the type Option<&str>
+    /// Matches a command line against the arguments, without printing anything, exiting or
+    /// reading the environment
     ///
-    /// The reason for the amount of args being a &str is because it doesn't only take positive
-    /// integers, it can also take "+" as an amount of arguments.
-    /// The "+" is equal to an unspecified amount of arguments.
+    /// A function that works like `parse_args`, for programs using taap's grammar in a front-end
+    /// of their own, like a custom shell, a server accepting argv-style requests or a test
+    /// harness. Instead of printing the help page, warnings and errors and exiting, it returns
+    /// what happened (see `Matches`). The environment isn't read either, so the variable set
+    /// with `set_options_env` and the language of the system are ignored. Values are still read
+    /// from files where the arguments say so (like `OptionSpec::value_files`), so leave those out
+    /// for requests that can't be trusted
     ///
-    /// The last argument is an Option<&str> because it's optional, which means you can pass None
-    /// if you don't want a help text for the argument
+    /// | Parameter | Type         | Description                                     |
+    /// |-----------|--------------|-------------------------------------------------|
+    /// | tokens    | Vec\<String\> | The command line, without the name of the program |
     ///
     /// Code Example:
     /// ```no_run
-    /// fn main() {
     /// // first initialize a new Argument instance using the "new" function
     /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
-    /// // Add a positonal argument to the Argument instance
-    /// arguments.add_arg("BAR", "1", Some("Some Help"));
-    /// // Add another positional argument, but this time it's "infinite"
-    /// arguments.add_arg("FOO", "+", None);
-    /// // ...
-    /// }
-    /// ```
+    /// arguments.add_arg("FILE", "1", None);
     ///
-    /// | Parameter   | Type         | Description                                                         |
-    /// |-------------|--------------|---------------------------------------------------------------------|
-    /// | placeholder | &str         | The placeholder of the positional argument, meant for the help page |
-    /// | args        | &str         | The amount of arguments, can either be a positive integer or a "+"  |
-    /// | help        | Option<&str> | The help text, can either be None or Some(&str)                     |
+    /// let matches = arguments.match_tokens(vec!["a.txt".to_string()]);
+    /// if let taap::Outcome::Parsed(parsed_arguments) = matches.outcome {
+    ///     // Do something with the parsed arguments
+    ///     // ...
+    /// };
+    /// ```
     ///
-    pub fn add_arg(&mut self, placeholder: &str, args: &str, help: Option<&str>) {
-        let nargs = if args == "+" {
-            -1
-        } else {
-            match args.to_string().parse::<usize>() {
-                Ok(n) => n as isize,
-                Err(_) => {
-                    panic!("Error! \"args\" parameter must be either a positive integer, 0 or +");
-                }
-            }
-        };
-        self.args.0.insert(
-            placeholder.to_string(),
-            (help.unwrap_or("").to_string(), nargs),
-        );
+    pub fn match_tokens(&mut self, tokens: Vec<String>) -> Matches {
+        output::transcribe(|| self.run(Some(tokens)))
     }
 
-    /// Add an optional argument
+    /// Returns a ParsedArgs containing the parsed arguments, awaiting the asynchronous value
+    /// sources and choices that are needed (requires the "async" feature)
     ///
-    /// A function that takes a short name as a char, a long name as a &str, the amount of arguments as a &str,
-    /// and a help &str of the type Option<&str>
+    /// A function that works just like `parse_args`, for programs running on an async runtime
+    /// (like tokio) whose options get their values or choices from async clients, see
+    /// `OptionSpec::async_value_source` and `OptionSpec::async_choices`. These are awaited one
+    /// after another, without blocking the runtime, once the parser gets to them: the choices of
+    /// the command and the subcommand used, and the value sources of the options that weren't
+    /// given otherwise. Nothing is fetched for the help or an option like --version. Options with
+    /// them can only be parsed using this function
     ///
-    /// The short name can be a space (' ') or a dash ('-') if you only want a long name
+    /// | Parameter      | Type                | Description                                                              |
+    /// |----------------|---------------------|--------------------------------------------------------------------------|
+    /// | custom_arglist | Option\<Vec\<String\>\> | A custom argument-list you can use instead of the command line arguments |
     ///
-    /// The long name can be an empty str (""), a space (" ") or a single/double dash ("-"/"--")
-    /// if you only want a short name
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
     ///
-    /// The reason for the amount of args being a &str is because it doesn't only take positive
-    /// integers, it can also take "+" as an amount of arguments.
-    /// The "+" is equal to an unspecified amount of arguments.
+    /// // run by the runtime of the program, e.g. from a #[tokio::main] function
+    /// async fn run() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('r', "region").takes(1).async_choices(|| {
+    ///     Box::pin(async {
+    ///         // ask the service which regions there are
+    ///         vec!["eu".to_string(), "us".to_string()]
+    ///     })
+    /// }));
     ///
-    /// The last argument is an Option<&str> because it's optional, which means you can pass None
-    /// if you don't want a help text for the argument
+    /// let parsed_arguments = arguments.parse_args_async(None).await;
+    /// // Do something with the parsed arguments
+    /// // ...
+    /// }
+    /// ```
+    ///
+    #[cfg(feature = "async")]
+    pub async fn parse_args_async(&mut self, custom_arglist: Option<Vec<String>>) -> ParsedArgs {
+        asynchronous::parse(self, custom_arglist)
+            .await
+            .unwrap_or_else(|stop| output::exit(stop))
+    }
+
+    /// Returns a ParsedArgs containing the parsed arguments, keeping arguments that aren't
+    /// valid Unicode
+    ///
+    /// A function that works just like `parse_args`, but takes the arguments as OsStrings (by
+    /// default from `std::env::args_os`), so an argument that isn't valid Unicode isn't an
+    /// error. This happens with file names holding lone surrogates on Windows, or bytes of
+    /// another encoding on Unix. Such arguments are parsed using a copy where the invalid parts
+    /// are replaced by "�" (U+FFFD), and `ParsedArgs::values_os` returns the original values, so
+    /// the files can still be opened. Use `display_os` to show them in messages
     ///
     /// Code Example:
     /// ```no_run
-    /// fn main() {
     /// // first initialize a new Argument instance using the "new" function
     /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
-    /// // Add some optional arguments
-    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
-    /// arguments.add_option('-', "boo", "2", Some("I only have a long name"));
-    /// arguments.add_option('a', "-", "0", Some("I only have a short name"));
-    /// arguments.add_option('n', "no-help", "0", None);
-    /// 
+    /// arguments.add_arg("FILE", "1", None);
+    /// let parsed_arguments = arguments.parse_args_os(None);
     ///
-    /// // More code...
+    /// let contents = std::fs::read(&parsed_arguments.values_os("FILE")[0]);
     /// // ...
-    /// }
     /// ```
     ///
-    /// | Parameter | Type         | Description                                                        |
-    /// |-----------|--------------|--------------------------------------------------------------------|
-    /// | short     | char         | The short name of the optional argument                            |
-    /// | long      | &str         | The long name of the optional argument
-    /// | args      | &str         | The amount of arguments, can either be a positive integer or a "+" |
-    /// | help      | Option<&str> | The help text, can either be None or Some(&str)                    |
+    /// | Parameter      | Type                  | Description                                                              |
+    /// |----------------|-----------------------|--------------------------------------------------------------------------|
+    /// | custom_arglist | Option\<Vec\<OsString\>\> | A custom argument-list you can use instead of the command line arguments |
     ///
-    pub fn add_option(
-        &mut self,
-        mut short: char,
-        mut long: &str,
-        parameters: &str,
-        help: Option<&str>,
-    ) {
-        if short == ' ' {
-            short = '-'
-        };
-        if long.is_empty() || long == " " || long == "-" || long == "--" {
-            long = ""
-        };
-        let nargs = if parameters == "+" {
-            -1
-        } else {
-            match parameters.to_string().parse::<usize>() {
-                Ok(n) => n as isize,
-                Err(_) => {
-                    panic!(
-                        "Error! \"parameters\" parameter must be either a positive integer, 0 or +"
-                    );
-                }
-            }
+    pub fn parse_args_os(&mut self, custom_arglist: Option<Vec<OsString>>) -> ParsedArgs {
+        let raw_args: Vec<OsString> = match custom_arglist {
+            Some(val) => std::iter::once(OsString::from(&self.name))
+                .chain(val)
+                .collect(),
+            None => std::env::args_os().collect(),
         };
-
-        self.args.1.insert(
-            short,
-            (long.to_string(), nargs, help.unwrap_or("").to_string()),
-        );
+        let lossy: Vec<String> = raw_args
+            .iter()
+            .map(|argument| argument.to_string_lossy().into_owned())
+            .collect();
+        let mut parsed = self.parse_args(Some(lossy[1..].to_vec()));
+        parsed.set_raw_args(lossy);
+        parsed.set_raw_args_os(raw_args);
+        parsed
     }
 
-    /// Prints the help page for your program
-    ///
-    /// Call this function to print the help page for your program.
+    /// Returns a ParsedArgs containing only the given options, ignoring all other arguments
     ///
-    /// The function takes no arguments
+    /// A function meant as the first stage of a two-stage parse, for programs which only know
+    /// all of their arguments after reading a configuration file or loading plugins. Options like
+    /// --config or --plugin-dir are added first and extracted using this function, without the
+    /// help page, the requirements, the positional arguments or warnings about the options it
+    /// doesn't know getting in the way. The program
+    /// then adds the remaining arguments, and parses the whole command line using `parse_args`
     ///
     /// Code Example:
     /// ```no_run
-    /// fn main() {
     /// // first initialize a new Argument instance using the "new" function
     /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
-    /// // Add some optional arguments
-    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
-    /// arguments.add_option('-', "boo", "2", Some("I only have a long name"));
-    /// arguments.add_option('a', "-", "0", Some("I only have a short name"));
-    /// arguments.add_option('n', "no-help", "0", None);
+    /// arguments.add_option('-', "plugin-dir", "1", Some("Where to look for plugins"));
     ///
-    /// // print the help
-    /// arguments.print_help();
+    /// let bootstrap = arguments.parse_bootstrap(&["plugin-dir"], None);
+    /// if let Some((true, values)) = bootstrap.get("plugin-dir") {
+    ///     // load the plugins in values[0], which add their own arguments
     /// }
+    ///
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// // ...
     /// ```
     ///
-    /// Most of the time printing the help manually is unnecessesary since the program already
-    /// adds the optional argument 'h' and "help" automatically
+    /// | Parameter      | Type                | Description                                                              |
+    /// |----------------|---------------------|--------------------------------------------------------------------------|
+    /// | names          | &\[&str\]            | The short or long names of the options to extract                        |
+    /// | custom_arglist | Option\<Vec\<String\>\> | A custom argument-list you can use instead of the command line arguments |
     ///
-
-    pub fn print_help(&self) {
-        let mut help_string = String::new();
-        let options = &self.args.1;
-        let pos_args = &self.args.0;
-        let name = &self.name;
-        let description = &self.description;
-        let credits = &self.credits;
-        let bottom_text = &self.epilog;
-        let exit_statuses = &self.exit_statuses;
-        let mut usage = format!("Usage: {}", name);
-        let mut pos_args_help = String::new();
-        for values in pos_args.iter() {
-            let argument = values.0;
-            let nargs = values.1 .1;
-            let help = &values.1 .0;
-            usage.push_str(format!(" {}", argument).as_str());
-            if nargs != 1 {
-                if nargs < 0 {
-                    usage.push_str("*∞");
-                    pos_args_help.push_str(format!("\n    {argument}*∞\t\t\t{help}").as_str());
-                } else {
-                    usage.push_str(format!("*{}", nargs).as_str());
-                    let tabs_needed = 3 - (nargs.to_string().len() as f32 / 8.0).ceil() as usize;
-                    pos_args_help.push_str(
-                        format!("\n    {argument}*{nargs}{:\t<tabs_needed$}{help}", "").as_str(),
-                    );
-                };
-            } else {
-                pos_args_help.push_str(format!("\n    {argument}\t\t\t{help}").as_str());
-            };
-        }
-
-        usage.push_str(" [OPTIONS]\n");
-
-        help_string.push_str(
-            format!(
-                "{}{}\n\nPositional Arguments:{}\n\nOptions:",
-                usage, description, pos_args_help
-            )
-            .as_str(),
-        );
-
-        for field in options.iter() {
-            let key: char;
-            if field.0 == &'-' {
-                key = ' ';
-            } else {
-                key = field.0.to_owned();
-            };
-            let values = field.1;
-            let tabs_needed = if values.1 > 0 {
-                2 - (values.1.to_string().len() as f32 / 8.0).ceil() as usize
-            } else if values.1 < 0 {
-                1
-            } else {
-                2
+    pub fn parse_bootstrap(
+        &self,
+        names: &[&str],
+        custom_arglist: Option<Vec<String>>,
+    ) -> ParsedArgs {
+        let mut bootstrap = Argument {
+            name: self.name.to_owned(),
+            limits: self.limits,
+            options_env: self.options_env.clone(),
+            output_format: self.output_format,
+            broken_pipe: self.broken_pipe,
+            bootstrap: true,
+            ..Default::default()
+        };
+        for name in names {
+            let Some(id) = self.option_id(name) else {
+                panic!("Error! There's no option \"{}\" to parse first", name);
             };
-            help_string.push_str(
-                format!(
-                    "\n    {}{}\t{}{}{}{:\t<tabs_needed$}{}",
-                    if key == ' ' { "" } else { "-" },
-                    key,
-                    if values.0 == "" { "" } else { "--" },
-                    values.0,
-                    if values.1 == 0 || values.1 == 1 || values.0.is_empty() {
-                        "".to_string()
-                    } else if values.1 < 0 {
-                        "*∞".to_string()
-                    } else {
-                        format!("*{}", values.1)
-                    },
-                    "",
-                    values.2
-                )
-                .as_str(),
-            );
+            let mut option = self.options[id].clone();
+            // the requirements are checked and the values are handed to sinks by the full parse
+            option.required = false;
+            option.required_if.clear();
+            option.short_circuit = false;
+            option.sink = None;
+            bootstrap.insert_option(option);
         }
-
-        if exit_statuses.len() > 1 {
-            help_string.push_str("\n\nExit Statuses:");
-            exit_statuses.iter().for_each(|(key, value)| {
-                help_string.push_str(format!("\n    {}\t{}", key, value).as_str())
-            });
-        };
-
-        help_string.push_str(format!("\n\n{}\n{}", bottom_text, credits).as_str());
-
-        println!("{}", help_string);
+        bootstrap.parse_args(custom_arglist)
     }
 
-    /// Returns a HashMap containing the parsed arguments
-    ///
-    /// A function that takes an Option<Vec<String>> value, parses arguments passed to the program and
-    /// returns a HashMap<String, (bool, Vec\<String\>)> which contains the parsed arguments
+    /// Returns a ParsedArgs containing the parsed arguments, or the ExitCode to return from main
     ///
+    /// A function that works just like `parse_args`, but never exits the program itself. When
+    /// the help page is printed the result is ExitCode::SUCCESS, and when an error is printed
+    /// it's ExitCode::FAILURE, so main can return it. Together with `exit_code` this lets main
+    /// be written as `fn main() -> ExitCode`, so destructors still run before the program ends
     ///
     /// | Parameter      | Type                | Description                                                              |
     /// |----------------|---------------------|--------------------------------------------------------------------------|
-    /// | custom_arglist | Option<Vec<String>> | A custom argument-list you can use instead of the command line arguments |
+    /// | custom_arglist | Option\<Vec\<String\>\> | A custom argument-list you can use instead of the command line arguments |
     ///
     /// Code Example:
     /// ```no_run
-    /// fn main() {
+    /// use std::process::ExitCode;
+    ///
+    /// fn main() -> ExitCode {
     /// // first initialize a new Argument instance using the "new" function
     /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
-    /// // Add a positonal argument
-    /// arguments.add_arg("BAR", "0", None);
-    /// // Add some optional arguments
-    /// arguments.add_option('f', "foo", "0", Some("I have a short and a long name!"));
-    /// arguments.add_option('-', "boo", "2", Some("I only have a long name"));
-    /// arguments.add_option('a', "-", "0", Some("I only have a short name"));
-    /// arguments.add_option('n', "no-help", "0", None);
+    /// arguments.add_arg("FILE", "1", None);
+    /// arguments.add_exit_status(0, "Everything went well!");
+    /// arguments.add_exit_status(2, "The file couldn't be read");
     ///
-    /// // Now parse the arguments and save the result in a variable
-    /// let parsed_arguments = arguments.parse_args(None);
+    /// let parsed_arguments = match arguments.parse_args_or_exit_code(None) {
+    ///     Ok(parsed_arguments) => parsed_arguments,
+    ///     Err(code) => return code,
+    /// };
+    /// let file = &parsed_arguments.get("FILE").unwrap().1[0];
+    /// match std::fs::read_to_string(file) {
+    ///     Ok(_) => arguments.exit_code(0),
+    ///     Err(_) => arguments.exit_code(2),
+    /// }
+    /// }
+    /// ```
     ///
-    /// // Do something with the parsed arguments
+    pub fn parse_args_or_exit_code(
+        &mut self,
+        custom_arglist: Option<Vec<String>>,
+    ) -> Result<ParsedArgs, ExitCode> {
+        self.run(custom_arglist).map_err(output::exit_code)
+    }
+
+    /// Returns the ExitCode of a documented exit status
+    ///
+    /// A function that turns an exit status added using `add_exit_status`,
+    /// `add_exit_status_range` or `add_exit_status_category` into an ExitCode to return from
+    /// main, so the program can only end with the statuses its help page describes. This
+    /// function panics if the status isn't documented, unless it's 0
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use std::process::ExitCode;
+    ///
+    /// fn main() -> ExitCode {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_exit_status(3, "Nothing was found");
     /// // ...
+    /// arguments.exit_code(3)
     /// }
     /// ```
     ///
+    /// | Parameter | Type | Description                   |
+    /// |-----------|------|-------------------------------|
+    /// | status    | u8   | The documented exit status    |
+    ///
+    pub fn exit_code(&self, status: u8) -> ExitCode {
+        self.check_exit_status(status);
+        ExitCode::from(status)
+    }
 
-    pub fn parse_args(
-        &mut self,
-        custom_arglist: Option<Vec<String>>,
-    ) -> BTreeMap<String, (bool, Vec<String>)> {
-        let mut collected_raw_args: Vec<String> = std::env::args().collect();
-        match custom_arglist {
-            Some(val) => collected_raw_args = val,
-            None => {
-                collected_raw_args.remove(0);
-            }
+    /// Exits the program with a documented exit status
+    ///
+    /// A function that works like `exit_code`, but ends the program straight away, for places
+    /// where returning from main isn't possible. Destructors don't run, just like with
+    /// std::process::exit. This function panics if the status isn't documented, unless it's 0
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_exit_status(3, "Nothing was found");
+    /// // ...
+    /// arguments.exit(3);
+    /// ```
+    ///
+    /// | Parameter | Type | Description                   |
+    /// |-----------|------|-------------------------------|
+    /// | status    | u8   | The documented exit status    |
+    ///
+    pub fn exit(&self, status: u8) -> ! {
+        self.check_exit_status(status);
+        std::process::exit(status.into())
+    }
+
+    // Parses the arguments like parse_args, returning the error parsing failed with instead of
+    // printing it
+    fn run(&mut self, custom_arglist: Option<Vec<String>>) -> Result<ParsedArgs, Stop> {
+        output::set_format(self.output_format);
+        output::set_broken_pipe(self.broken_pipe);
+        // the untouched command line, including the name of the program
+        let raw_args: Vec<String> = match custom_arglist {
+            Some(val) => std::iter::once(self.name.to_owned()).chain(val).collect(),
+            None => parse::string_args(std::env::args_os())?,
         };
-        let positional_arguments = &self.args.0;
-        let options = &self.args.1;
-        let mut return_map: BTreeMap<String, (bool, Vec<String>)> = BTreeMap::new();
-        for (key, val) in options.iter() {
-            let name: String;
-            if key.to_owned() == '-' {
-                name = val.0.to_owned();
-            } else {
-                name = key.to_string();
-            };
-            return_map.insert(name, (false, vec![]));
-        }
+        let collected_raw_args: Vec<String> = raw_args.iter().skip(1).cloned().collect();
+        let parsed = parse::parse(self, collected_raw_args);
+        if output::pipe_closed() {
+            return Err(Stop::Exited(141));
+        };
+        let mut parsed = parsed?;
+        // the positions are counted without the name of the program
+        let secret_args = parsed.take_secret_args();
+        parsed.set_secret_args(secret_args.into_iter().map(|secret| secret + 1).collect());
+        parsed.map_positions(&|position| Some(position + 1));
+        // without a line of its own, the command line is the arguments joined by spaces
+        let mut start = 0;
+        let token_spans = raw_args[1..]
+            .iter()
+            .map(|argument| {
+                let span = start..start + argument.len();
+                start = span.end + 1;
+                span
+            })
+            .collect();
+        parsed.set_token_spans(token_spans);
+        parsed.set_raw_args(raw_args);
+        Ok(parsed)
+    }
 
-        for key in positional_arguments.iter() {
-            return_map.insert(key.0.to_owned(), (true, vec![]));
-        }
+    // Splits the command line and parses it like parse_args_str, returning the error parsing
+    // failed with instead of printing it
+    fn run_str(&mut self, line: &str) -> Result<ParsedArgs, Stop> {
+        output::set_format(self.output_format);
+        let words = shell::split_spans(line, self.quote_dialect).map_err(|at| {
+            output::fail(format_args!(
+                "The quote at column {} of the command line isn't closed: \"{}\"",
+                line[..at].chars().count() + 1,
+                sanitize::sanitize(line)
+            ))
+        })?;
+        let (arguments, token_spans): (Vec<String>, Vec<Range<usize>>) = words.into_iter().unzip();
+        let mut parsed = self.run(Some(arguments))?;
+        parsed.set_token_spans(token_spans);
+        Ok(parsed)
+    }
 
-        // handling optional arguments
-        for (pos, argument) in collected_raw_args.iter().enumerate() {
-            // only parse if it's over 1 character, starts with - and 2nd character isn't -
-            if argument.len() > 1
-                && argument.starts_with("-")
-                && argument.chars().nth(1).unwrap() != '-'
-            {
-                // trim out the - and get characters, since options are single characters
-                for part in argument.get(1..).unwrap().chars() {
-                    // if it's in the hashmap, we know it exists, else just skip
-                    if options.contains_key(&part) {
-                        let options_needed = options.get(&part).unwrap().1;
-                        // infinite args part
-                        if options_needed < 0 {
-                            let mut temp_infinite_arglist: Vec<String> = vec![];
-                            for argument2 in collected_raw_args[pos + 1..].iter() {
-                                if argument2.starts_with("-") {
-                                    break;
-                                };
-                                if argument2.starts_with(r"\") {
-                                    temp_infinite_arglist.push(argument2[1..].to_string());
-                                } else {
-                                    temp_infinite_arglist.push(argument2.to_owned());
-                                };
-                            }
-                            *return_map.get_mut(&part.to_string()).unwrap() =
-                                (true, temp_infinite_arglist);
-                        } else {
-                            // Normal args go down here
-                            if collected_raw_args.len() < pos + 1 + options_needed as usize {
-                                eprintln!(
-                                    "Error! -{} requires {} arguments",
-                                    &part, options_needed
-                                );
-                                exit(1);
-                            };
-                            *return_map.get_mut(&part.to_string()).unwrap() = (
-                                true,
-                                collected_raw_args[pos + 1..(pos + 1 + options_needed as usize)]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                            );
-                        };
-                    };
-                }
-            } else if argument.len() > 2 && argument.get(..2).unwrap() == "--" {
-                let part = argument.get(2..).unwrap();
-                for (key, values) in &*options {
-                    if part == values.0 {
-                        let name: String;
-                        if key.to_owned() != '-' {
-                            name = key.to_string();
-                        } else {
-                            name = part.to_string();
-                        };
-                        let options_needed = values.1;
-                        // infinite args handling
-                        if options_needed < 0 {
-                            let mut temp_infinite_arglist: Vec<String> = vec![];
-                            for argument2 in collected_raw_args[pos + 1..].iter() {
-                                if argument2.starts_with("-") {
-                                    break;
-                                };
-                                if argument2.starts_with(r"\") {
-                                    temp_infinite_arglist.push(argument2[1..].to_string());
-                                } else {
-                                    temp_infinite_arglist.push(argument2.to_owned());
-                                };
-                            }
-                            *return_map.get_mut(&part.to_string()).unwrap() =
-                                (true, temp_infinite_arglist);
-                        } else {
-                            // Normal args are handled HERE
-                            if collected_raw_args.len() < pos + 1 + options_needed as usize {
-                                eprintln!(
-                                    "Error! --{} requires {} arguments",
-                                    &part, options_needed
-                                );
-                                exit(1);
-                            };
-                            *return_map.get_mut(&name).unwrap() = (
-                                true,
-                                collected_raw_args[pos + 1..(pos + 1 + options_needed as usize)]
-                                    .iter()
-                                    .cloned()
-                                    .collect(),
-                            );
-                        };
-                    }
-                }
-            }
-        }
-        if return_map.get("h").unwrap().0 == true {
-            self.print_help();
-            exit(0);
-        };
-
-        // handling positional_arguments
-        let mut current_argument_position: usize = 0;
-        for (pos, (key, value)) in positional_arguments.iter().enumerate() {
-            let argument_length = value.1;
-            if argument_length < 0 {
-                let mut temp_infinite_arglist: Vec<String> = vec![];
-                for argument in collected_raw_args[pos..].iter() {
-                    if argument.starts_with("-") {
-                        break;
-                    };
-                    if argument.starts_with(r"\") {
-                        temp_infinite_arglist.push(argument[1..].to_string());
-                    } else {
-                        temp_infinite_arglist.push(argument.to_owned());
-                    };
-                }
-                *return_map.get_mut(key).unwrap() = (true, temp_infinite_arglist);
-            } else {
-                if current_argument_position + argument_length as usize > collected_raw_args.len() {
-                    eprintln!(
-                        "Error! {} requires {} arguments",
-                        key,
-                        match positional_arguments.get(key) {
-                            Some(val) => val.1,
-                            None => panic!("Panic! Key \"{}\" non-existant!", key),
-                        }
-                    );
-                    exit(1);
-                };
-                *return_map.get_mut(key).unwrap() = (
-                    true,
-                    collected_raw_args[current_argument_position
-                        ..current_argument_position + argument_length as usize]
-                        .iter()
-                        .cloned()
-                        .collect(),
+    // Panics if an exit status isn't on the help page
+    fn check_exit_status(&self, status: u8) {
+        let status = u16::from(status);
+        let in_range = |(first, last): &(u16, u16)| (*first..=*last).contains(&status);
+        let documented = status == 0
+            || self.exit_statuses.contains_key(&status)
+            || self.exit_status_ranges.keys().any(in_range)
+            || self
+                .exit_status_categories
+                .iter()
+                .any(|category| category.statuses.keys().any(in_range));
+        if !documented {
+            panic!("Error! The exit status {} isn't documented", status);
+        };
+    }
+
+    // Adds an optional argument. An option reusing the short or long name of another option
+    // replaces it, keeping its place
+    fn insert_option(&mut self, option: OptionSpec) {
+        if option.require_equals && (option.arity != Arity::Exactly(1) || option.long.is_none()) {
+            panic!(
+                "Error! \"{}\" must take one value and have a long name to require =",
+                option.display_name()
+            );
+        };
+        if option.negatable && (option.arity != Arity::Exactly(0) || option.long.is_none()) {
+            panic!(
+                "Error! \"{}\" must take no values and have a long name to be negatable",
+                option.display_name()
+            );
+        };
+        if !option.enables.is_empty() && option.arity != Arity::Exactly(0) {
+            panic!(
+                "Error! \"{}\" must take no values to enable other flags",
+                option.display_name()
+            );
+        };
+        if option.until.is_some() && (option.arity == Arity::Exactly(0) || option.require_equals) {
+            panic!(
+                "Error! \"{}\" has to take values, without requiring =, to end at a keyword",
+                option.display_name()
+            );
+        };
+        if let Some(default) = &option.default {
+            let fits = match option.arity {
+                Arity::Exactly(amount) => amount > 0 && amount == default.len(),
+                Arity::Infinite => true,
+            };
+            if !fits {
+                panic!(
+                    "Error! The default of \"{}\" doesn't match the amount of values it takes",
+                    option.display_name()
                 );
-                current_argument_position += argument_length as usize;
+            };
+        };
+        let existing = option
+            .short
+            .and_then(|short| self.shorts.get(&short))
+            .or_else(|| option.long.as_deref().and_then(|long| self.longs.get(long)))
+            .copied();
+        let id = match existing {
+            Some(id) => {
+                let replaced = std::mem::replace(&mut self.options[id], option);
+                if let Some(short) = replaced.short {
+                    self.shorts.remove(&short);
+                };
+                if let Some(long) = replaced.long {
+                    self.longs.remove(&*long);
+                };
+                id
             }
-        }
+            None => {
+                self.options.push(option);
+                self.options.len() - 1
+            }
+        };
+        if let Some(short) = self.options[id].short {
+            self.shorts.insert(short, id);
+        };
+        if let Some(long) = &self.options[id].long {
+            self.longs.insert(long.to_string(), id);
+        };
+    }
 
-        return_map
+    // Adds a positional argument. A positional argument reusing a placeholder replaces the
+    // previous one, keeping its place
+    fn insert_positional(&mut self, positional: PositionalSpec) {
+        if self
+            .positionals
+            .iter()
+            .any(|existing| existing.command && existing.placeholder != positional.placeholder)
+        {
+            panic!("Error! The command tail has to be the last positional argument");
+        };
+        if positional.command
+            && self.positionals.iter().any(|existing| {
+                existing.arity == Arity::Infinite && existing.placeholder != positional.placeholder
+            })
+        {
+            panic!("Error! The command tail can't follow an infinite positional argument");
+        };
+        match self
+            .positionals
+            .iter_mut()
+            .find(|existing| existing.placeholder == positional.placeholder)
+        {
+            Some(existing) => *existing = positional,
+            None => self.positionals.push(positional),
+        };
+    }
+
+    // Returns the id of the option with this name (the name in the parse result, or the long
+    // name)
+    fn option_id(&self, name: &str) -> Option<usize> {
+        self.options.iter().position(|option| option.is_named(name))
+    }
+
+    // Returns the key an option or a positional argument is stored under, by any of its names
+    pub(crate) fn argument_key(&self, name: &str) -> Option<String> {
+        match self.option_id(name) {
+            Some(id) => Some(self.options[id].key()),
+            None => self
+                .positionals
+                .iter()
+                .find(|positional| positional.placeholder == name)
+                .map(|positional| positional.placeholder.to_string()),
+        }
     }
 }
@@ -0,0 +1,81 @@
+use crate::{output::fail_with, sanitize::sanitize, Error, ErrorKind};
+
+/// Limits for the input the parser accepts
+///
+/// Every limit is None (unlimited) by default. When a limit is exceeded, the parser prints a
+/// clear error and exits with status 1, instead of trying to handle the input. This is useful
+/// for tools that pass untrusted strings to the parser.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.set_limits(taap::Limits {
+///     max_tokens: Some(1000),
+///     max_value_length: Some(4096),
+///     ..Default::default()
+/// });
+/// // ...
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Limits {
+    /// The maximum amount of command line arguments
+    pub max_tokens: Option<usize>,
+    /// The maximum length of a single argument, in bytes (also applies to expanded values)
+    pub max_value_length: Option<usize>,
+    /// The maximum amount of values a single expansion (e.g. a file list) may produce
+    pub max_expansion: Option<usize>,
+}
+
+impl Limits {
+    pub(crate) fn check_tokens(&self, tokens: &[String]) -> Result<(), Error> {
+        self.check_token_count(tokens.len())?;
+        for token in tokens.iter() {
+            self.check_value(token)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_token_count(&self, amount: usize) -> Result<(), Error> {
+        if let Some(max_tokens) = self.max_tokens {
+            if amount > max_tokens {
+                return Err(fail_with(
+                    ErrorKind::LimitExceeded,
+                    format_args!(
+                        "Too many arguments ({}), the limit is {}",
+                        amount, max_tokens
+                    ),
+                ));
+            };
+        };
+        Ok(())
+    }
+
+    pub(crate) fn check_value(&self, value: &str) -> Result<(), Error> {
+        if let Some(max_value_length) = self.max_value_length {
+            if value.len() > max_value_length {
+                return Err(fail_with(
+                    ErrorKind::LimitExceeded,
+                    format_args!(
+                        "Argument \"{}\" is too long ({} bytes), the limit is {}",
+                        sanitize(value),
+                        value.len(),
+                        max_value_length
+                    ),
+                ));
+            };
+        };
+        Ok(())
+    }
+
+    pub(crate) fn check_expansion(&self, amount: usize) -> Result<(), Error> {
+        if let Some(max_expansion) = self.max_expansion {
+            if amount > max_expansion {
+                return Err(fail_with(
+                    ErrorKind::LimitExceeded,
+                    format_args!("Expansion produced more than {} values", max_expansion),
+                ));
+            };
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+// Translations of the help page, loaded from bundles embedded in the program
+//
+// A bundle holds the translations of one language, one "key = text" per line:
+//
+//     # German
+//     description = Ein Beispielprogramm
+//     option.verbose = Ausführliche Ausgabe
+//     positional.FILE = Die Datei
+//     remote.description = Verwaltet die Server
+//
+// Keys of subcommands start with the name of the subcommand, and "\n" starts a new line. The
+// language is chosen using LC_ALL, LC_MESSAGES or LANG (in that order), unless it's set by the
+// program.
+
+use crate::{output, spec::OptionSpec, Argument};
+use std::{collections::BTreeMap, env};
+
+pub(crate) type Bundle = BTreeMap<String, String>;
+
+pub(crate) fn parse_bundle(language: &str, bundle: &str) -> Bundle {
+    let mut translations = Bundle::new();
+    for line in bundle.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        };
+        match line.split_once('=') {
+            Some((key, text)) if !key.trim().is_empty() => {
+                translations.insert(key.trim().to_string(), text.trim().replace("\\n", "\n"));
+            }
+            _ => panic!(
+                "Error! Invalid line \"{}\" in the translations for \"{}\"",
+                line, language
+            ),
+        };
+    }
+    translations
+}
+
+// Languages written from right to left
+const RIGHT_TO_LEFT: [&str; 8] = ["ar", "dv", "fa", "he", "ks", "ps", "ur", "yi"];
+
+// The language used, without the encoding (e.g. "de_AT" for "de_AT.UTF-8")
+fn language(argument: &Argument) -> Option<String> {
+    let language = match &argument.language {
+        Some(language) => language.to_owned(),
+        None => system_language()?,
+    };
+    language
+        .split(['.', '@'])
+        .next()
+        .map(str::to_string)
+        .filter(|language| !language.is_empty())
+}
+
+// The language without the region, e.g. "de" for "de_AT"
+fn base(language: &str) -> &str {
+    language.split(['_', '-']).next().unwrap_or_default()
+}
+
+// The translations of the language used, if there are any. "de_AT" falls back to "de"
+pub(crate) fn bundle(argument: &Argument) -> Option<&Bundle> {
+    let language = language(argument)?;
+    argument
+        .translations
+        .get(&language)
+        .or_else(|| argument.translations.get(base(&language)))
+}
+
+// Returns true if the language used is written from right to left
+pub(crate) fn is_right_to_left(argument: &Argument) -> bool {
+    language(argument).is_some_and(|language| RIGHT_TO_LEFT.contains(&base(&language)))
+}
+
+fn system_language() -> Option<String> {
+    // a command line that's only matched isn't from the user of this system
+    if output::transcribing() {
+        return None;
+    };
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|variable| env::var(variable).ok())
+        .find(|value| !value.is_empty())
+}
+
+// The translations of a subcommand are the keys starting with its name
+pub(crate) fn subcommand_translations(
+    translations: &BTreeMap<String, Bundle>,
+    name: &str,
+) -> BTreeMap<String, Bundle> {
+    let prefix = format!("{}.", name);
+    translations
+        .iter()
+        .map(|(language, bundle)| {
+            (
+                language.to_owned(),
+                bundle
+                    .iter()
+                    .filter_map(|(key, text)| {
+                        Some((key.strip_prefix(&prefix)?.to_string(), text.to_owned()))
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+// The key of an option: its long name, or its short name if it has none
+pub(crate) fn option_key(option: &OptionSpec) -> String {
+    format!(
+        "option.{}",
+        option
+            .long
+            .as_deref()
+            .map_or_else(|| option.key(), str::to_string)
+    )
+}
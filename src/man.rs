@@ -0,0 +1,74 @@
+// Man pages, rendered from the same model as the help page (see help::render_man)
+
+use crate::{help, subcommand, Argument};
+
+/// How the man pages of an Argument with subcommands are laid out, see
+/// `Argument::render_man_pages`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ManLayout {
+    /// A single page, describing every subcommand in a section of its own
+    #[default]
+    Consolidated,
+    /// A page for the program and one for every subcommand (e.g. mytool-add.1), which refer to
+    /// each other, like the manuals of git and cargo
+    PerSubcommand,
+}
+
+// The name of the page of a (sub)command, e.g. "mytool-remote-add" for "mytool remote add"
+pub(crate) fn page_name(name: &str) -> String {
+    name.replace(' ', "-")
+}
+
+// The file names and the contents of the man pages
+pub(crate) fn pages(argument: &Argument, layout: ManLayout) -> Vec<(String, String)> {
+    match layout {
+        ManLayout::Consolidated => vec![(
+            file_name(&argument.name),
+            help::render_man(argument, &nested(argument), &[]),
+        )],
+        ManLayout::PerSubcommand => separate(argument, None),
+    }
+}
+
+fn file_name(name: &str) -> String {
+    format!("{}.1", page_name(name))
+}
+
+// The effective Arguments of the subcommands, the way they're parsed and their help is shown
+fn children(argument: &Argument) -> Vec<Argument> {
+    argument
+        .subcommands
+        .iter()
+        .filter_map(|sub| subcommand::effective(argument, &sub.name))
+        .collect()
+}
+
+// All subcommands, with the nested ones right after their parent
+fn nested(argument: &Argument) -> Vec<Argument> {
+    children(argument)
+        .into_iter()
+        .flat_map(|child| {
+            let grandchildren = nested(&child);
+            std::iter::once(child).chain(grandchildren)
+        })
+        .collect()
+}
+
+// A page for the (sub)command and for each of its subcommands. Every page refers to the page of
+// its parent and the pages of its subcommands
+fn separate(argument: &Argument, parent: Option<&str>) -> Vec<(String, String)> {
+    let children = children(argument);
+    let see_also: Vec<String> = parent
+        .map(page_name)
+        .into_iter()
+        .chain(children.iter().map(|child| page_name(&child.name)))
+        .collect();
+    let mut pages = vec![(
+        file_name(&argument.name),
+        help::render_man(argument, &[], &see_also),
+    )];
+    for child in children.iter() {
+        pages.extend(separate(child, Some(&argument.name)));
+    }
+    pages
+}
@@ -0,0 +1,46 @@
+// Matching a command line without the side effects of parse_args, for programs embedding taap's
+// grammar in a front-end of their own, see Argument::match_tokens
+
+use crate::{Error, ParsedArgs};
+
+/// The result of matching a command line with `Argument::match_tokens`
+///
+/// Code Example:
+/// ```no_run
+/// use taap::Outcome;
+///
+/// fn main() {
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_arg("FILE", "1", Some("The file to open"));
+///
+/// // e.g. a line typed into the shell of the program
+/// let matches = arguments.match_tokens(vec!["--help".to_string()]);
+/// match matches.outcome {
+///     Outcome::Parsed(parsed) => println!("Opening {}", parsed.get("FILE").unwrap().1[0]),
+///     Outcome::Failed(err) => println!("{}", err),
+///     Outcome::Exited(_) => println!("{}", matches.output.join("\n")),
+/// };
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Matches {
+    /// How matching ended
+    pub outcome: Outcome,
+    /// What would have been printed to stdout, like the help page for --help
+    pub output: Vec<String>,
+    /// The warnings that would have been printed, like the use of a deprecated option
+    pub warnings: Vec<String>,
+}
+
+/// How matching a command line ended, see `Matches`
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Outcome {
+    /// The command line was parsed
+    Parsed(ParsedArgs),
+    /// The command line has an error
+    Failed(Error),
+    /// An option like --help or --version asked for the program to end after printing
+    /// something, with the status it would have exited with
+    Exited(u8),
+}
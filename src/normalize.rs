@@ -0,0 +1,64 @@
+//! Common normalizations for the values of arguments
+//!
+//! These can be passed to `OptionSpec::normalize` and `PositionalSpec::normalize`, just like
+//! any other function or closure taking and returning a String.
+//!
+//! Code Example:
+//! ```no_run
+//! use taap::{normalize, Argument, Opt, Pos};
+//!
+//! fn main() {
+//! let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+//! arguments.add(Opt::new('u', "user").takes(1).normalize(normalize::trim).normalize(normalize::lowercase));
+//! arguments.add(Pos::new("FILE").normalize(normalize::canonicalize));
+//! // ...
+//! }
+//! ```
+
+use crate::callback::Callback;
+use std::{fs, sync::Arc};
+
+// A function applied to every value of an argument before it's stored
+pub(crate) type Normalizer = Callback<dyn Fn(String) -> String + Send + Sync>;
+
+pub(crate) fn normalizer(
+    normalize: impl Fn(String) -> String + Send + Sync + 'static,
+) -> Normalizer {
+    Callback(Arc::new(normalize))
+}
+
+// Applies every normalizer of an argument to its values, in the order they were added
+pub(crate) fn apply(normalizers: &[Normalizer], values: Vec<String>) -> Vec<String> {
+    if normalizers.is_empty() {
+        return values;
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            normalizers
+                .iter()
+                .fold(value, |value, normalize| (normalize.0)(value))
+        })
+        .collect()
+}
+
+/// Removes whitespace at the start and the end of the value
+pub fn trim(value: String) -> String {
+    value.trim().to_string()
+}
+
+/// Converts the value to lowercase
+pub fn lowercase(value: String) -> String {
+    value.to_lowercase()
+}
+
+/// Turns a path into an absolute path, with all symbolic links resolved
+///
+/// A path that can't be resolved (e.g. because it doesn't exist yet) is left as it is, since it
+/// might be created by the program itself.
+pub fn canonicalize(value: String) -> String {
+    match fs::canonicalize(&value) {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(_) => value,
+    }
+}
@@ -0,0 +1,81 @@
+// Arguments that aren't valid Unicode, like file names with lone surrogates on Windows or with
+// bytes of another encoding on Unix. The parser works with lossy copies of them, and the
+// originals are taken from the arguments the values were given by, see Argument::parse_args_os
+
+use crate::sanitize::sanitize;
+use std::ffi::{OsStr, OsString};
+
+// Takes the original of a value out of the arguments at the given positions (which are left
+// out afterwards, so equal values are matched to arguments in order). The value is either a
+// whole argument or attached to a long option using =
+pub(crate) fn take_original(
+    args: &[OsString],
+    positions: &mut Vec<usize>,
+    value: &str,
+) -> Option<OsString> {
+    let (at, original) = positions.iter().enumerate().find_map(|(at, &position)| {
+        let arg = args.get(position)?;
+        if arg.to_string_lossy() == value {
+            return Some((at, arg.to_owned()));
+        };
+        attached_value(arg)
+            .filter(|attached| attached.to_string_lossy() == value)
+            .map(|attached| (at, attached))
+    })?;
+    positions.remove(at);
+    Some(original)
+}
+
+// The value after the first = of a long option, as in --name=value
+#[cfg(unix)]
+fn attached_value(arg: &OsStr) -> Option<OsString> {
+    use std::os::unix::ffi::OsStrExt;
+    let bytes = arg.as_bytes();
+    let at = bytes.iter().position(|byte| *byte == b'=')?;
+    bytes
+        .starts_with(b"--")
+        .then(|| OsStr::from_bytes(&bytes[at + 1..]).to_owned())
+}
+
+// The value after the first = of a long option, as in --name=value. Windows arguments are UTF-16,
+// which may contain lone surrogates
+#[cfg(windows)]
+fn attached_value(arg: &OsStr) -> Option<OsString> {
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    let wide: Vec<u16> = arg.encode_wide().collect();
+    let at = wide.iter().position(|unit| *unit == u16::from(b'='))?;
+    wide.starts_with(&[u16::from(b'-'); 2])
+        .then(|| OsString::from_wide(&wide[at + 1..]))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn attached_value(_arg: &OsStr) -> Option<OsString> {
+    None
+}
+
+/// Returns an argument that may not be valid Unicode as a String that's safe to show, e.g. in an
+/// error message
+///
+/// The parts that aren't valid Unicode are replaced by "�" (U+FFFD), control characters are
+/// escaped and very long values are cut short, just like in the error messages of the parser
+///
+/// Code Example:
+/// ```no_run
+/// // first initialize a new Argument instance using the "new" function
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_arg("FILE", "1", None);
+/// let parsed_arguments = arguments.parse_args_os(None);
+///
+/// let file = &parsed_arguments.values_os("FILE")[0];
+/// if std::fs::metadata(file).is_err() {
+///     eprintln!("Can't find {}", taap::display_os(file));
+/// }
+/// ```
+///
+/// | Parameter | Type   | Description              |
+/// |-----------|--------|--------------------------|
+/// | value     | &OsStr | The argument to be shown |
+///
+pub fn display_os(value: &OsStr) -> String {
+    sanitize(&value.to_string_lossy())
+}
@@ -0,0 +1,346 @@
+// The output layer: everything taap shows the user (the help page and error messages) goes
+// through here, in the selected format
+
+use crate::{
+    error::{Error, ErrorKind},
+    matcher::{Matches, Outcome},
+    sanitize::sanitize,
+    trace::event,
+};
+use std::{
+    cell::{Cell, RefCell},
+    error,
+    fmt::Display,
+    io::{self, Write},
+    process::{self, ExitCode},
+    str::FromStr,
+};
+
+/// The format of the help page and the error messages
+///
+/// It can be set by the program using `Argument::set_output_format`, or chosen by the user with
+/// the --format option added by `Argument::add_format_option`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum OutputFormat {
+    /// Plain text, meant for a terminal
+    #[default]
+    Plain,
+    /// Markdown, e.g. for generating documentation
+    Markdown,
+    /// JSON, for other programs
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "plain" | "text" => Ok(OutputFormat::Plain),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!(
+                "Unknown format \"{}\", expected plain, markdown or json",
+                sanitize(format)
+            )),
+        }
+    }
+}
+
+/// What happens when the output can't be written because the pipe is closed
+///
+/// E.g. `name --help | head -1` closes the pipe after the first line. Either way taap never
+/// panics on a closed pipe. It can be set using `Argument::set_broken_pipe`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BrokenPipe {
+    /// Stop writing, and carry on as if everything was written
+    #[default]
+    Ignore,
+    /// Exit with status 141, like a program killed by SIGPIPE would
+    Exit,
+}
+
+// The long name of the option choosing the format
+pub(crate) const FORMAT_OPTION: &str = "format";
+
+thread_local! {
+    // the format of the output while parsing, since errors can happen deep inside the parser
+    static FORMAT: Cell<OutputFormat> = const { Cell::new(OutputFormat::Plain) };
+    static BROKEN_PIPE: Cell<BrokenPipe> = const { Cell::new(BrokenPipe::Ignore) };
+    // if a pipe was closed while printing, and the program asked to exit when that happens
+    static PIPE_CLOSED: Cell<bool> = const { Cell::new(false) };
+    // what would have been printed while transcribing, as (output, warnings)
+    static TRANSCRIPT: RefCell<Option<(Vec<String>, Vec<String>)>> = const { RefCell::new(None) };
+    // what was printed while the output was held, as (printed to stderr, line)
+    static HELD: RefCell<Option<Vec<(bool, String)>>> = const { RefCell::new(None) };
+}
+
+// How parsing ended early, handed up to the function the parser was called from, which decides
+// whether to print the error and exit
+#[derive(Debug)]
+pub(crate) enum Stop {
+    // the command line has an error
+    Failed(Error),
+    // something was printed, like the help page, and the program should end with the status
+    Exited(u8),
+    // the parser got to async parts of the options, which have to be awaited before parsing
+    // again, see asynchronous::parse
+    #[cfg(feature = "async")]
+    Await(Vec<crate::asynchronous::Pending>),
+}
+
+impl From<Error> for Stop {
+    fn from(err: Error) -> Self {
+        Stop::Failed(err)
+    }
+}
+
+pub(crate) fn set_format(format: OutputFormat) {
+    FORMAT.with(|current| current.set(format));
+}
+
+pub(crate) fn format() -> OutputFormat {
+    FORMAT.with(Cell::get)
+}
+
+pub(crate) fn set_broken_pipe(policy: BrokenPipe) {
+    BROKEN_PIPE.with(|current| current.set(policy));
+    PIPE_CLOSED.with(|closed| closed.set(false));
+}
+
+// Returns true once if a pipe was closed while printing with BrokenPipe::Exit, in which case the
+// program ends with status 141 instead of the one it would have ended with
+pub(crate) fn pipe_closed() -> bool {
+    PIPE_CLOSED.with(|closed| closed.replace(false))
+}
+
+// Prints a line to stdout
+pub(crate) fn print(text: &str) {
+    let transcribed = TRANSCRIPT.with(|transcript| match &mut *transcript.borrow_mut() {
+        Some((output, _)) => {
+            output.push(text.to_string());
+            true
+        }
+        None => false,
+    });
+    if !transcribed && !held(false, text) {
+        write_line(io::stdout().lock(), text);
+    };
+}
+
+// Prints a line to stderr
+pub(crate) fn print_error(text: &str) {
+    if !held(true, text) {
+        write_line(io::stderr().lock(), text);
+    };
+}
+
+// Keeps the line back if the output is held, returning true if it was
+fn held(to_stderr: bool, text: &str) -> bool {
+    HELD.with(|held| match &mut *held.borrow_mut() {
+        Some(lines) => {
+            lines.push((to_stderr, text.to_string()));
+            true
+        }
+        None => false,
+    })
+}
+
+// Runs the function while holding back everything it prints, which is returned for release
+#[cfg(feature = "async")]
+pub(crate) fn hold<T>(run: impl FnOnce() -> T) -> (T, Vec<(bool, String)>) {
+    // the output isn't held anymore even if the function panics
+    struct Release;
+    impl Drop for Release {
+        fn drop(&mut self) {
+            HELD.with(|held| held.take());
+        }
+    }
+    HELD.with(|held| held.replace(Some(vec![])));
+    let release = Release;
+    let result = run();
+    let lines = HELD.with(|held| held.take()).unwrap_or_default();
+    drop(release);
+    (result, lines)
+}
+
+// Prints the lines held by hold
+#[cfg(feature = "async")]
+pub(crate) fn release(lines: Vec<(bool, String)>) {
+    for (to_stderr, text) in lines {
+        match to_stderr {
+            true => print_error(&text),
+            false => print(&text),
+        };
+    }
+}
+
+// Writes a line, without panicking if the stream is closed. A closed pipe is noted for
+// pipe_closed, other write errors are ignored since there's nowhere left to report them
+pub(crate) fn write_line(mut stream: impl Write, text: &str) {
+    let written = writeln!(stream, "{}", text).and_then(|_| stream.flush());
+    if let Err(err) = written {
+        if err.kind() == io::ErrorKind::BrokenPipe
+            && BROKEN_PIPE.with(Cell::get) == BrokenPipe::Exit
+        {
+            PIPE_CLOSED.with(|closed| closed.set(true));
+        };
+    };
+}
+
+// Looks for the format option before the command line is parsed, so errors found while parsing
+// are already printed in the chosen format. Invalid values are reported later by the parser
+pub(crate) fn select_format(tokens: &[String]) {
+    let option = format!("--{}", FORMAT_OPTION);
+    let mut tokens = tokens.iter().take_while(|token| *token != "--");
+    while let Some(token) = tokens.next() {
+        let value = match token.strip_prefix(&option) {
+            Some("") => tokens.next().map(String::as_str),
+            Some(attached) => attached.strip_prefix('='),
+            None => None,
+        };
+        if let Some(format) = value.and_then(|value| value.parse().ok()) {
+            set_format(format);
+        };
+    }
+}
+
+// Ends the program the way parsing ended: printing the error and exiting with status 1, or
+// exiting with the status asked for
+pub(crate) fn exit(stop: Stop) -> ! {
+    match stop {
+        Stop::Failed(err) => {
+            report(&err);
+            process::exit(if pipe_closed() { 141 } else { 1 })
+        }
+        Stop::Exited(status) => process::exit(status.into()),
+        #[cfg(feature = "async")]
+        Stop::Await(_) => unreachable!("async parts are only asked for by parse_args_async"),
+    }
+}
+
+// Returns the ExitCode to end the program with the way parsing ended, printing the error if
+// there is one
+pub(crate) fn exit_code(stop: Stop) -> ExitCode {
+    match stop {
+        Stop::Failed(err) => {
+            report(&err);
+            match pipe_closed() {
+                true => ExitCode::from(141),
+                false => ExitCode::FAILURE,
+            }
+        }
+        Stop::Exited(status) => ExitCode::from(status),
+        #[cfg(feature = "async")]
+        Stop::Await(_) => unreachable!("async parts are only asked for by parse_args_async"),
+    }
+}
+
+// The error of a usage mistake, returned up to the function the parser was called from
+pub(crate) fn fail(message: impl Display) -> Error {
+    error(ErrorKind::Usage, message.to_string(), None)
+}
+
+// Like fail, for errors of another kind than a usage error
+pub(crate) fn fail_with(kind: ErrorKind, message: impl Display) -> Error {
+    error(kind, message.to_string(), None)
+}
+
+// Like fail, for errors caused by another error (e.g. a file that couldn't be read)
+pub(crate) fn fail_caused(
+    message: impl Display,
+    source: impl error::Error + Send + Sync + 'static,
+) -> Error {
+    error(
+        ErrorKind::Usage,
+        message.to_string(),
+        Some(Box::new(source)),
+    )
+}
+
+fn error(
+    kind: ErrorKind,
+    message: String,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+) -> Error {
+    event!(error = %message, "parsing failed");
+    Error::new(kind, message, source)
+}
+
+// Runs the parser without printing anything, exiting or reading the environment, returning what
+// it would have printed and how it ended instead, see Argument::match_tokens
+pub(crate) fn transcribe(parse: impl FnOnce() -> Result<crate::ParsedArgs, Stop>) -> Matches {
+    let previous = TRANSCRIPT.with(|transcript| transcript.replace(Some((vec![], vec![]))));
+    let result = parse();
+    let (output, warnings) = TRANSCRIPT
+        .with(|transcript| transcript.replace(previous))
+        .unwrap_or_default();
+    let outcome = match result {
+        Ok(parsed) => Outcome::Parsed(parsed),
+        Err(Stop::Failed(err)) => Outcome::Failed(err),
+        Err(Stop::Exited(status)) => Outcome::Exited(status),
+        #[cfg(feature = "async")]
+        Err(Stop::Await(_)) => unreachable!("async parts are only asked for by parse_args_async"),
+    };
+    Matches {
+        outcome,
+        output,
+        warnings,
+    }
+}
+
+// If the parser is run by transcribe, which keeps it away from the environment
+pub(crate) fn transcribing() -> bool {
+    TRANSCRIPT.with(|transcript| transcript.borrow().is_some())
+}
+
+// Prints the error parsing failed with in the current format
+fn report(err: &Error) {
+    let message = match error::Error::source(err) {
+        Some(source) => format!("{}: {}", err.message(), source),
+        None => err.message().to_string(),
+    };
+    print_error(&match format() {
+        OutputFormat::Plain => format!("Error! {}", message),
+        OutputFormat::Markdown => format!("**Error!** {}", message),
+        OutputFormat::Json => format!("{{\"error\": {}}}", json_string(&message)),
+    });
+}
+
+// Prints a warning in the current format, without stopping the program
+pub(crate) fn warn(message: impl Display) {
+    let transcribed = TRANSCRIPT.with(|transcript| match &mut *transcript.borrow_mut() {
+        Some((_, warnings)) => {
+            warnings.push(message.to_string());
+            true
+        }
+        None => false,
+    });
+    if transcribed {
+        return;
+    };
+    print_error(&match format() {
+        OutputFormat::Plain => format!("Warning! {}", message),
+        OutputFormat::Markdown => format!("**Warning!** {}", message),
+        OutputFormat::Json => format!("{{\"warning\": {}}}", json_string(&message.to_string())),
+    });
+}
+
+// Quotes and escapes a string for JSON
+pub(crate) fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            character if character.is_control() => {
+                quoted.push_str(format!("\\u{:04x}", character as u32).as_str())
+            }
+            character => quoted.push(character),
+        };
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,1215 @@
+// The parsing engine behind Argument::parse_args
+//
+// The command line is read in a single pass: options take their values straight away, and
+// everything else is collected as positional values. Every option splits the positional values
+// into a new "run", since an option terminates an infinite positional argument. Afterwards the
+// positional values are handed out to the positional arguments in the order they were added.
+// When a subcommand is found, the rest of the command line is parsed as the subcommand.
+
+use crate::{
+    aliases, exclusive, expand, help, hooks, interpolate, normalize,
+    output::{self, fail, fail_with, OutputFormat, Stop},
+    parsed::{Kind, Source},
+    profiles, renamed,
+    sanitize::sanitize,
+    shell, show_config, sink,
+    spec::{Arity, Duplicates, MergeStrategy, OptionSpec},
+    subcommand,
+    suggest::{dash_hint, did_you_mean, suggest},
+    trace::{event, span},
+    value_source, version, Argument, Error, ErrorKind, ParseStats, ParsedArgs,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    ops::Range,
+    time::Instant,
+};
+
+pub(crate) type ParseResult = BTreeMap<String, (bool, Vec<String>)>;
+
+pub(crate) fn parse(argument: &mut Argument, tokens: Vec<String>) -> Result<ParsedArgs, Stop> {
+    let started = Instant::now();
+    #[cfg(feature = "async")]
+    crate::asynchronous::prepare(argument)?;
+    let _parse = span!("parse", command = %argument.name);
+    let tokenize = span!("tokenize");
+    let given = tokens.clone();
+    let tokens = hooks::run_pre_parse(&argument.pre_parse_hooks, tokens);
+    // where in the given tokens every token came from, kept through every rewrite below
+    let mut origins = origins(&given, &tokens);
+    if argument.format_option {
+        output::select_format(&tokens);
+    };
+    let tokens = match &argument.aliases_file {
+        Some(path) => aliases::expand_aliases(argument, path, tokens, &mut origins)?,
+        None => tokens,
+    };
+    let (tokens, token_sources) = match &argument.profiles_file {
+        Some(path) => {
+            let (expanded, sources) = profiles::expand_profiles(argument, path, tokens)?;
+            // the arguments of the profiles are placed before the command line
+            origins.splice(..0, vec![None; expanded.len() - origins.len()]);
+            (expanded, sources)
+        }
+        None => {
+            let sources = vec![Source::CommandLine; tokens.len()];
+            (tokens, sources)
+        }
+    };
+    // the arguments in the environment variable come first, so the command line overrides them
+    let (tokens, token_sources) = match &argument.options_env {
+        Some(variable) if !output::transcribing() => {
+            let mut arguments = options_env(variable)?;
+            let mut sources = vec![Source::Environment(variable.to_owned()); arguments.len()];
+            origins.splice(..0, vec![None; arguments.len()]);
+            arguments.extend(tokens);
+            sources.extend(token_sources);
+            (arguments, sources)
+        }
+        _ => (tokens, token_sources),
+    };
+    let (tokens, origins) = renamed::rewrite(argument, tokens, origins);
+    argument.limits.check_tokens(&tokens)?;
+    event!(tokens = tokens.len(), "tokenized the command line");
+    drop(tokenize);
+
+    let mut return_map: ParseResult = BTreeMap::new();
+    for option in argument.options.iter() {
+        return_map.insert(option.key(), (false, vec![]));
+    }
+    let mut sources: BTreeMap<String, Source> = BTreeMap::new();
+    // the files values were read from, see OptionSpec::value_files
+    let mut value_files: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    // the tokens holding values of secret options
+    let mut secret_tokens: Vec<usize> = vec![];
+    // the tokens every argument was given by, see ParsedArgs::spans
+    let mut positions: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    // the flags turned off with --no-NAME
+    let mut negated: BTreeSet<String> = BTreeSet::new();
+    for positional in argument.positionals.iter() {
+        return_map.insert(positional.placeholder.to_string(), (true, vec![]));
+        sources.insert(positional.placeholder.to_string(), Source::CommandLine);
+    }
+
+    // handling optional arguments, keeping the positional values in runs with their positions
+    let mut runs: Vec<Vec<(usize, String)>> = vec![vec![]];
+    let mut position: usize = 0;
+    let mut subcommand_at: Option<usize> = None;
+    let mut rest: Vec<String> = vec![];
+    let mut trailing: Option<Vec<String>> = None;
+    // a command tail starts at the first value after the other positional arguments
+    let tail_at = tail_at(argument);
+    let mut positional_count: usize = 0;
+    // the options that were skipped, and if they came after values of an infinite positional
+    // argument, for the hint in errors about missing values
+    let mut unknown: Vec<(String, bool)> = vec![];
+    let variadic = argument
+        .positionals
+        .iter()
+        .any(|positional| positional.arity == Arity::Infinite && !positional.command);
+    let mut tail: Vec<String> = vec![];
+    let mut tail_from: usize = 0;
+    let is_terminator = |token: &String| argument.terminator.as_ref() == Some(token);
+    let matching = span!("match");
+    while position < tokens.len() {
+        let token = &tokens[position];
+        position += 1;
+        if is_terminator(token) {
+            // everything after the terminator is left as it is
+            rest = tokens[position..].to_vec();
+            break;
+        } else if token == "--" {
+            // everything after "--" is positional, up to the terminator
+            let end = tokens[position..]
+                .iter()
+                .position(is_terminator)
+                .map_or(tokens.len(), |end| position + end);
+            let tail_start = match tail_at {
+                Some(tail_at) => (position + tail_at.saturating_sub(positional_count)).min(end),
+                None => end,
+            };
+            runs.last_mut()
+                .unwrap()
+                .extend((position..tail_start).map(|at| (at, tokens[at].to_owned())));
+            tail = tokens[tail_start..end].to_vec();
+            tail_from = tail_start;
+            trailing = Some(tokens[position..end].to_vec());
+            if end < tokens.len() {
+                rest = tokens[end + 1..].to_vec();
+            };
+            break;
+        } else if let Some(long) = token.strip_prefix("--") {
+            // the first value can be attached using =, as in --name=value
+            let (long, attached) = match long.split_once('=') {
+                Some((long, value)) => (long, Some(value)),
+                None => (long, None),
+            };
+            // unknown options are skipped
+            if let Some(&id) = argument.longs.get(long) {
+                let used_as = format!("--{}", long);
+                version::check_compatibility(argument, id, &used_as);
+                check_spacing(
+                    &argument.options[id],
+                    &used_as,
+                    attached,
+                    &tokens[position..],
+                )?;
+                let option_at = position - 1;
+                let first = check_duplicate(
+                    argument,
+                    id,
+                    &used_as,
+                    &token_sources[position - 1],
+                    &sources,
+                    &return_map,
+                )?;
+                let earlier = set_source(
+                    argument,
+                    id,
+                    &token_sources[position - 1],
+                    &mut sources,
+                    &return_map,
+                );
+                // an attached value is part of the option itself
+                let values_at = position - usize::from(attached.is_some());
+                position += take_values(
+                    argument,
+                    id,
+                    &used_as,
+                    attached,
+                    value_tokens(argument, id, &tokens[position..], positional_count),
+                    &mut return_map,
+                    &mut value_files,
+                )?;
+                note_secret(argument, id, values_at..position, &mut secret_tokens);
+                note_positions(argument, id, option_at..position, &mut positions);
+                merge_layers(argument, id, earlier, &mut return_map);
+                keep_first(argument, id, first, &mut return_map);
+            } else if let Some(id) = negated_option(argument, long) {
+                let option = &argument.options[id];
+                if attached.is_some() {
+                    return Err(fail_with(
+                        ErrorKind::UnexpectedValue,
+                        format_args!(
+                            "--no-{} doesn't take a value",
+                            option.long.as_deref().unwrap()
+                        ),
+                    )
+                    .into());
+                };
+                set_source(
+                    argument,
+                    id,
+                    &token_sources[position - 1],
+                    &mut sources,
+                    &return_map,
+                );
+                *return_map.get_mut(&option.key()).unwrap() = (false, vec![]);
+                negated.insert(option.key());
+                positions.insert(option.key(), vec![position - 1]);
+            } else {
+                if argument.strict {
+                    return Err(fail_unknown(argument, token).into());
+                };
+                // skipping a typo of an option silently would leave the user puzzled, unless the
+                // option is only unknown to the first stage of a two-stage parse
+                if let Some(meant) = misspelled(argument, long).filter(|_| !argument.bootstrap) {
+                    output::warn(format_args!(
+                        "Skipped the unknown option {}, did you mean \"{}\"?",
+                        sanitize(&without_value(long)),
+                        meant
+                    ));
+                };
+                event!(token = %token, "skipped an unknown option");
+                unknown.push((token.to_owned(), variadic && positional_count > 0));
+            };
+            runs.push(vec![]);
+        } else if let Some(id) = number_option(argument, token) {
+            // -NUMBER is a shorthand for the number option, with the number as its value
+            version::check_compatibility(argument, id, token);
+            let first = check_duplicate(
+                argument,
+                id,
+                token,
+                &token_sources[position - 1],
+                &sources,
+                &return_map,
+            )?;
+            let earlier = set_source(
+                argument,
+                id,
+                &token_sources[position - 1],
+                &mut sources,
+                &return_map,
+            );
+            let values_at = position - 1;
+            position += take_values(
+                argument,
+                id,
+                token,
+                Some(&token[1..]),
+                value_tokens(argument, id, &tokens[position..], positional_count),
+                &mut return_map,
+                &mut value_files,
+            )?;
+            note_secret(argument, id, values_at..position, &mut secret_tokens);
+            note_positions(argument, id, values_at..position, &mut positions);
+            merge_layers(argument, id, earlier, &mut return_map);
+            keep_first(argument, id, first, &mut return_map);
+            runs.push(vec![]);
+        } else if token.len() > 1 && token.starts_with('-') && !is_negative_number(argument, token)
+        {
+            // options are single characters, so every character is its own option
+            let option_at = position - 1;
+            if token
+                .chars()
+                .skip(1)
+                .any(|short| !argument.shorts.contains_key(&short))
+            {
+                if argument.strict {
+                    return Err(fail_unknown(argument, token).into());
+                };
+                event!(token = %token, "skipped an unknown option");
+                unknown.push((token.to_owned(), variadic && positional_count > 0));
+            };
+            for short in token.chars().skip(1) {
+                if let Some(&id) = argument.shorts.get(&short) {
+                    let used_as = format!("-{}", short);
+                    version::check_compatibility(argument, id, &used_as);
+                    let first = check_duplicate(
+                        argument,
+                        id,
+                        &used_as,
+                        &token_sources[option_at],
+                        &sources,
+                        &return_map,
+                    )?;
+                    let earlier = set_source(
+                        argument,
+                        id,
+                        &token_sources[option_at],
+                        &mut sources,
+                        &return_map,
+                    );
+                    let values_at = position;
+                    position += take_values(
+                        argument,
+                        id,
+                        &used_as,
+                        None,
+                        value_tokens(argument, id, &tokens[position..], positional_count),
+                        &mut return_map,
+                        &mut value_files,
+                    )?;
+                    note_secret(argument, id, values_at..position, &mut secret_tokens);
+                    note_positions(argument, id, option_at..position, &mut positions);
+                    merge_layers(argument, id, earlier, &mut return_map);
+                    keep_first(argument, id, first, &mut return_map);
+                };
+            }
+            runs.push(vec![]);
+        } else if subcommand::is_subcommand(argument, token) {
+            event!(subcommand = %token, "found a subcommand");
+            subcommand_at = Some(position - 1);
+            break;
+        } else if tail_at == Some(positional_count) {
+            // the command takes everything from here on, exactly as it was given
+            tail = tokens[position - 1..].to_vec();
+            tail_from = position - 1;
+            break;
+        } else {
+            runs.last_mut()
+                .unwrap()
+                .push((position - 1, token.to_owned()));
+            positional_count += 1;
+        };
+    }
+    drop(matching);
+
+    if argument.format_option {
+        if let Some((true, values)) = return_map.get(output::FORMAT_OPTION) {
+            match values[0].parse::<OutputFormat>() {
+                Ok(format) => output::set_format(format),
+                Err(err) => return Err(fail(err).into()),
+            };
+        };
+    };
+
+    if !argument.raw && return_map.get("h").is_some_and(|help| help.0) {
+        match help_query(argument, &tokens) {
+            Some(query) => help::print_matching(argument, output::format(), query),
+            None => help::print(argument, output::format()),
+        };
+        return Err(Stop::Exited(0));
+    };
+
+    if let Some(version) = &argument.version {
+        if argument
+            .option_id("version")
+            .is_some_and(|id| return_map.get(&argument.options[id].key()).unwrap().0)
+        {
+            output::print(&format!("{} {}", argument.name, version));
+            return Err(Stop::Exited(0));
+        };
+    };
+
+    // the requirements don't apply when an option like --version is used
+    let short_circuit = argument
+        .options
+        .iter()
+        .any(|option| option.short_circuit && return_map.get(&option.key()).unwrap().0);
+
+    let mut subcommand: Option<(String, ParsedArgs)> = None;
+    if let Some(at) = subcommand_at {
+        let (name, mut parsed) =
+            subcommand::parse_subcommand(argument, &tokens[at], &tokens[at + 1..])?;
+        secret_tokens.extend(
+            parsed
+                .take_secret_args()
+                .into_iter()
+                .map(|secret| at + 1 + secret),
+        );
+        parsed.map_positions(&|position| Some(at + 1 + position));
+        // global options can be used both before and after the subcommand
+        for option in argument.options.iter().filter(|option| option.global) {
+            let key = option.key();
+            if let Some(value) = parsed.get_mut(&key) {
+                let parent_value = return_map.get_mut(&key).unwrap();
+                if value.0 && !parent_value.0 {
+                    *parent_value = value.clone();
+                    if let Some(source) = parsed.source(&key) {
+                        sources.insert(key.to_owned(), source.clone());
+                    };
+                } else if parent_value.0 {
+                    *value = parent_value.clone();
+                    parsed.set_source(&key, sources.get(&key).unwrap().clone());
+                };
+            };
+        }
+        subcommand = Some((name, parsed));
+    };
+
+    let _validate = span!("validate");
+    enable_members(argument, &negated, &mut return_map, &mut sources);
+    // values from external stores count for required options, unlike defaults
+    if !short_circuit {
+        value_source::environment(&argument.options, &negated, &mut return_map, &mut sources)?;
+        #[cfg(feature = "async")]
+        crate::asynchronous::check_sources(argument, &return_map)?;
+        value_source::fetch(&argument.options, &mut return_map, &mut sources)?;
+    };
+
+    // every missing required option is listed at once, so they don't have to be found one by one
+    let missing: Vec<String> = argument
+        .options
+        .iter()
+        .filter(|option| option.required && !short_circuit)
+        .filter(|option| !return_map.get(&option.key()).unwrap().0)
+        .map(|option| option.display_name())
+        .collect();
+    match missing.as_slice() {
+        [] => (),
+        [option] => {
+            return Err(fail_with(
+                ErrorKind::MissingRequired,
+                format_args!("{} is required", option),
+            )
+            .into())
+        }
+        [options @ .., last] => {
+            return Err(fail_with(
+                ErrorKind::MissingRequired,
+                format_args!("{} and {} are required", options.join(", "), last),
+            )
+            .into())
+        }
+    };
+
+    // defaults referring to other arguments are filled in once the positional arguments are too
+    for option in argument
+        .options
+        .iter()
+        .filter(|option| !option.default_template)
+    {
+        if let Some(default) = &option.default {
+            let value = return_map.get_mut(&option.key()).unwrap();
+            if !value.0 {
+                *value = (
+                    true,
+                    normalize::apply(&option.normalizers, default.to_owned()),
+                );
+                sources.insert(option.key(), Source::Default);
+            };
+        };
+    }
+
+    // handling positional arguments
+    let excused = exclusive::excused(argument, &return_map, &sources);
+    let mut runs = runs.into_iter().filter(|run| !run.is_empty());
+    let mut current_run: Vec<(usize, String)> = vec![];
+    for positional in argument.positionals.iter() {
+        let (at, values): (Vec<usize>, Vec<String>) = match positional.arity {
+            _ if positional.command => (
+                (tail_from..tail_from + tail.len()).collect(),
+                std::mem::take(&mut tail),
+            ),
+            Arity::Infinite => {
+                if current_run.is_empty() {
+                    current_run = runs.next().unwrap_or_default();
+                };
+                current_run
+                    .drain(..)
+                    .map(|(at, value)| match value.strip_prefix('\\') {
+                        Some(stripped) => (at, stripped.to_string()),
+                        None => (at, value),
+                    })
+                    .unzip()
+            }
+            Arity::Exactly(amount) => {
+                let mut values: Vec<(usize, String)> = vec![];
+                while values.len() < amount {
+                    if current_run.is_empty() {
+                        match runs.next() {
+                            Some(run) => current_run = run,
+                            None if short_circuit || excused.contains(&*positional.placeholder) => {
+                                break
+                            }
+                            None => {
+                                return Err(fail_with(
+                                    ErrorKind::MissingPositional,
+                                    format_args!(
+                                        "{} requires {} arguments{}",
+                                        positional.placeholder,
+                                        amount,
+                                        dash_hint(&unknown)
+                                    ),
+                                )
+                                .into());
+                            }
+                        };
+                    };
+                    let needed = (amount - values.len()).min(current_run.len());
+                    values.extend(current_run.drain(..needed));
+                }
+                values.into_iter().unzip()
+            }
+        };
+        if !positional.allows_empty() && values.iter().any(String::is_empty) {
+            return Err(fail_with(
+                ErrorKind::InvalidValue,
+                format_args!("{} can't be empty", positional.placeholder),
+            )
+            .into());
+        };
+        if !at.is_empty() {
+            positions.insert(positional.placeholder.to_string(), at);
+        };
+        return_map.get_mut(&*positional.placeholder).unwrap().1 = values;
+    }
+
+    // expanding file lists
+    for positional in argument.positionals.iter() {
+        if let Some(expansion) = &positional.file_expansion {
+            let lists = match &expansion.files_from {
+                Some(key) => return_map.get(key).unwrap().1.clone(),
+                None => vec![],
+            };
+            let values = &mut return_map.get_mut(&*positional.placeholder).unwrap().1;
+            *values = expand::expand_file_list(values, &lists, &argument.limits)?;
+        };
+    }
+
+    for positional in argument.positionals.iter() {
+        let values = &mut return_map.get_mut(&*positional.placeholder).unwrap().1;
+        *values = normalize::apply(&positional.normalizers, std::mem::take(values));
+    }
+
+    interpolate::resolve(argument, &mut return_map, &mut sources);
+
+    // options required because of the value of another argument, which a default doesn't satisfy
+    for option in argument.options.iter().filter(|_| !short_circuit) {
+        let used = return_map.get(&option.key()).unwrap().0
+            && sources.get(&option.key()) != Some(&Source::Default);
+        let condition = option.required_if.iter().find(|(name, value)| {
+            let key = match argument.option_id(name) {
+                Some(id) => argument.options[id].key(),
+                None if argument
+                    .positionals
+                    .iter()
+                    .any(|positional| positional.placeholder == *name) =>
+                {
+                    name.to_owned()
+                }
+                None => panic!(
+                    "Error! \"{}\" is required if \"{}\" has a value, but there's no such argument",
+                    option.display_name(),
+                    name
+                ),
+            };
+            return_map.get(&key).unwrap().1.contains(value)
+        });
+        if let Some((name, value)) = condition.filter(|_| !used) {
+            return Err(fail_with(
+                ErrorKind::MissingRequired,
+                format_args!(
+                    "{} is required when {} is \"{}\"",
+                    option.display_name(),
+                    name,
+                    value
+                ),
+            )
+            .into());
+        };
+    }
+
+    if !short_circuit {
+        exclusive::check(argument, &return_map, &sources)?;
+    };
+
+    // values with a custom storage are moved there, now that they're final
+    let sinks = argument
+        .options
+        .iter()
+        .map(|option| (option.key(), &option.sink))
+        .chain(
+            argument
+                .positionals
+                .iter()
+                .map(|positional| (positional.placeholder.to_string(), &positional.sink)),
+        );
+    for (key, sink) in sinks {
+        if let Some(sink) = sink {
+            sink::drain(sink, &mut return_map.get_mut(&key).unwrap().1);
+        };
+    }
+
+    if argument.collect_stats {
+        let stored_values: usize = return_map.values().map(|value| value.1.len()).sum();
+        let stored_vectors = return_map
+            .values()
+            .filter(|value| !value.1.is_empty())
+            .count();
+        argument.stats = Some(ParseStats {
+            tokens: tokens.len(),
+            options_matched: argument
+                .options
+                .iter()
+                .filter(|option| return_map.get(&option.key()).is_some_and(|value| value.0))
+                .count(),
+            positionals_matched: argument.positionals.len(),
+            // the arguments themselves, every key, and every stored value and vector
+            allocations_estimate: tokens.len() + return_map.len() + stored_values + stored_vectors,
+            elapsed: started.elapsed(),
+        });
+    };
+
+    let kinds = argument
+        .options
+        .iter()
+        .map(|option| match option.arity {
+            Arity::Exactly(0) => (option.key(), Kind::Flag),
+            _ => (option.key(), Kind::Option),
+        })
+        .chain(
+            argument
+                .positionals
+                .iter()
+                .map(|positional| (positional.placeholder.to_string(), Kind::Positional)),
+        )
+        .collect();
+    let lists = argument
+        .options
+        .iter()
+        .filter(|option| {
+            !matches!(option.arity, Arity::Exactly(0 | 1))
+                || option.merge_strategy != MergeStrategy::Replace
+        })
+        .map(|option| option.key())
+        .chain(
+            argument
+                .positionals
+                .iter()
+                .filter(|positional| positional.arity != Arity::Exactly(1))
+                .map(|positional| positional.placeholder.to_string()),
+        )
+        .collect();
+    // a flag used again after --no-NAME is on
+    negated.retain(|key| !return_map.get(key).unwrap().0);
+    let mut parsed = ParsedArgs::new(return_map, sources, kinds, subcommand);
+    parsed.set_negated(negated);
+    parsed.set_lists(lists);
+    parsed.set_rest(rest);
+    parsed.set_trailing(trailing);
+    parsed.set_value_files(value_files);
+    parsed.set_secret_args(given_positions(&origins, secret_tokens));
+    parsed.set_positions(positions);
+    parsed.map_positions(&|position| origins.get(position).copied().flatten());
+    hooks::run_post_parse(&argument.post_parse_hooks, &mut parsed);
+    hooks::run_audit(&argument.audit_hooks, &parsed);
+    if argument.show_config_option
+        && parsed
+            .get(show_config::SHOW_CONFIG_OPTION)
+            .is_some_and(|value| value.0)
+    {
+        show_config::print(&parsed);
+        return Err(Stop::Exited(0));
+    };
+    Ok(parsed)
+}
+
+// Converts the arguments of the process to Strings, failing on the first one that isn't valid
+// UTF-8 instead of panicking like std::env::args would
+pub(crate) fn string_args(
+    arguments: impl IntoIterator<Item = OsString>,
+) -> Result<Vec<String>, Error> {
+    arguments
+        .into_iter()
+        .enumerate()
+        .map(|(index, argument)| {
+            argument.into_string().map_err(|argument| {
+                fail_with(
+                    ErrorKind::InvalidEncoding(index),
+                    format_args!(
+                        "Argument {} isn't valid UTF-8: \"{}\"",
+                        index,
+                        sanitize(&argument.to_string_lossy())
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+// Records where the values of an option come from. If they come from another place than its
+// earlier values, and the option combines the values of different places, the earlier values
+// are returned to be merged with the new ones
+fn set_source(
+    argument: &Argument,
+    id: usize,
+    source: &Source,
+    sources: &mut BTreeMap<String, Source>,
+    return_map: &ParseResult,
+) -> Option<Vec<String>> {
+    let option = &argument.options[id];
+    event!(option = %option.display_name(), source = ?source, "matched an option");
+    let earlier = sources.insert(option.key(), source.to_owned())?;
+    (earlier != *source && option.merge_strategy != MergeStrategy::Replace)
+        .then(|| return_map.get(&option.key()).unwrap().1.clone())
+}
+
+// The word after the help option, as in "mytool --help output", which limits the help page to
+// the entries mentioning it. The name of a subcommand isn't a query
+fn help_query<'a>(argument: &Argument, tokens: &'a [String]) -> Option<&'a str> {
+    let help = argument.options.iter().find(|option| option.key() == "h")?;
+    let at = tokens
+        .iter()
+        .position(|token| match token.strip_prefix("--") {
+            Some(long) => help.long.as_deref() == Some(long),
+            None => help
+                .short
+                .is_some_and(|short| *token == format!("-{}", short)),
+        })?;
+    tokens
+        .get(at + 1)
+        .filter(|query| !query.starts_with('-') && !subcommand::is_subcommand(argument, query))
+        .map(String::as_str)
+}
+
+// Turns on the flags enabled by the flags that are on, until there are no more. Flags used or
+// turned off on their own are left alone
+fn enable_members(
+    argument: &Argument,
+    negated: &BTreeSet<String>,
+    return_map: &mut ParseResult,
+    sources: &mut BTreeMap<String, Source>,
+) {
+    let mut enabled = true;
+    while enabled {
+        enabled = false;
+        for option in argument
+            .options
+            .iter()
+            .filter(|option| !option.enables.is_empty())
+        {
+            if !return_map.get(&option.key()).unwrap().0 {
+                continue;
+            };
+            for name in option.enables.iter() {
+                let member = match argument.option_id(name) {
+                    Some(id) if argument.options[id].arity == Arity::Exactly(0) => {
+                        &argument.options[id]
+                    }
+                    _ => panic!(
+                        "Error! \"{}\" enables \"{}\", but there's no such flag",
+                        option.display_name(),
+                        name
+                    ),
+                };
+                let key = member.key();
+                if return_map.get(&key).unwrap().0 || negated.contains(&key) {
+                    continue;
+                };
+                *return_map.get_mut(&key).unwrap() = (true, vec![]);
+                if let Some(source) = sources.get(&option.key()).cloned() {
+                    sources.insert(key, source);
+                };
+                enabled = true;
+            }
+        }
+    }
+}
+
+// The error for an option that doesn't exist, which is only an error in strict mode, suggesting
+// the closest long option. A group of short options like -verbose is often meant as a long one
+pub(crate) fn fail_unknown(argument: &Argument, token: &str) -> Error {
+    let longs = long_names(argument);
+    let message = match token.strip_prefix("--") {
+        Some(long) => {
+            let used_as = without_value(long);
+            format!(
+                "Unknown option {}{}",
+                sanitize(&used_as),
+                did_you_mean(longs, &used_as)
+            )
+        }
+        None => {
+            let short = token
+                .chars()
+                .skip(1)
+                .find(|short| !argument.shorts.contains_key(short))
+                .unwrap_or_default();
+            match token.chars().count() {
+                2 => format!("Unknown option {}", sanitize(token)),
+                _ => format!(
+                    "Unknown option -{} in {}{}",
+                    sanitize(&short.to_string()),
+                    sanitize(token),
+                    did_you_mean(longs, &format!("-{}", token))
+                ),
+            }
+        }
+    };
+    fail_with(ErrorKind::UnknownOption, message)
+}
+
+// Returns the long option an unknown long option (like --hlep) is likely a typo of
+pub(crate) fn misspelled(argument: &Argument, long: &str) -> Option<String> {
+    suggest(long_names(argument), &without_value(long))
+}
+
+// The long names of the options, with their dashes
+fn long_names(argument: &Argument) -> impl Iterator<Item = String> + '_ {
+    argument
+        .options
+        .iter()
+        .filter_map(|option| option.long.as_ref().map(|long| format!("--{}", long)))
+}
+
+// The long option of a token without its dashes, leaving out a value attached using =
+fn without_value(long: &str) -> String {
+    format!("--{}", long.split_once('=').map_or(long, |(long, _)| long))
+}
+
+// The negatable flag turned off by --no-NAME, unless NAME is an option of its own
+fn negated_option(argument: &Argument, long: &str) -> Option<usize> {
+    let id = *argument.longs.get(long.strip_prefix("no-")?)?;
+    argument.options[id].negatable.then_some(id)
+}
+
+// Notes the tokens holding the values of a secret option
+fn note_secret(
+    argument: &Argument,
+    id: usize,
+    values: Range<usize>,
+    secret_tokens: &mut Vec<usize>,
+) {
+    if argument.options[id].secret {
+        secret_tokens.extend(values);
+    };
+}
+
+// Notes the tokens an option was given by
+fn note_positions(
+    argument: &Argument,
+    id: usize,
+    tokens: Range<usize>,
+    positions: &mut BTreeMap<String, Vec<usize>>,
+) {
+    positions
+        .entry(argument.options[id].key())
+        .or_default()
+        .extend(tokens);
+}
+
+// Where in the tokens given to the parser every token returned by the pre-parse hooks came from,
+// None for tokens that weren't given. The hooks can change the tokens in any way, so the ones at
+// the start and the end they left alone are matched, and the ones in between are taken as new
+fn origins(given: &[String], tokens: &[String]) -> Vec<Option<usize>> {
+    let start = given
+        .iter()
+        .zip(tokens)
+        .take_while(|(given, token)| given == token)
+        .count();
+    let end = given[start..]
+        .iter()
+        .rev()
+        .zip(tokens[start..].iter().rev())
+        .take_while(|(given, token)| given == token)
+        .count();
+    let mut origins: Vec<Option<usize>> = (0..start).map(Some).collect();
+    origins.resize(tokens.len() - end, None);
+    origins.extend((given.len() - end..given.len()).map(Some));
+    origins
+}
+
+// Turns positions in the expanded tokens into positions in the tokens given to the parser, see
+// origins
+fn given_positions(origins: &[Option<usize>], positions: Vec<usize>) -> Vec<usize> {
+    let mut positions: Vec<usize> = positions
+        .into_iter()
+        .filter_map(|position| origins.get(position).copied().flatten())
+        .collect();
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
+
+// Applies the duplicates policy if the option was already used in the same place. Returns the
+// earlier values if they're kept instead of the ones about to be taken
+fn check_duplicate(
+    argument: &Argument,
+    id: usize,
+    used_as: &str,
+    source: &Source,
+    sources: &BTreeMap<String, Source>,
+    return_map: &ParseResult,
+) -> Result<Option<(bool, Vec<String>)>, Error> {
+    let option = &argument.options[id];
+    let earlier = return_map.get(&option.key()).unwrap();
+    if !earlier.0 || sources.get(&option.key()) != Some(source) {
+        return Ok(None);
+    };
+    match option.duplicates.unwrap_or(argument.duplicates) {
+        Duplicates::TakeLast => Ok(None),
+        Duplicates::Warn => {
+            output::warn(format_args!(
+                "{} was used more than once, only the last use counts",
+                used_as
+            ));
+            Ok(None)
+        }
+        Duplicates::TakeFirst => Ok(Some(earlier.clone())),
+        Duplicates::Error => Err(fail_with(
+            ErrorKind::DuplicateOption,
+            format_args!("{} can only be used once", used_as),
+        )),
+    }
+}
+
+// Puts back the values of the first use of an option, see check_duplicate
+fn keep_first(
+    argument: &Argument,
+    id: usize,
+    first: Option<(bool, Vec<String>)>,
+    return_map: &mut ParseResult,
+) {
+    if let Some(first) = first {
+        *return_map.get_mut(&argument.options[id].key()).unwrap() = first;
+    };
+}
+
+// Combines the values just taken by an option with its earlier values from another place
+fn merge_layers(
+    argument: &Argument,
+    id: usize,
+    earlier: Option<Vec<String>>,
+    return_map: &mut ParseResult,
+) {
+    let Some(mut earlier) = earlier else {
+        return;
+    };
+    let option = &argument.options[id];
+    let values = &mut return_map.get_mut(&option.key()).unwrap().1;
+    match option.merge_strategy {
+        MergeStrategy::Replace => {}
+        MergeStrategy::Append => {
+            earlier.append(values);
+            *values = earlier;
+        }
+        MergeStrategy::Prepend => values.append(&mut earlier),
+    };
+}
+
+// The arguments in the environment variable, split like a shell would
+fn options_env(variable: &str) -> Result<Vec<String>, Error> {
+    let Some(value) = std::env::var_os(variable) else {
+        return Ok(vec![]);
+    };
+    let Some(value) = value.to_str() else {
+        return Err(fail(format_args!("{} isn't valid UTF-8", variable)));
+    };
+    shell::split(value).ok_or_else(|| {
+        fail(format_args!(
+            "{} has a quote that isn't closed: \"{}\"",
+            variable,
+            sanitize(value)
+        ))
+    })
+}
+
+// Fails on a space after the = attaching a value, as in "--name= value", which would otherwise
+// give the option an empty value. Options that decide about empty values with allow_empty are
+// left alone. A space before it, as in "--name =value" or "--name = value", still gives the option
+// a value ("=value" or "="), which may be meant, so it's only warned about
+fn check_spacing(
+    option: &OptionSpec,
+    used_as: &str,
+    attached: Option<&str>,
+    remaining: &[String],
+) -> Result<(), Error> {
+    if option.arity == Arity::Exactly(0) {
+        return Ok(());
+    };
+    match (attached, remaining) {
+        (Some(""), [value, ..]) if option.allow_empty.is_none() && !value.starts_with('-') => {
+            Err(fail(format_args!(
+                "Unexpected space after \"=\" in {}= {}, use {}={}",
+                used_as,
+                sanitize(value),
+                used_as,
+                sanitize(value)
+            )))
+        }
+        (None, [equals, value, ..]) if equals == "=" => {
+            output::warn(format_args!(
+                "\"{} = {}\" gives {} the value \"=\", use {}={} for \"{}\"",
+                used_as,
+                sanitize(value),
+                used_as,
+                used_as,
+                sanitize(value),
+                sanitize(value)
+            ));
+            Ok(())
+        }
+        (None, [value, ..]) if value.len() > 1 && value.starts_with('=') => {
+            output::warn(format_args!(
+                "\"{} {}\" gives {} the value \"{}\", use {}{} for \"{}\"",
+                used_as,
+                sanitize(value),
+                used_as,
+                sanitize(value),
+                used_as,
+                sanitize(value),
+                sanitize(&value[1..])
+            ));
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+// The tokens the values of an option are taken from. With reserve_positionals, an infinite option
+// whose values would reach the end of the command line leaves the last ones to the positional
+// arguments that are still missing values
+fn value_tokens<'a>(
+    argument: &Argument,
+    id: usize,
+    remaining: &'a [String],
+    positional_count: usize,
+) -> &'a [String] {
+    let option = &argument.options[id];
+    let all_values = remaining
+        .iter()
+        .all(|token| !token.starts_with('-') && argument.terminator.as_ref() != Some(token));
+    if !argument.reserve_positionals
+        || option.arity != Arity::Infinite
+        || option.require_equals
+        || option.until.is_some()
+        || !all_values
+    {
+        return remaining;
+    };
+    let needed: usize = argument
+        .positionals
+        .iter()
+        .filter(|positional| !positional.command)
+        .map(|positional| match positional.arity {
+            Arity::Exactly(amount) => amount,
+            Arity::Infinite => 0,
+        })
+        .sum();
+    let missing = needed.saturating_sub(positional_count);
+    &remaining[..remaining.len().saturating_sub(missing)]
+}
+
+// Stores the values of an option (starting with the value attached using =, if any), and returns
+// the amount of tokens taken from the remaining ones
+fn take_values(
+    argument: &Argument,
+    id: usize,
+    used_as: &str,
+    attached: Option<&str>,
+    remaining: &[String],
+    return_map: &mut ParseResult,
+    value_files: &mut BTreeMap<String, Vec<String>>,
+) -> Result<usize, Error> {
+    let option = &argument.options[id];
+    let mut values: Vec<String> = attached.map(str::to_string).into_iter().collect();
+    // the tokens taken besides the values, i.e. the keyword ending them
+    let mut skipped = 0;
+    match option.arity {
+        // the value can only be attached, so the next argument is never taken
+        _ if option.require_equals => {}
+        _ if option.until.is_some() => {
+            let keyword = option.until.as_deref().unwrap_or_default();
+            let most = match option.arity {
+                Arity::Exactly(amount) => amount,
+                Arity::Infinite => usize::MAX,
+            };
+            let mut remaining = remaining.iter();
+            while values.len() < most {
+                match remaining.next() {
+                    Some(token) if token == keyword => {
+                        skipped = 1;
+                        break;
+                    }
+                    Some(token) => values.push(token.to_owned()),
+                    None => {
+                        return Err(fail_with(
+                            ErrorKind::MissingOptionValue,
+                            format_args!(
+                                "The values of {} have to end with \"{}\"",
+                                used_as, keyword
+                            ),
+                        ))
+                    }
+                };
+            }
+        }
+        Arity::Exactly(0) if attached.is_some() => {
+            return Err(fail_with(
+                ErrorKind::UnexpectedValue,
+                format_args!("{} doesn't take a value", used_as),
+            ));
+        }
+        // infinite arguments are terminated by anything starting with -, which can be escaped
+        // using \
+        Arity::Infinite => values.extend(
+            remaining
+                .iter()
+                .take_while(|token| {
+                    !token.starts_with('-') && argument.terminator.as_ref() != Some(token)
+                })
+                .map(|token| token.strip_prefix('\\').unwrap_or(token).to_string()),
+        ),
+        Arity::Exactly(amount) => {
+            let needed = amount - values.len();
+            if remaining.len() < needed {
+                return Err(fail_with(
+                    ErrorKind::MissingOptionValue,
+                    format_args!("{} requires {} arguments", used_as, amount),
+                ));
+            };
+            values.extend(remaining[..needed].iter().cloned());
+        }
+    };
+    let taken = values.len() + skipped - attached.map_or(0, |_| 1);
+    let mut files: Vec<String> = vec![];
+    if option.value_files {
+        for value in values.iter_mut() {
+            if let Some((path, contents)) = expand::value_file(used_as, value, &argument.limits)? {
+                files.push(path);
+                *value = contents;
+            };
+        }
+    };
+    value_files.insert(option.key(), files);
+    if !option.allows_empty() && values.iter().any(String::is_empty) {
+        return Err(fail_with(
+            ErrorKind::InvalidValue,
+            format_args!("{} can't be empty", used_as),
+        ));
+    };
+    if let Some(choices) = &option.choices {
+        choices.check(used_as, &values)?;
+    };
+    *return_map.get_mut(&option.key()).unwrap() =
+        (true, normalize::apply(&option.normalizers, values));
+    Ok(taken)
+}
+
+// The amount of the remaining tokens an option takes as its values, by the rules of take_values,
+// for looking through the command line before it's parsed
+pub(crate) fn values_taken(
+    argument: &Argument,
+    id: usize,
+    attached: bool,
+    remaining: &[String],
+    positional_count: usize,
+) -> usize {
+    let option = &argument.options[id];
+    let remaining = value_tokens(argument, id, remaining, positional_count);
+    let attached = usize::from(attached);
+    match option.arity {
+        _ if option.require_equals => 0,
+        _ if option.until.is_some() => {
+            let keyword = option.until.as_deref().unwrap_or_default();
+            let most = match option.arity {
+                Arity::Exactly(amount) => amount.saturating_sub(attached),
+                Arity::Infinite => usize::MAX,
+            };
+            match remaining
+                .iter()
+                .take(most)
+                .position(|token| token == keyword)
+            {
+                Some(end) => end + 1,
+                None => remaining.len().min(most),
+            }
+        }
+        Arity::Infinite => remaining
+            .iter()
+            .take_while(|token| {
+                !token.starts_with('-') && argument.terminator.as_ref() != Some(token)
+            })
+            .count(),
+        Arity::Exactly(amount) => amount.saturating_sub(attached).min(remaining.len()),
+    }
+}
+
+// The amount of positional values after which a command tail starts, if the last positional
+// argument is one
+pub(crate) fn tail_at(argument: &Argument) -> Option<usize> {
+    match argument.positionals.split_last() {
+        Some((last, others)) if last.command => Some(
+            others
+                .iter()
+                .map(|positional| match positional.arity {
+                    Arity::Exactly(amount) => amount,
+                    Arity::Infinite => 0,
+                })
+                .sum(),
+        ),
+        _ => None,
+    }
+}
+
+// Returns the id of the number option if the token is a dash followed by digits
+pub(crate) fn number_option(argument: &Argument, token: &str) -> Option<usize> {
+    let digits = token.strip_prefix('-')?;
+    if digits.is_empty() || !digits.chars().all(|digit| digit.is_ascii_digit()) {
+        return None;
+    };
+    argument.option_id(argument.number_option.as_deref()?)
+}
+
+// Negative numbers (e.g. -5) are values, unless the digit is registered as an option
+pub(crate) fn is_negative_number(argument: &Argument, token: &str) -> bool {
+    token
+        .chars()
+        .nth(1)
+        .is_some_and(|first| first.is_ascii_digit() && !argument.shorts.contains_key(&first))
+        && token[1..].parse::<f64>().is_ok()
+}
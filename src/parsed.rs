@@ -0,0 +1,788 @@
+use crate::{os, sanitize::sanitize, shell, Error, ErrorKind};
+use std::{
+    collections::{btree_map, BTreeMap, BTreeSet},
+    ffi::OsString,
+    fmt::Display,
+    ops::{Deref, DerefMut, Range},
+    str::FromStr,
+};
+
+/// The result of `Argument::parse_args`
+///
+/// It dereferences to a map from the name of every argument to a tuple of whether it was used
+/// and the values it got, so it can be used just like a `BTreeMap<String, (bool, Vec<String>)>`.
+/// Optional arguments are stored under their short name, or their long name if they don't have
+/// a short name, and positional arguments under their placeholder.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_option('f', "foo", "0", None);
+/// let remote = arguments.add_subcommand("remote", "Manage remotes");
+/// remote.add_arg("NAME", "1", None);
+///
+/// let parsed_arguments = arguments.parse_args(None);
+/// if parsed_arguments.get("f").unwrap().0 {
+///     // ...
+/// }
+/// if let Some(("remote", remote_arguments)) = parsed_arguments.subcommand() {
+///     let name = &remote_arguments.get("NAME").unwrap().1[0];
+///     // ...
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ParsedArgs {
+    values: BTreeMap<String, (bool, Vec<String>)>,
+    sources: BTreeMap<String, Source>,
+    // the names of the arguments in the order they were added, with their kind
+    kinds: Vec<(String, Kind)>,
+    subcommand: Option<(String, Box<ParsedArgs>)>,
+    raw_args: Vec<String>,
+    rest: Vec<String>,
+    // the arguments after "--", if it was used
+    trailing: Option<Vec<String>>,
+    // the files the values of options were read from
+    value_files: BTreeMap<String, Vec<String>>,
+    // the positions of the arguments holding values of secret options
+    secret_args: Vec<usize>,
+    // the positions of the arguments every argument was given by, see spans
+    positions: BTreeMap<String, Vec<usize>>,
+    // the bytes of the command line every argument (after the name of the program) is made of
+    token_spans: Vec<Range<usize>>,
+    // the program of an external subcommand
+    external: Option<String>,
+    // the flags turned off with --no-NAME
+    negated: BTreeSet<String>,
+    // the untouched command line as OsStrings, including the name of the program, see
+    // parse_args_os
+    raw_args_os: Vec<OsString>,
+    // the arguments that can hold more than one value, see to_env_exports
+    lists: BTreeSet<String>,
+}
+
+/// Where an argument of the command line is, see `ParsedArgs::spans`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Span {
+    /// The position of the argument in `ParsedArgs::raw_args`
+    pub index: usize,
+    /// The bytes of the command line the argument is made of, quotes included
+    pub bytes: Range<usize>,
+}
+
+/// The kind of an argument in the parse result, see `ParsedArgs::entries`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Kind {
+    /// An optional argument taking no values
+    Flag,
+    /// An optional argument taking values
+    Option,
+    /// A positional argument
+    Positional,
+    /// The subcommand that was used, which has no values of its own
+    Subcommand,
+}
+
+/// Whether a flag is on, see `ParsedArgs::presence`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Presence {
+    /// The flag was used
+    Enabled,
+    /// The flag was turned off with --no-NAME, see `OptionSpec::negatable`
+    Disabled,
+    /// The flag wasn't used at all
+    Unset,
+}
+
+/// Where the value of an argument came from
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Source {
+    /// The command line
+    CommandLine,
+    /// The named profile, loaded with --profile
+    Profile(String),
+    /// The default of the option, since it wasn't used
+    Default,
+    /// The environment variable holding default arguments, set with `Argument::set_options_env`,
+    /// or the variable of the option, set with `OptionSpec::env`
+    Environment(String),
+    /// An external store, by the name it gave itself, see `ValueSource`
+    External(String),
+}
+
+impl ParsedArgs {
+    pub(crate) fn new(
+        values: BTreeMap<String, (bool, Vec<String>)>,
+        sources: BTreeMap<String, Source>,
+        kinds: Vec<(String, Kind)>,
+        subcommand: Option<(String, ParsedArgs)>,
+    ) -> Self {
+        Self {
+            values,
+            sources,
+            kinds,
+            subcommand: subcommand.map(|(name, parsed)| (name, Box::new(parsed))),
+            raw_args: vec![],
+            rest: vec![],
+            trailing: None,
+            value_files: BTreeMap::new(),
+            secret_args: vec![],
+            positions: BTreeMap::new(),
+            token_spans: vec![],
+            external: None,
+            negated: BTreeSet::new(),
+            raw_args_os: vec![],
+            lists: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the name and the parsed arguments of the subcommand that was used, if any
+    pub fn subcommand(&self) -> Option<(&str, &ParsedArgs)> {
+        self.subcommand
+            .as_ref()
+            .map(|(name, parsed)| (name.as_str(), parsed.as_ref()))
+    }
+
+    /// Returns every subcommand that was used, from the outermost to the innermost, with its
+    /// parsed arguments
+    ///
+    /// Every subcommand has its own result, holding only its own arguments (and the global
+    /// options), so `name remote add` is walked as "remote" and then "add".
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// let remote = arguments.add_subcommand("remote", "Manage remotes");
+    /// let add = remote.add_subcommand("add", "Add a remote");
+    /// add.add_arg("NAME", "1", None);
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// let path: Vec<&str> = parsed_arguments.subcommands().map(|(name, _)| name).collect();
+    /// if path == ["remote", "add"] {
+    ///     let name = &parsed_arguments.innermost().get("NAME").unwrap().1[0];
+    ///     // ...
+    /// }
+    /// ```
+    pub fn subcommands(&self) -> impl Iterator<Item = (&str, &ParsedArgs)> {
+        std::iter::successors(self.subcommand(), |(_, parsed)| parsed.subcommand())
+    }
+
+    /// Returns the parsed arguments of the innermost subcommand that was used, or these parsed
+    /// arguments if no subcommand was used
+    pub fn innermost(&self) -> &ParsedArgs {
+        self.subcommands().last().map_or(self, |(_, parsed)| parsed)
+    }
+
+    /// Returns the untouched command line, including the name of the program (argv\[0\])
+    ///
+    /// These are the arguments as they were before any hooks, aliases or profiles were applied,
+    /// which is useful for logging, auditing, or running the program again. When parse_args was
+    /// given a custom argument-list, the name passed to `Argument::new` is used as argv\[0\].
+    ///
+    /// Only the result of parse_args itself has them, the results of subcommands don't.
+    pub fn raw_args(&self) -> &[String] {
+        &self.raw_args
+    }
+
+    pub(crate) fn set_raw_args(&mut self, raw_args: Vec<String>) {
+        self.raw_args = raw_args;
+    }
+
+    /// Returns the positions in `raw_args` of the arguments holding values of secret options
+    ///
+    /// These are the arguments to overwrite when the program hides its command line, e.g. by
+    /// changing its process title, or to leave out of logs (see `OptionSpec::secret`). Values
+    /// attached to the option (as in --token=VALUE) are in the same argument as the option, so
+    /// the whole argument is listed. Secret values from profiles or the environment aren't on
+    /// the command line, so they're not listed.
+    ///
+    /// Only the result of parse_args itself has them, the results of subcommands don't.
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Opt};
+    ///
+    /// fn main() {
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('t', "token").takes(1).secret());
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// let mut logged = parsed_arguments.raw_args().to_vec();
+    /// for position in parsed_arguments.secret_args() {
+    ///     logged[*position] = "***".to_string();
+    /// }
+    /// println!("Started as {}", logged.join(" "));
+    /// }
+    /// ```
+    pub fn secret_args(&self) -> &[usize] {
+        &self.secret_args
+    }
+
+    pub(crate) fn set_secret_args(&mut self, secret_args: Vec<usize>) {
+        self.secret_args = secret_args;
+    }
+
+    pub(crate) fn take_secret_args(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.secret_args)
+    }
+
+    /// Returns where an argument was given on the command line, one span per argument of the
+    /// command line it was given by
+    ///
+    /// Meant for tools highlighting the arguments of a command line, like interactive shells and
+    /// editors. The spans of an option are the option itself and its values, and the ones of a
+    /// positional argument are its values. Every span has the position of the argument in
+    /// `raw_args` and its bytes in the command line, which is the string given to
+    /// `Argument::parse_args_str`, or else the arguments after the name of the program joined by
+    /// spaces. Arguments that got their values from anywhere but the command line (like a
+    /// default or a profile) have no spans
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | name      | &str | The name of the argument, as used in the parsed result |
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(taap::Opt::new('o', "output").takes(1).help("Where to write to"));
+    /// let line = "--output 'my file.txt'";
+    /// let parsed_arguments = arguments.parse_args_str(line);
+    ///
+    /// for span in parsed_arguments.spans("o") {
+    ///     // prints "--output" and "'my file.txt'"
+    ///     println!("{}", &line[span.bytes]);
+    /// }
+    /// ```
+    pub fn spans(&self, name: &str) -> Vec<Span> {
+        self.positions
+            .get(name)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .filter_map(|&index| {
+                let bytes = self.token_spans.get(index.checked_sub(1)?)?;
+                Some(Span {
+                    index,
+                    bytes: bytes.to_owned(),
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn set_positions(&mut self, positions: BTreeMap<String, Vec<usize>>) {
+        self.positions = positions;
+    }
+
+    // Moves the positions (including the ones of the subcommand), leaving out the ones mapped
+    // to None
+    pub(crate) fn map_positions(&mut self, map: &dyn Fn(usize) -> Option<usize>) {
+        for positions in self.positions.values_mut() {
+            *positions = positions
+                .iter()
+                .filter_map(|&position| map(position))
+                .collect();
+        }
+        self.positions.retain(|_, positions| !positions.is_empty());
+        if let Some((_, subcommand)) = &mut self.subcommand {
+            subcommand.map_positions(map);
+        };
+    }
+
+    pub(crate) fn set_token_spans(&mut self, token_spans: Vec<Range<usize>>) {
+        if let Some((_, subcommand)) = &mut self.subcommand {
+            subcommand.set_token_spans(token_spans.clone());
+        };
+        self.token_spans = token_spans;
+    }
+
+    /// Returns the program and the arguments of a command tail (see `PositionalSpec::command`),
+    /// or None if no command was given
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Argument, Pos};
+    ///
+    /// fn main() {
+    /// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Pos::new("COMMAND").command().help("The command to run"));
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// if let Some((program, args)) = parsed_arguments.command("COMMAND") {
+    ///     std::process::Command::new(program).args(args).status().unwrap();
+    /// }
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type | Description                         |
+    /// |-----------|------|-------------------------------------|
+    /// | name      | &str | The placeholder of the command tail |
+    ///
+    pub fn command(&self, name: &str) -> Option<(&str, &[String])> {
+        let (program, args) = self.values.get(name)?.1.split_first()?;
+        Some((program.as_str(), args))
+    }
+
+    /// Returns the program and the arguments of an external subcommand (see
+    /// `Argument::add_external_subcommand`), or None if these aren't the parsed arguments of one
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.discover_external_subcommands("mytool-");
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// if let Some((program, args)) = parsed_arguments.subcommand().and_then(|(_, sub)| sub.external()) {
+    ///     std::process::Command::new(program).args(args).status().unwrap();
+    /// }
+    /// ```
+    pub fn external(&self) -> Option<(&str, &[String])> {
+        let program = self.external.as_deref()?;
+        Some((program, &self.rest))
+    }
+
+    pub(crate) fn set_external(&mut self, external: Option<String>) {
+        self.external = external;
+    }
+
+    /// Returns the arguments after the terminator set with `Argument::set_terminator`, exactly
+    /// as they were given
+    ///
+    /// When a subcommand was used, the terminator ends the command line of the subcommand, so
+    /// the arguments are in the result of the subcommand.
+    pub fn rest(&self) -> &[String] {
+        &self.rest
+    }
+
+    pub(crate) fn set_rest(&mut self, rest: Vec<String>) {
+        self.rest = rest;
+    }
+
+    /// Returns the arguments after "--" (up to the terminator), exactly as they were given, or
+    /// None if "--" wasn't used
+    ///
+    /// The arguments are still the values of the positional arguments, this tells which of them
+    /// were passed through explicitly, e.g. to hand them to another program. "--" belongs to
+    /// the command it's used in: after the name of a subcommand the arguments are in the result
+    /// of the innermost subcommand, while "--" before it makes the name of the subcommand a
+    /// positional argument like any other.
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("mytool", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments
+    ///     .add_subcommand("run", "Run a program")
+    ///     .add_arg("COMMAND", "+", Some("The program to run, with its arguments"));
+    /// // mytool run -- cargo test --release
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// if let Some(passed) = parsed_arguments.innermost().trailing() {
+    ///     std::process::Command::new(&passed[0]).args(&passed[1..]).status().unwrap();
+    /// }
+    /// ```
+    pub fn trailing(&self) -> Option<&[String]> {
+        self.trailing.as_deref()
+    }
+
+    pub(crate) fn set_trailing(&mut self, trailing: Option<Vec<String>>) {
+        self.trailing = trailing;
+    }
+
+    /// Returns the files the values of an option were read from, in the order of the values read
+    /// from a file (see `OptionSpec::value_files`)
+    ///
+    /// | Parameter | Type | Description                                          |
+    /// |-----------|------|------------------------------------------------------|
+    /// | name      | &str | The name of the option, as used in the parsed result |
+    ///
+    pub fn value_files(&self, name: &str) -> &[String] {
+        self.value_files.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns whether a flag was used, turned off with --no-NAME, or not used at all
+    ///
+    /// Unlike the used field of the parsed result, this tells "turned off" apart from "not
+    /// used", e.g. to only detect if the output is a terminal when neither --color nor --no-color
+    /// was used. `ParsedArgs::source` returns where the deciding use came from. Arguments that
+    /// don't exist are Unset
+    ///
+    /// | Parameter | Type | Description                                        |
+    /// |-----------|------|----------------------------------------------------|
+    /// | name      | &str | The name of the flag, as used in the parsed result |
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::{Opt, Presence};
+    ///
+    /// fn main() {
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::new('-', "color").negatable().help("Color the output"));
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// let color = match parsed_arguments.presence("color") {
+    ///     Presence::Enabled => true,
+    ///     Presence::Disabled => false,
+    ///     Presence::Unset => std::env::var_os("NO_COLOR").is_none(),
+    /// };
+    /// }
+    /// ```
+    pub fn presence(&self, name: &str) -> Presence {
+        if self.negated.contains(name) {
+            Presence::Disabled
+        } else if self.values.get(name).is_some_and(|value| value.0) {
+            Presence::Enabled
+        } else {
+            Presence::Unset
+        }
+    }
+
+    /// Returns whether an argument was used, or got a default value
+    ///
+    /// The same as the first field of the tuple in the parsed result, for flags and other
+    /// arguments whose values don't matter. Arguments that don't exist aren't present
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | name      | &str | The name of the argument, as used in the parsed result |
+    ///
+    pub fn is_present(&self, name: &str) -> bool {
+        self.values.get(name).is_some_and(|value| value.0)
+    }
+
+    /// Returns the first value of an argument, converted to a type implementing FromStr
+    ///
+    /// Fails with ErrorKind::MissingRequired if the argument didn't get a value, and with
+    /// ErrorKind::InvalidValue if the value can't be converted, with the error of the conversion
+    /// in the message. Use `ParsedArgs::is_present` first for arguments that don't have to be
+    /// used. The value of a flag is whether it was used, so `get_one::<bool>` works for flags.
+    /// Panics if there's no argument with the name, since that's a bug in the program
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | name      | &str | The name of the argument, as used in the parsed result |
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// fn main() -> Result<(), taap::Error> {
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(taap::Opt::new('p', "port").takes(1).default("8080").help("The port to listen on"));
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// let port: u16 = parsed_arguments.get_one("p")?;
+    /// // ...
+    /// Ok(())
+    /// }
+    /// ```
+    pub fn get_one<T>(&self, name: &str) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        if self.kinds.contains(&(name.to_string(), Kind::Flag)) {
+            return convert(name, &self.is_present(name).to_string());
+        };
+        match self.get_many(name)?.into_iter().next() {
+            Some(value) => Ok(value),
+            None => Err(Error::new(
+                ErrorKind::MissingRequired,
+                format!("{} didn't get a value", name),
+                None,
+            )),
+        }
+    }
+
+    /// Returns every value of an argument, converted to a type implementing FromStr
+    ///
+    /// Works like `ParsedArgs::get_one`, but returns an empty Vec if the argument didn't get
+    /// any values
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | name      | &str | The name of the argument, as used in the parsed result |
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// fn main() -> Result<(), taap::Error> {
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_arg("NUMBERS", "+", Some("The numbers to add up"));
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// let numbers: Vec<f64> = parsed_arguments.get_many("NUMBERS")?;
+    /// println!("{}", numbers.iter().sum::<f64>());
+    /// Ok(())
+    /// }
+    /// ```
+    pub fn get_many<T>(&self, name: &str) -> Result<Vec<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some((_, values)) = self.values.get(name) else {
+            panic!("Error! There's no argument named \"{}\"", name);
+        };
+        values.iter().map(|value| convert(name, value)).collect()
+    }
+
+    /// Returns the values of an argument as OsStrings, with the original values of arguments
+    /// that aren't valid Unicode
+    ///
+    /// Only `Argument::parse_args_os` keeps the original values, otherwise these are the values
+    /// in the parsed result. The originals are taken from the arguments the argument was given
+    /// by, so values that didn't come from the command line, or were changed while parsing (e.g.
+    /// by a normalizer), are returned as they are in the parsed result
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | name      | &str | The name of the argument, as used in the parsed result |
+    ///
+    pub fn values_os(&self, name: &str) -> Vec<OsString> {
+        let mut positions = self.positions.get(name).cloned().unwrap_or_default();
+        self.values
+            .get(name)
+            .map(|(_, values)| {
+                values
+                    .iter()
+                    .map(|value| {
+                        os::take_original(&self.raw_args_os, &mut positions, value)
+                            .unwrap_or_else(|| OsString::from(value))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // The results of subcommands get the command line as well, since their positions are in it
+    pub(crate) fn set_raw_args_os(&mut self, raw_args_os: Vec<OsString>) {
+        if let Some((_, subcommand)) = &mut self.subcommand {
+            subcommand.set_raw_args_os(raw_args_os.clone());
+        };
+        self.raw_args_os = raw_args_os;
+    }
+
+    pub(crate) fn set_negated(&mut self, negated: BTreeSet<String>) {
+        self.negated = negated;
+    }
+
+    pub(crate) fn set_lists(&mut self, lists: BTreeSet<String>) {
+        self.lists = lists;
+    }
+
+    pub(crate) fn set_value_files(&mut self, value_files: BTreeMap<String, Vec<String>>) {
+        self.value_files = value_files;
+    }
+
+    /// Returns where the value of an argument came from, or None if the argument wasn't used
+    ///
+    /// Positional arguments always come from the command line. For a flag turned off with
+    /// --no-NAME, it's where that came from
+    ///
+    /// | Parameter | Type | Description                                            |
+    /// |-----------|------|--------------------------------------------------------|
+    /// | name      | &str | The name of the argument, as used in the parsed result |
+    ///
+    pub fn source(&self, name: &str) -> Option<&Source> {
+        self.sources.get(name)
+    }
+
+    /// Returns every argument in the order it was added, with its kind, whether it was used and
+    /// its values
+    ///
+    /// Unlike iterating over the map itself, which is sorted by name, this keeps the order the
+    /// arguments were declared in, e.g. for reports of what was passed. The subcommand that was
+    /// used, if any, comes last.
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::Kind;
+    ///
+    /// fn main() {
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('v', "verbose", "0", None);
+    /// arguments.add_arg("FILE", "1", None);
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// for (name, kind, used, values) in parsed_arguments.entries() {
+    ///     match kind {
+    ///         Kind::Flag => println!("{}: {}", name, used),
+    ///         _ => println!("{}: {}", name, values.join(" ")),
+    ///     }
+    /// }
+    /// }
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = (&str, Kind, bool, &[String])> {
+        self.kinds
+            .iter()
+            .map(|(name, kind)| {
+                let (used, values) = &self.values[name];
+                (name.as_str(), *kind, *used, values.as_slice())
+            })
+            .chain(
+                self.subcommand()
+                    .map(|(name, _)| (name, Kind::Subcommand, true, &[] as &[String])),
+            )
+    }
+
+    pub(crate) fn kinds(&self) -> &[(String, Kind)] {
+        &self.kinds
+    }
+
+    pub(crate) fn set_source(&mut self, name: &str, source: Source) {
+        self.sources.insert(name.to_string(), source);
+    }
+
+    /// Returns shell export lines for the parsed arguments, meant to be evaluated by a script
+    ///
+    /// A function that produces one `export PREFIX_NAME=value` line per used argument, where
+    /// NAME is the name of the argument in uppercase (with anything but letters and digits
+    /// replaced by underscores), in the order the arguments were added. Flags are exported as
+    /// 1, and everything is quoted for the shell. Unused options are left out.
+    ///
+    /// The value of an argument that can hold more than one value (one taking several values,
+    /// or an option merging its values) is a list of shell words: every value is quoted on its
+    /// own, and they're separated by spaces, so a script gets them back (spaces and all) using
+    /// `eval "set -- $PREFIX_NAME"`. The value of any other argument is exported as it is.
+    ///
+    /// When a subcommand was used, its name is exported as PREFIX_SUBCOMMAND, and its arguments
+    /// are exported with the prefix PREFIX_SUBCOMMANDNAME.
+    ///
+    /// Panics if two arguments get the same variable name, e.g. "dry-run" and "dry_run", or
+    /// an argument named "subcommand" when a subcommand was used, since one would overwrite the
+    /// other.
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add_option('-', "output", "1", None);
+    /// let parsed_arguments = arguments.parse_args(None);
+    ///
+    /// // prints e.g. "export MYAPP_OUTPUT='my file.txt'", so a wrapper script can use
+    /// // eval "$(name --output 'my file.txt')"
+    /// print!("{}", parsed_arguments.to_env_exports("MYAPP"));
+    /// ```
+    ///
+    /// | Parameter | Type | Description                                         |
+    /// |-----------|------|-----------------------------------------------------|
+    /// | prefix    | &str | The prefix of the variable names, e.g. the app name |
+    ///
+    pub fn to_env_exports(&self, prefix: &str) -> String {
+        let prefix = match shell::env_name(prefix) {
+            prefix if prefix.is_empty() || prefix.ends_with('_') => prefix,
+            prefix => prefix + "_",
+        };
+        let mut names: BTreeMap<String, &str> = BTreeMap::new();
+        if self.subcommand.is_some() {
+            names.insert("SUBCOMMAND".to_string(), "the subcommand");
+        };
+        for (name, _) in self.kinds.iter() {
+            if let Some(earlier) = names.insert(shell::env_name(name), name) {
+                panic!(
+                    "Error! \"{}\" and \"{}\" are both exported as {}{}",
+                    earlier,
+                    name,
+                    prefix,
+                    shell::env_name(name)
+                );
+            };
+        }
+        let mut exports = String::new();
+        for (name, kind) in self.kinds.iter() {
+            let (used, values) = &self.values[name];
+            if !used {
+                continue;
+            };
+            let value = match kind {
+                Kind::Flag => "1".to_string(),
+                _ if self.lists.contains(name) => values
+                    .iter()
+                    .map(|value| shell::quote(value))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                // the subcommand is exported below
+                Kind::Option | Kind::Positional | Kind::Subcommand => values.join(" "),
+            };
+            exports.push_str(
+                format!(
+                    "export {}{}={}\n",
+                    prefix,
+                    shell::env_name(name),
+                    shell::quote(&value)
+                )
+                .as_str(),
+            );
+        }
+        if let Some((name, parsed)) = self.subcommand() {
+            exports
+                .push_str(format!("export {}SUBCOMMAND={}\n", prefix, shell::quote(name)).as_str());
+            exports.push_str(&parsed.to_env_exports(&(prefix + name)));
+        };
+        exports
+    }
+
+    /// Returns the map of the parsed arguments, without the subcommand
+    pub fn into_map(self) -> BTreeMap<String, (bool, Vec<String>)> {
+        self.values
+    }
+}
+
+// Converts a value of the argument for get_one and get_many
+fn convert<T>(name: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|err: T::Err| {
+        Error::new(
+            ErrorKind::InvalidValue,
+            format!(
+                "Invalid value \"{}\" for {}: {}",
+                sanitize(value),
+                name,
+                err
+            ),
+            None,
+        )
+    })
+}
+
+impl Deref for ParsedArgs {
+    type Target = BTreeMap<String, (bool, Vec<String>)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl DerefMut for ParsedArgs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
+impl IntoIterator for ParsedArgs {
+    type Item = (String, (bool, Vec<String>));
+    type IntoIter = btree_map::IntoIter<String, (bool, Vec<String>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ParsedArgs {
+    type Item = (&'a String, &'a (bool, Vec<String>));
+    type IntoIter = btree_map::Iter<'a, String, (bool, Vec<String>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl PartialEq<BTreeMap<String, (bool, Vec<String>)>> for ParsedArgs {
+    fn eq(&self, other: &BTreeMap<String, (bool, Vec<String>)>) -> bool {
+        self.subcommand.is_none() && &self.values == other
+    }
+}
+
+impl PartialEq<ParsedArgs> for BTreeMap<String, (bool, Vec<String>)> {
+    fn eq(&self, other: &ParsedArgs) -> bool {
+        other == self
+    }
+}
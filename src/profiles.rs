@@ -0,0 +1,212 @@
+// Named profiles: bundles of arguments stored in a file, loaded with --profile NAME
+//
+// The file is made of sections, each starting with the name of a profile in brackets, followed
+// by the arguments of the profile, split like a shell would (so 'a b' is a single argument).
+// Empty lines and lines starting with # are ignored:
+//
+//     [base]
+//     --host example.com
+//
+//     [prod]
+//     --profile base
+//     --port 443
+//
+// The arguments of every profile used are placed before the rest of the command line, so
+// arguments passed directly always take precedence over the ones from a profile. Both
+// --profile NAME and --profile=NAME load a profile.
+
+use crate::{
+    output::{fail, fail_caused},
+    parse,
+    parsed::Source,
+    sanitize::sanitize,
+    shell, subcommand,
+    suggest::did_you_mean,
+    Argument, Error,
+};
+use std::{collections::BTreeMap, fs};
+
+// The long name of the option loading a profile
+pub(crate) const PROFILE_OPTION: &str = "profile";
+
+// Expands the profiles used on the command line, returning the arguments together with where
+// every one of them came from
+pub(crate) fn expand_profiles(
+    argument: &Argument,
+    path: &str,
+    tokens: Vec<String>,
+) -> Result<(Vec<String>, Vec<Source>), Error> {
+    let names = used_profiles(argument, &tokens);
+    if names.is_empty() {
+        let sources = vec![Source::CommandLine; tokens.len()];
+        return Ok((tokens, sources));
+    };
+
+    let profiles = read_profiles(path)?;
+    let mut expanded: Vec<String> = vec![];
+    let mut sources: Vec<Source> = vec![];
+    for name in names {
+        expand(&name, &profiles, &mut vec![], &mut expanded, &mut sources)?;
+    }
+    sources.extend(vec![Source::CommandLine; tokens.len()]);
+    expanded.extend(tokens);
+    Ok((expanded, sources))
+}
+
+// The names of the profiles used on the command line. It's looked through like the parser does,
+// so it ends at "--", the terminator or a command tail, and the values of other options are
+// skipped: a --profile meant for a wrapped command, or given as the value of an option, isn't
+// loaded
+fn used_profiles(argument: &Argument, tokens: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    // the subcommand the tokens belong to, once one was used
+    let mut subcommand: Option<Argument> = None;
+    let mut positional_count: usize = 0;
+    let mut position: usize = 0;
+    while position < tokens.len() {
+        let command = subcommand.as_ref().unwrap_or(argument);
+        let token = &tokens[position];
+        position += 1;
+        if token == "--" || command.terminator.as_ref() == Some(token) {
+            break;
+        } else if let Some(long) = token.strip_prefix("--") {
+            let (long, attached) = match long.split_once('=') {
+                Some((long, value)) => (long, Some(value)),
+                None => (long, None),
+            };
+            if long == PROFILE_OPTION {
+                if let Some(name) = attached.or(tokens.get(position).map(String::as_str)) {
+                    names.push(name.to_string());
+                };
+                position += usize::from(attached.is_none());
+            } else if let Some(&id) = command.longs.get(long) {
+                position += parse::values_taken(
+                    command,
+                    id,
+                    attached.is_some(),
+                    &tokens[position..],
+                    positional_count,
+                );
+            };
+        } else if parse::number_option(command, token).is_some() {
+            // the number is the value, so nothing else is taken
+        } else if token.len() > 1
+            && token.starts_with('-')
+            && !parse::is_negative_number(command, token)
+        {
+            for short in token.chars().skip(1) {
+                if let Some(&id) = command.shorts.get(&short) {
+                    position += parse::values_taken(
+                        command,
+                        id,
+                        false,
+                        &tokens[position..],
+                        positional_count,
+                    );
+                };
+            }
+        } else if subcommand::is_subcommand(command, token) {
+            // the help dispatch and external subcommands don't parse the rest
+            match subcommand::effective(command, token) {
+                Some(used) if used.external.is_none() => {
+                    subcommand = Some(used);
+                    positional_count = 0;
+                }
+                _ => break,
+            };
+        } else if parse::tail_at(command) == Some(positional_count) {
+            break;
+        } else {
+            positional_count += 1;
+        };
+    }
+    names
+}
+
+// Adds the arguments of a profile, including the ones of the profiles it loads itself
+fn expand(
+    name: &str,
+    profiles: &BTreeMap<String, Vec<String>>,
+    stack: &mut Vec<String>,
+    expanded: &mut Vec<String>,
+    sources: &mut Vec<Source>,
+) -> Result<(), Error> {
+    if stack.iter().any(|used| used == name) {
+        stack.push(name.to_string());
+        return Err(fail(format_args!(
+            "Profile \"{}\" loads itself ({})",
+            sanitize(name),
+            sanitize(&stack.join(" -> "))
+        )));
+    };
+    let Some(arguments) = profiles.get(name) else {
+        return Err(fail(format_args!(
+            "Unknown profile \"{}\"{}",
+            sanitize(name),
+            did_you_mean(profiles.keys(), name)
+        )));
+    };
+    stack.push(name.to_string());
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let long = argument.strip_prefix("--").unwrap_or_default();
+        if long == PROFILE_OPTION {
+            if let Some(included) = arguments.next() {
+                expand(included, profiles, stack, expanded, sources)?;
+            };
+        } else if let Some(included) = long
+            .strip_prefix(PROFILE_OPTION)
+            .and_then(|long| long.strip_prefix('='))
+        {
+            expand(included, profiles, stack, expanded, sources)?;
+        } else {
+            expanded.push(argument.to_owned());
+            sources.push(Source::Profile(name.to_string()));
+        };
+    }
+    stack.pop();
+    Ok(())
+}
+
+fn read_profiles(path: &str) -> Result<BTreeMap<String, Vec<String>>, Error> {
+    match fs::read_to_string(path) {
+        Ok(contents) => parse_profiles(path, &contents),
+        Err(err) => Err(fail_caused(
+            format_args!("Couldn't read the profiles in \"{}\"", sanitize(path)),
+            err,
+        )),
+    }
+}
+
+fn parse_profiles(path: &str, contents: &str) -> Result<BTreeMap<String, Vec<String>>, Error> {
+    let mut profiles: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        };
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            let name = name.trim().to_string();
+            profiles.entry(name.clone()).or_default();
+            current = Some(name);
+        } else if let Some(name) = &current {
+            let Some(arguments) = shell::split(line) else {
+                return Err(fail(format_args!(
+                    "Profile \"{}\" in \"{}\" has a quote that isn't closed",
+                    sanitize(name),
+                    sanitize(path)
+                )));
+            };
+            profiles.get_mut(name).unwrap().extend(arguments);
+        } else {
+            return Err(fail(format_args!(
+                "Arguments outside of a profile: \"{}\"",
+                sanitize(line)
+            )));
+        };
+    }
+    Ok(profiles)
+}
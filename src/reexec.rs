@@ -0,0 +1,261 @@
+// Running the program again, with the same or modified arguments
+
+use crate::{parsed::Kind, spec::Arity, subcommand::effective, Argument, ParsedArgs};
+use std::{
+    ffi::OsString,
+    io,
+    process::{Child, Command},
+};
+
+/// A builder for running the program again, e.g. with sudo or as a daemon
+///
+/// The command line is taken either from the raw arguments the program was started with, or
+/// rebuilt from a parse result, which can be modified first. Arguments can then be added or
+/// removed, and the command can be wrapped in another one (like sudo) before it's spawned or
+/// executed. The program itself is the current executable.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Reexec};
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_option('d', "daemonize", "0", Some("Run in the background"));
+/// let parsed_arguments = arguments.parse_args(None);
+///
+/// if parsed_arguments.get("d").unwrap().0 {
+///     // start again in the background, without --daemonize
+///     Reexec::from_raw(&parsed_arguments)
+///         .remove("-d")
+///         .remove("--daemonize")
+///         .arg("--foreground")
+///         .spawn()
+///         .expect("Couldn't start the daemon");
+///     return;
+/// }
+/// // ...
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Reexec {
+    program: OsString,
+    args: Vec<String>,
+    wrapper: Vec<String>,
+}
+
+impl Reexec {
+    /// Returns a new Reexec with the raw arguments the program was started with
+    ///
+    /// | Parameter | Type        | Description              |
+    /// |-----------|-------------|--------------------------|
+    /// | parsed    | &ParsedArgs | The result of parse_args |
+    ///
+    pub fn from_raw(parsed: &ParsedArgs) -> Self {
+        Self::with_args(
+            parsed.raw_args().first().map(String::as_str),
+            parsed.raw_args().iter().skip(1).cloned().collect(),
+        )
+    }
+
+    /// Returns a new Reexec with the command line rebuilt from the parse result
+    ///
+    /// Every used option is written using its long name if it has one, followed by its values,
+    /// then come the positional arguments and the subcommand. Since the result can be modified
+    /// before it's passed here (e.g. marking a flag as unused), this is the way to change
+    /// options with values
+    ///
+    /// | Parameter | Type        | Description                                  |
+    /// |-----------|-------------|----------------------------------------------|
+    /// | argument  | &Argument   | The Argument the result was parsed with      |
+    /// | parsed    | &ParsedArgs | The (possibly modified) result of parse_args |
+    ///
+    pub fn from_parsed(argument: &Argument, parsed: &ParsedArgs) -> Self {
+        let mut args: Vec<String> = vec![];
+        canonical_args(argument, parsed, &[], &mut args);
+        Self::with_args(parsed.raw_args().first().map(String::as_str), args)
+    }
+
+    fn with_args(argv0: Option<&str>, args: Vec<String>) -> Self {
+        let program = std::env::current_exe()
+            .map(|path| path.into_os_string())
+            .unwrap_or_else(|_| OsString::from(argv0.unwrap_or_default()));
+        Self {
+            program,
+            args,
+            wrapper: vec![],
+        }
+    }
+
+    /// Adds an argument to the end of the command line
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_string());
+        self
+    }
+
+    /// Removes every argument equal to the given one, e.g. a flag
+    pub fn remove(mut self, arg: &str) -> Self {
+        self.args.retain(|existing| existing != arg);
+        self
+    }
+
+    /// Runs the program through another command, e.g. \["sudo", "--"\]
+    pub fn wrap_with<I, S>(mut self, wrapper: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.wrapper = wrapper
+            .into_iter()
+            .map(|part| part.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Returns the arguments the program will be run with
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Returns the command, to configure it further (e.g. its environment) before running it
+    pub fn command(&self) -> Command {
+        match self.wrapper.split_first() {
+            Some((wrapper, wrapper_args)) => {
+                let mut command = Command::new(wrapper);
+                command
+                    .args(wrapper_args)
+                    .arg(&self.program)
+                    .args(&self.args);
+                command
+            }
+            None => {
+                let mut command = Command::new(&self.program);
+                command.args(&self.args);
+                command
+            }
+        }
+    }
+
+    /// Starts the command, while the current program keeps running
+    pub fn spawn(&self) -> io::Result<Child> {
+        self.command().spawn()
+    }
+
+    /// Replaces the current program with the command, which only returns if that failed
+    #[cfg(unix)]
+    pub fn exec(&self) -> io::Error {
+        use std::os::unix::process::CommandExt;
+        self.command().exec()
+    }
+}
+
+// Writes the used arguments of a parse result as a command line. Global options already
+// written for a parent (skip) aren't repeated for its subcommands
+fn canonical_args(
+    argument: &Argument,
+    parsed: &ParsedArgs,
+    skip: &[String],
+    args: &mut Vec<String>,
+) {
+    let mut positionals: Vec<String> = vec![];
+    let mut infinite: Vec<String> = vec![];
+    for (name, kind) in parsed.kinds() {
+        let (used, values) = &parsed[name];
+        if !used || skip.contains(name) {
+            continue;
+        };
+        match kind {
+            Kind::Positional => {
+                let is_infinite = argument.positionals.iter().any(|positional| {
+                    positional.placeholder == *name
+                        && positional.arity == Arity::Infinite
+                        && !positional.command
+                });
+                if is_infinite {
+                    // after "--" only a leading backslash needs escaping, except for a command
+                    // tail, which is taken as it is
+                    positionals.extend(values.iter().map(|value| match value.starts_with('\\') {
+                        true => escape(value),
+                        false => value.to_owned(),
+                    }));
+                } else {
+                    positionals.extend(values.iter().cloned());
+                };
+            }
+            // the subcommand is written below
+            Kind::Subcommand => {}
+            Kind::Flag | Kind::Option => {
+                let Some(option) = argument.options.iter().find(|option| option.key() == *name)
+                else {
+                    continue;
+                };
+                match option.arity {
+                    // an infinite option takes everything up to the next token starting with a
+                    // dash, so they're written last, with their values escaped
+                    _ if option.until.is_some() => {
+                        args.push(option.display_name());
+                        args.extend(values.iter().cloned());
+                        args.extend(option.until.clone());
+                    }
+                    Arity::Infinite => {
+                        infinite.push(option.display_name());
+                        infinite.extend(values.iter().map(|value| escape(value)));
+                    }
+                    // the value of an option requiring = has to be attached
+                    _ if option.require_equals => match values.first() {
+                        Some(value) => args.push(format!("{}={}", option.display_name(), value)),
+                        None => args.push(option.display_name()),
+                    },
+                    Arity::Exactly(_) => {
+                        args.push(option.display_name());
+                        args.extend(values.iter().cloned());
+                    }
+                };
+            }
+        };
+    }
+
+    match parsed.subcommand() {
+        // a lone "--" would turn the subcommand into a positional argument, so the positional
+        // arguments are written before the options instead. An infinite option directly before
+        // the subcommand would still take its name as a value, which can't be avoided
+        Some((name, sub_parsed)) => {
+            args.splice(..0, positionals);
+            args.extend(infinite);
+            args.push(name.to_string());
+            let Some(sub_argument) = effective(argument, name) else {
+                return;
+            };
+            let globals: Vec<String> = argument
+                .options
+                .iter()
+                .filter(|option| option.global)
+                .map(|option| option.key())
+                .chain(skip.iter().cloned())
+                .collect();
+            canonical_args(&sub_argument, sub_parsed, &globals, args);
+        }
+        None => {
+            args.extend(infinite);
+            if !positionals.is_empty() {
+                args.push("--".to_string());
+                args.extend(positionals);
+            };
+            if let Some(terminator) = &argument.terminator {
+                if !parsed.rest().is_empty() {
+                    args.push(terminator.to_owned());
+                    args.extend(parsed.rest().iter().cloned());
+                };
+            };
+        }
+    };
+}
+
+// Escapes a value of an infinite argument, which would otherwise end it (a leading dash) or
+// lose its first character (a leading backslash)
+fn escape(value: &str) -> String {
+    if value.starts_with(['-', '\\']) {
+        format!("\\{}", value)
+    } else {
+        value.to_string()
+    }
+}
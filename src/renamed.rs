@@ -0,0 +1,105 @@
+// Long options that were renamed, see Argument::add_renamed. The old names are rewritten to the
+// new ones before the command line is parsed, together with their values, so the parser only
+// ever sees the new names
+
+use crate::{callback::Callback, output, spec::Arity, Argument};
+use std::sync::Arc;
+
+// Turns a value of the old option into a value of the new one
+pub(crate) type ValueMap = Callback<dyn Fn(&str) -> String + Send + Sync>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Renamed {
+    pub(crate) old: String,
+    // the new option, by any of its names
+    pub(crate) new: String,
+    pub(crate) map: Option<ValueMap>,
+}
+
+impl Renamed {
+    pub(crate) fn new(old: &str, new: &str, map: Option<ValueMap>) -> Self {
+        Self {
+            old: old.trim_start_matches('-').to_string(),
+            new: new.to_string(),
+            map,
+        }
+    }
+}
+
+pub(crate) fn value_map(map: impl Fn(&str) -> String + Send + Sync + 'static) -> ValueMap {
+    Callback(Arc::new(map))
+}
+
+// Rewrites the old names (and their values) to the new ones, warning about every one used. Every
+// rewritten token keeps the origin (see parse::origins) of the token it was rewritten from
+pub(crate) fn rewrite(
+    argument: &Argument,
+    tokens: Vec<String>,
+    origins: Vec<Option<usize>>,
+) -> (Vec<String>, Vec<Option<usize>>) {
+    if argument.renamed.is_empty() {
+        return (tokens, origins);
+    };
+    let mut rewritten: Vec<(String, Option<usize>)> = Vec::with_capacity(tokens.len());
+    let paired: Vec<(String, Option<usize>)> = tokens.into_iter().zip(origins).collect();
+    let mut tokens = paired.into_iter();
+    while let Some((token, origin)) = tokens.next() {
+        if token == "--" || argument.terminator.as_ref() == Some(&token) {
+            rewritten.push((token, origin));
+            rewritten.extend(tokens.by_ref());
+            break;
+        };
+        let Some(long) = token.strip_prefix("--") else {
+            rewritten.push((token, origin));
+            continue;
+        };
+        let (long, attached) = match long.split_once('=') {
+            Some((long, value)) => (long, Some(value)),
+            None => (long, None),
+        };
+        let Some(renamed) = argument.renamed.iter().find(|renamed| renamed.old == long) else {
+            rewritten.push((token, origin));
+            continue;
+        };
+        let option = match argument.option_id(&renamed.new) {
+            Some(id) => &argument.options[id],
+            None => panic!(
+                "Error! --{} was renamed to \"{}\", but there's no such option",
+                renamed.old, renamed.new
+            ),
+        };
+        output::warn(format_args!(
+            "--{} is deprecated, use {} instead",
+            renamed.old,
+            option.display_name()
+        ));
+        let map = |value: &str| match &renamed.map {
+            Some(map) => (map.0)(value),
+            None => value.to_string(),
+        };
+        let name = option.display_name();
+        match attached {
+            Some(value) if option.long.is_some() => {
+                rewritten.push((format!("{}={}", name, map(value)), origin));
+            }
+            // a short option can't have its value attached with =
+            Some(value) => rewritten.extend([(name, origin), (map(value), origin)]),
+            None => rewritten.push((name, origin)),
+        };
+        // the values that follow are mapped as well
+        let following = match option.arity {
+            Arity::Exactly(amount) => amount.saturating_sub(usize::from(attached.is_some())),
+            Arity::Infinite => usize::MAX,
+        };
+        let values = tokens
+            .as_slice()
+            .iter()
+            .take(following)
+            .take_while(|(value, _)| !value.starts_with('-'))
+            .count();
+        for (value, origin) in tokens.by_ref().take(values) {
+            rewritten.push((map(&value), origin));
+        }
+    }
+    rewritten.into_iter().unzip()
+}
@@ -0,0 +1,72 @@
+// Sanitizing of user input before it gets echoed back in error messages, so a malicious or
+// binary argument can't garble the terminal
+
+// The maximum amount of characters of a value shown in an error message
+const MAX_ECHO_LENGTH: usize = 64;
+
+// Strips ANSI escape sequences, escapes control characters and truncates very long values
+pub(crate) fn sanitize(input: &str) -> String {
+    let mut sanitized = String::new();
+    let mut shown: usize = 0;
+    let mut chars = input.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character == '\u{1b}' {
+            skip_escape_sequence(&mut chars);
+            continue;
+        };
+        if shown == MAX_ECHO_LENGTH {
+            sanitized
+                .push_str(format!("... ({} characters in total)", input.chars().count()).as_str());
+            break;
+        };
+        if character.is_control() {
+            sanitized.extend(character.escape_debug());
+        } else {
+            sanitized.push(character);
+        };
+        shown += 1;
+    }
+    sanitized
+}
+
+// Strips ANSI escape sequences (e.g. colors), leaving everything else as it is
+pub(crate) fn strip_ansi(input: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character == '\u{1b}' {
+            skip_escape_sequence(&mut chars);
+        } else {
+            stripped.push(character);
+        };
+    }
+    stripped
+}
+
+// Skips the rest of an escape sequence, after the ESC character
+fn skip_escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    match chars.next() {
+        // CSI sequences, e.g. colors: ESC [ parameters final-byte
+        Some('[') => {
+            for character in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&character) {
+                    break;
+                };
+            }
+        }
+        // OSC sequences, e.g. window titles: ESC ] text (BEL | ESC \)
+        Some(']') => {
+            while let Some(character) = chars.next() {
+                if character == '\u{7}' {
+                    break;
+                };
+                if character == '\u{1b}' && chars.peek() == Some(&'\\') {
+                    chars.next();
+                    break;
+                };
+            }
+        }
+        // everything else is a two character sequence
+        _ => (),
+    }
+}
@@ -0,0 +1,267 @@
+// Helpers for producing text meant for a POSIX shell, and for splitting command lines given as a
+// single string
+
+use std::{borrow::Cow, ops::Range};
+
+/// The quoting rules a command line given as a single string is split by, see
+/// `Argument::set_quote_dialect`
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_arg("FILE", "1", Some("The file to open"));
+/// arguments.set_quote_dialect(taap::QuoteDialect::Windows);
+/// // FILE is C:\My Files\a.txt
+/// let parsed_arguments = arguments.parse_args_str(r#""C:\My Files\a.txt""#);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum QuoteDialect {
+    /// The rules of a POSIX shell like sh, without any expansions: single quotes keep everything
+    /// literal, and a backslash escapes the next character (inside double quotes only ", \\, $
+    /// and `)
+    #[default]
+    Posix,
+    /// The rules of Windows programs (cmd.exe and CommandLineToArgvW): only double quotes quote,
+    /// "" inside quotes is a literal quote, and backslashes are literal unless they come before
+    /// a quote, where every pair of them is a single backslash and an odd one escapes the quote
+    Windows,
+    /// The rules of PowerShell, without any expansions: '' inside single quotes and "" inside
+    /// double quotes are literal quotes, and a backtick escapes the next character (outside
+    /// single quotes), with `n, `t and the like being special characters. Backslashes are literal
+    PowerShell,
+}
+
+// Quotes a value so a shell reads it back as a single word, leaving it as it is when it only
+// contains characters without a special meaning
+pub(crate) fn quote(value: &str) -> Cow<'_, str> {
+    let is_safe =
+        |character: char| character.is_ascii_alphanumeric() || "_-+=.,:/@%".contains(character);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        Cow::Borrowed(value)
+    } else {
+        // inside single quotes everything is literal, except the single quote itself
+        Cow::Owned(format!("'{}'", value.replace('\'', "'\\''")))
+    }
+}
+
+// Turns a name into a valid environment variable name: uppercase, with everything but letters,
+// digits and underscores replaced by underscores
+pub(crate) fn env_name(name: &str) -> String {
+    name.chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() {
+                character.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// Splits a string into words the way a POSIX shell does, without any expansions: words are
+// separated by whitespace, single quotes keep everything literal, and a backslash escapes the
+// next character (inside double quotes only ", \, $ and `). Returns None if a quote is never
+// closed
+pub(crate) fn split(text: &str) -> Option<Vec<String>> {
+    Some(
+        split_spans(text, QuoteDialect::Posix)
+            .ok()?
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect(),
+    )
+}
+
+// Splits a string into words using the rules of the dialect, together with the bytes of the text
+// every word was made from, quotes included. Fails with the byte a quote that's never closed is at
+pub(crate) fn split_spans(
+    text: &str,
+    dialect: QuoteDialect,
+) -> Result<Vec<(String, Range<usize>)>, usize> {
+    match dialect {
+        QuoteDialect::Posix => split_posix(text),
+        QuoteDialect::Windows => split_windows(text),
+        QuoteDialect::PowerShell => split_powershell(text),
+    }
+}
+
+fn split_posix(text: &str) -> Result<Vec<(String, Range<usize>)>, usize> {
+    let mut words: Vec<(String, Range<usize>)> = vec![];
+    // the word being read, with where it started
+    let mut word: Option<(String, usize)> = None;
+    let mut characters = text.char_indices();
+    while let Some((at, character)) = characters.next() {
+        match character {
+            character if character.is_whitespace() => {
+                words.extend(word.take().map(|(word, start)| (word, start..at)));
+            }
+            '\'' => {
+                let word = &mut word.get_or_insert_with(|| (String::new(), at)).0;
+                loop {
+                    match characters.next().ok_or(at)?.1 {
+                        '\'' => break,
+                        character => word.push(character),
+                    };
+                }
+            }
+            '"' => {
+                let word = &mut word.get_or_insert_with(|| (String::new(), at)).0;
+                loop {
+                    match characters.next().ok_or(at)?.1 {
+                        '"' => break,
+                        '\\' => match characters.next().ok_or(at)?.1 {
+                            escaped @ ('"' | '\\' | '$' | '`') => word.push(escaped),
+                            other => {
+                                word.push('\\');
+                                word.push(other);
+                            }
+                        },
+                        character => word.push(character),
+                    };
+                }
+            }
+            '\\' => {
+                if let Some((_, escaped)) = characters.next() {
+                    word.get_or_insert_with(|| (String::new(), at))
+                        .0
+                        .push(escaped);
+                };
+            }
+            character => word
+                .get_or_insert_with(|| (String::new(), at))
+                .0
+                .push(character),
+        };
+    }
+    words.extend(word.map(|(word, start)| (word, start..text.len())));
+    Ok(words)
+}
+
+fn split_windows(text: &str) -> Result<Vec<(String, Range<usize>)>, usize> {
+    let mut words: Vec<(String, Range<usize>)> = vec![];
+    let mut word: Option<(String, usize)> = None;
+    // where the quote the text is inside of started
+    let mut quoted: Option<usize> = None;
+    let mut characters = text.char_indices().peekable();
+    while let Some((at, character)) = characters.next() {
+        match character {
+            character if character.is_whitespace() && quoted.is_none() => {
+                words.extend(word.take().map(|(word, start)| (word, start..at)));
+            }
+            '\\' => {
+                let mut backslashes = 1;
+                while characters
+                    .next_if(|(_, character)| *character == '\\')
+                    .is_some()
+                {
+                    backslashes += 1;
+                }
+                let word = &mut word.get_or_insert_with(|| (String::new(), at)).0;
+                if characters
+                    .peek()
+                    .is_some_and(|(_, character)| *character == '"')
+                {
+                    // an odd backslash escapes the quote, which the next turn would read
+                    word.push_str(&"\\".repeat(backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        characters.next();
+                        word.push('"');
+                    };
+                } else {
+                    word.push_str(&"\\".repeat(backslashes));
+                };
+            }
+            '"' => {
+                let word = &mut word.get_or_insert_with(|| (String::new(), at)).0;
+                match quoted {
+                    Some(_)
+                        if characters
+                            .next_if(|(_, character)| *character == '"')
+                            .is_some() =>
+                    {
+                        word.push('"');
+                    }
+                    Some(_) => quoted = None,
+                    None => quoted = Some(at),
+                };
+            }
+            character => word
+                .get_or_insert_with(|| (String::new(), at))
+                .0
+                .push(character),
+        };
+    }
+    if let Some(at) = quoted {
+        return Err(at);
+    };
+    words.extend(word.map(|(word, start)| (word, start..text.len())));
+    Ok(words)
+}
+
+// The character a backtick followed by the character stands for in PowerShell
+fn powershell_escape(character: char) -> char {
+    match character {
+        '0' => '\0',
+        'a' => '\u{7}',
+        'b' => '\u{8}',
+        'e' => '\u{1b}',
+        'f' => '\u{c}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\u{b}',
+        character => character,
+    }
+}
+
+fn split_powershell(text: &str) -> Result<Vec<(String, Range<usize>)>, usize> {
+    let mut words: Vec<(String, Range<usize>)> = vec![];
+    let mut word: Option<(String, usize)> = None;
+    let mut characters = text.char_indices().peekable();
+    while let Some((at, character)) = characters.next() {
+        match character {
+            character if character.is_whitespace() => {
+                words.extend(word.take().map(|(word, start)| (word, start..at)));
+            }
+            '\'' => {
+                let word = &mut word.get_or_insert_with(|| (String::new(), at)).0;
+                loop {
+                    match characters.next().ok_or(at)?.1 {
+                        '\'' if characters.next_if(|(_, next)| *next == '\'').is_some() => {
+                            word.push('\'');
+                        }
+                        '\'' => break,
+                        character => word.push(character),
+                    };
+                }
+            }
+            '"' => {
+                let word = &mut word.get_or_insert_with(|| (String::new(), at)).0;
+                loop {
+                    match characters.next().ok_or(at)?.1 {
+                        '"' if characters.next_if(|(_, next)| *next == '"').is_some() => {
+                            word.push('"');
+                        }
+                        '"' => break,
+                        '`' => word.push(powershell_escape(characters.next().ok_or(at)?.1)),
+                        character => word.push(character),
+                    };
+                }
+            }
+            '`' => {
+                // a backtick at the end of the line continues it, which adds nothing
+                if let Some((_, escaped)) = characters.next() {
+                    word.get_or_insert_with(|| (String::new(), at))
+                        .0
+                        .push(powershell_escape(escaped));
+                };
+            }
+            character => word
+                .get_or_insert_with(|| (String::new(), at))
+                .0
+                .push(character),
+        };
+    }
+    words.extend(word.map(|(word, start)| (word, start..text.len())));
+    Ok(words)
+}
@@ -0,0 +1,105 @@
+// The --show-effective-config option, printing the final value of every argument together with
+// where it came from
+
+use crate::{
+    output::{self, json_string, OutputFormat},
+    parsed::{Kind, Source},
+    ParsedArgs,
+};
+
+// The long name of the option
+pub(crate) const SHOW_CONFIG_OPTION: &str = "show-effective-config";
+
+struct Entry {
+    name: String,
+    values: Vec<String>,
+    // None if the argument wasn't used
+    source: Option<String>,
+}
+
+fn describe(source: &Source) -> String {
+    match source {
+        Source::CommandLine => "command line".to_string(),
+        Source::Profile(name) => format!("profile {}", name),
+        Source::Default => "default".to_string(),
+        Source::Environment(variable) => format!("environment variable {}", variable),
+        Source::External(name) => name.to_owned(),
+    }
+}
+
+// Every argument, with the ones of a subcommand prefixed with its name
+fn entries(parsed: &ParsedArgs, prefix: &str, collected: &mut Vec<Entry>) {
+    for (name, kind, used, values) in parsed.entries() {
+        match kind {
+            Kind::Subcommand => {}
+            _ if name == "h" || name == SHOW_CONFIG_OPTION => {}
+            _ => collected.push(Entry {
+                name: format!("{}{}", prefix, name),
+                values: values.to_vec(),
+                source: parsed.source(name).filter(|_| used).map(describe),
+            }),
+        };
+    }
+    if let Some((name, subcommand)) = parsed.subcommand() {
+        entries(subcommand, &format!("{}{} ", prefix, name), collected);
+    };
+}
+
+// Renders the configuration in the given format
+pub(crate) fn render(parsed: &ParsedArgs, format: OutputFormat) -> String {
+    let mut all: Vec<Entry> = vec![];
+    entries(parsed, "", &mut all);
+    match format {
+        OutputFormat::Plain => std::iter::once("Argument\tValue\tSource".to_string())
+            .chain(all.iter().map(|entry| {
+                format!(
+                    "{}\t{}\t{}",
+                    entry.name,
+                    entry.values.join(" "),
+                    entry.source.as_deref().unwrap_or("unset")
+                )
+            }))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        OutputFormat::Markdown => {
+            std::iter::once("| Argument | Value | Source |\n|---|---|---|".to_string())
+                .chain(all.iter().map(|entry| {
+                    format!(
+                        "| `{}` | {} | {} |",
+                        entry.name,
+                        entry.values.join(" ").replace('|', "\\|"),
+                        entry.source.as_deref().unwrap_or("unset")
+                    )
+                }))
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+        OutputFormat::Json => format!(
+            "[{}]",
+            all.iter()
+                .map(|entry| {
+                    format!(
+                        "{{\"name\": {}, \"values\": [{}], \"source\": {}}}",
+                        json_string(&entry.name),
+                        entry
+                            .values
+                            .iter()
+                            .map(|value| json_string(value))
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                        entry
+                            .source
+                            .as_deref()
+                            .map_or("null".to_string(), json_string)
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
+}
+
+// Prints the configuration in the current format
+pub(crate) fn print(parsed: &ParsedArgs) {
+    output::print(&render(parsed, output::format()));
+}
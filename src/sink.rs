@@ -0,0 +1,96 @@
+// Custom storage for the values of an argument, instead of the Vec<String> in the parse result
+
+use crate::callback::Callback;
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::{Arc, Mutex},
+};
+
+/// A custom storage for the values of an argument
+///
+/// When an argument has a sink (see `OptionSpec::sink` and `PositionalSpec::sink`), its values
+/// are handed to it once parsing succeeded, and the values in the parse result stay empty,
+/// while the argument is still marked as used. This lets tools fed huge amounts of values
+/// intern, hash or write them to disk, instead of keeping them in the parse result.
+///
+/// The values aren't streamed while parsing: the parser still collects them, since an option
+/// used again replaces its earlier values, positional values are only handed out once the whole
+/// command line was read, and file lists are expanded and normalizers applied afterwards. The
+/// sink gets every value once it's final, so a sink keeps the values from staying in memory
+/// after parsing, not while parsing.
+///
+/// To get the values back after parsing, pass the sink wrapped in an `Arc<Mutex<_>>` and keep a
+/// clone of it.
+///
+/// Code Example:
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use taap::{Argument, Pos, ValueSink};
+///
+/// // counts the values instead of storing them
+/// #[derive(Default)]
+/// struct Counter(usize);
+///
+/// impl ValueSink for Counter {
+///     fn push(&mut self, _value: String) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// fn main() {
+/// let counter = Arc::new(Mutex::new(Counter::default()));
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(Pos::new("FILES").infinite().sink(Arc::clone(&counter)));
+/// arguments.parse_args(None);
+///
+/// println!("Got {} files", counter.lock().unwrap().0);
+/// }
+/// ```
+pub trait ValueSink: Send {
+    /// Stores a value of the argument
+    fn push(&mut self, value: String);
+}
+
+impl ValueSink for Vec<String> {
+    fn push(&mut self, value: String) {
+        Vec::push(self, value);
+    }
+}
+
+impl ValueSink for HashSet<String> {
+    fn push(&mut self, value: String) {
+        self.insert(value);
+    }
+}
+
+impl ValueSink for BTreeSet<String> {
+    fn push(&mut self, value: String) {
+        self.insert(value);
+    }
+}
+
+impl<T: ValueSink> ValueSink for Arc<Mutex<T>> {
+    fn push(&mut self, value: String) {
+        self.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(value);
+    }
+}
+
+// A sink as stored in the spec, shared by the clones of the spec (e.g. in subcommands)
+pub(crate) type SharedSink = Callback<Mutex<dyn ValueSink>>;
+
+pub(crate) fn shared(sink: impl ValueSink + 'static) -> SharedSink {
+    Callback(Arc::new(Mutex::new(sink)))
+}
+
+// Moves the values of an argument into its sink
+pub(crate) fn drain(sink: &SharedSink, values: &mut Vec<String>) {
+    let mut sink = sink
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for value in values.drain(..) {
+        sink.push(value);
+    }
+}
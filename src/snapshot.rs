@@ -0,0 +1,85 @@
+// Golden-file tests of the help page, for programs using taap
+//
+// The plain help page doesn't depend on the terminal (its columns are separated by tabs), so the
+// only things that have to be fixed for a stable snapshot are the decorations, which are
+// stripped, and the language, which is never taken from the system.
+
+use crate::{sanitize::strip_ansi, Argument, OutputFormat};
+use std::{env, fs, path::Path};
+
+// The environment variable which, when set, updates the snapshots instead of comparing them
+const UPDATE_VARIABLE: &str = "TAAP_UPDATE_SNAPSHOTS";
+
+/// Asserts that the help page of an Argument matches the one stored in a file
+///
+/// Takes an Argument and the path of the file, relative to the crate being tested. The plain
+/// help page is rendered without any decorations (like colors) and compared to the contents of
+/// the file, failing the test if they differ or if the file doesn't exist. Run the tests with
+/// the environment variable `TAAP_UPDATE_SNAPSHOTS` set to create or update the files instead.
+///
+/// The language of the system (LC_ALL, LC_MESSAGES or LANG) is ignored, so the snapshot is the
+/// same on every machine: the help page is in the language set using `Argument::set_language`,
+/// or untranslated if there's none.
+///
+/// Code Example:
+/// ```no_run
+/// fn arguments() -> taap::Argument {
+///     let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+///     // ...
+///     arguments
+/// }
+///
+/// #[test]
+/// fn help() {
+///     taap::assert_help_snapshot!(arguments(), "tests/snapshots/help.txt");
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_help_snapshot {
+    ($argument:expr, $path:expr) => {
+        $crate::check_help_snapshot(
+            &$argument,
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+// Used by assert_help_snapshot!, which has to be able to call it from other crates
+#[doc(hidden)]
+pub fn check_help_snapshot(argument: &Argument, path: &Path) {
+    let mut argument = argument.clone();
+    if argument.language.is_none() {
+        // "C" has no translations, as in LANG=C
+        argument.set_language("C");
+    };
+    let help = format!(
+        "{}\n",
+        strip_ansi(&argument.render_help(OutputFormat::Plain))
+    );
+    if env::var_os(UPDATE_VARIABLE).is_some() {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).unwrap_or_else(|err| {
+                panic!("Error! Couldn't create {}: {}", directory.display(), err)
+            });
+        };
+        fs::write(path, &help)
+            .unwrap_or_else(|err| panic!("Error! Couldn't write {}: {}", path.display(), err));
+        return;
+    };
+    let Ok(snapshot) = fs::read_to_string(path) else {
+        panic!(
+            "Error! The help snapshot {} doesn't exist, run the tests with {} set to create it",
+            path.display(),
+            UPDATE_VARIABLE
+        );
+    };
+    assert!(
+        snapshot == help,
+        "The help page doesn't match the snapshot {} (run the tests with {} set to update it)\n\
+         --- snapshot\n{}\n--- help page\n{}",
+        path.display(),
+        UPDATE_VARIABLE,
+        snapshot,
+        help
+    );
+}
@@ -0,0 +1,1000 @@
+// The specifications of the arguments an Argument accepts, which also act as builders
+
+#[cfg(feature = "async")]
+use crate::{
+    asynchronous::{self, AsyncChoices, SharedAsyncSource},
+    AsyncValueSource, BoxFuture,
+};
+use crate::{
+    choices::Choices,
+    normalize::{normalizer, Normalizer},
+    sink::{shared, SharedSink},
+    text::Text,
+    value_source::{self, SharedSource},
+    Argument, ValueSink, ValueSource,
+};
+use std::borrow::Cow;
+
+/// The amount of values an argument takes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Arity {
+    /// A fixed amount of values, 0 for flags
+    Exactly(usize),
+    /// An unspecified amount of values, written as "+"
+    Infinite,
+}
+
+impl Arity {
+    // Parses the amount of arguments as passed to add_arg and add_option: a positive integer,
+    // 0 or "+"
+    pub(crate) fn parse(amount: &str) -> Option<Self> {
+        if amount == "+" {
+            Some(Arity::Infinite)
+        } else {
+            amount.parse::<usize>().ok().map(Arity::Exactly)
+        }
+    }
+}
+
+/// How the values of an option are combined when they come from several places
+///
+/// The values of the environment variable set with `Argument::set_options_env` come first, then
+/// the ones of profiles, and then the ones of the command line. Using the option twice in the
+/// same place always replaces the earlier values.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MergeStrategy {
+    /// The later values replace the earlier ones
+    #[default]
+    Replace,
+    /// The later values are added after the earlier ones, e.g. for search paths
+    Append,
+    /// The later values are added before the earlier ones
+    Prepend,
+}
+
+/// What happens when an option is used more than once in the same place
+///
+/// It can be set for all options using `Argument::set_duplicates`, and for a single option using
+/// `OptionSpec::duplicates`. Values from different places (the environment, profiles and the
+/// command line) aren't duplicates, they're combined using the `MergeStrategy` of the option.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Duplicates, Opt};
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.set_duplicates(Duplicates::Error);
+/// // "--output a --output b" is an error, but "-v -v" isn't
+/// arguments.add(Opt::new('o', "output").takes(1));
+/// arguments.add(Opt::new('v', "verbose").duplicates(Duplicates::TakeLast));
+/// // ...
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Duplicates {
+    /// The last use replaces the earlier ones
+    #[default]
+    TakeLast,
+    /// The last use replaces the earlier ones, and a warning is printed
+    Warn,
+    /// The first use is kept, the later ones are skipped (including their values)
+    TakeFirst,
+    /// Using the option more than once is an error
+    Error,
+}
+
+/// An optional argument, built using chained function calls
+///
+/// Add it to an Argument using `Argument::add`. `Opt` is a shorter name for the same type.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Opt};
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(Opt::new('f', "foo").takes(2).help("I take two values").required());
+/// arguments.add(Opt::new('-', "bar").infinite().help("I only have a long name"));
+/// arguments.add(Opt::new('v', "").help("I'm a flag with only a short name"));
+/// arguments.add(Opt::new('p', "port").takes(1).default("8080").help("The port to listen on"));
+/// // ...
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OptionSpec {
+    pub(crate) short: Option<char>,
+    pub(crate) long: Option<Cow<'static, str>>,
+    pub(crate) arity: Arity,
+    pub(crate) help: Text,
+    pub(crate) required: bool,
+    pub(crate) global: bool,
+    pub(crate) default: Option<Vec<String>>,
+    // the default refers to other arguments, like "{INPUT}.out"
+    pub(crate) default_template: bool,
+    pub(crate) choices: Option<Choices>,
+    pub(crate) normalizers: Vec<Normalizer>,
+    pub(crate) sink: Option<SharedSink>,
+    pub(crate) value_source: Option<SharedSource>,
+    // the environment variable the values are read from when the option isn't used
+    pub(crate) env: Option<String>,
+    #[cfg(feature = "async")]
+    pub(crate) async_value_source: Option<SharedAsyncSource>,
+    #[cfg(feature = "async")]
+    pub(crate) async_choices: Option<AsyncChoices>,
+    pub(crate) require_equals: bool,
+    pub(crate) until: Option<String>,
+    // the option is required if the argument (by name) has the value
+    pub(crate) required_if: Vec<(String, String)>,
+    pub(crate) short_circuit: bool,
+    pub(crate) merge_strategy: MergeStrategy,
+    // None uses the policy of the Argument
+    pub(crate) duplicates: Option<Duplicates>,
+    pub(crate) value_files: bool,
+    pub(crate) secret: bool,
+    // None allows empty values unless the values are checked or converted
+    pub(crate) allow_empty: Option<bool>,
+    // a negatable flag can be turned off again with --no-NAME
+    pub(crate) negatable: bool,
+    // the flags (by name) turned on along with this one
+    pub(crate) enables: Vec<String>,
+    // the versions of the program the option was added and removed in
+    pub(crate) since: Option<String>,
+    pub(crate) removed: Option<String>,
+}
+
+/// A shorter name for OptionSpec
+pub type Opt = OptionSpec;
+
+// A short name of ' ' or '-' means there's none
+const fn short_name(short: char) -> Option<char> {
+    match short {
+        ' ' | '-' => None,
+        short => Some(short),
+    }
+}
+
+// A long name of "", " ", "-" or "--" means there's none
+const fn long_name(long: &str) -> Option<&str> {
+    match long.as_bytes() {
+        b"" | b" " | b"-" | b"--" => None,
+        _ => Some(long),
+    }
+}
+
+impl OptionSpec {
+    /// Returns a new optional argument, which takes no values and has no help text
+    ///
+    /// Just like with `Argument::add_option`, the short name can be a space (' ') or a dash
+    /// ('-') if you only want a long name, and the long name can be an empty str (""), a space
+    /// (" ") or a single/double dash ("-"/"--") if you only want a short name
+    ///
+    /// The long name can be a &'static str, which is kept as it is, or an owned String made at
+    /// runtime
+    ///
+    /// | Parameter | Type                             | Description                             |
+    /// |-----------|----------------------------------|-----------------------------------------|
+    /// | short     | char                             | The short name of the optional argument |
+    /// | long      | impl Into\<Cow\<'static, str\>\> | The long name of the optional argument  |
+    ///
+    pub fn new(short: char, long: impl Into<Cow<'static, str>>) -> Self {
+        let long = long.into();
+        let long = long_name(&long).is_some().then_some(long);
+        Self::blank(short_name(short), long, Text::default())
+    }
+
+    /// Returns a new optional argument with only a long name, which takes no values and has no
+    /// help text
+    ///
+    /// Starts the definition of an option from the name it's known by, adding the short name
+    /// using `short` if it has one, so every setting is named instead of passed by position
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::Opt;
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// arguments.add(Opt::long("foo").short('f').takes(2).help("I take two values").required());
+    /// arguments.add(Opt::long("verbose").help("Print more details"));
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type                             | Description                            |
+    /// |-----------|----------------------------------|----------------------------------------|
+    /// | long      | impl Into\<Cow\<'static, str\>\> | The long name of the optional argument |
+    ///
+    pub fn long(long: impl Into<Cow<'static, str>>) -> Self {
+        Self::new('-', long)
+    }
+
+    /// Returns a new optional argument with a help text, which takes no values and can be
+    /// created at compile time
+    ///
+    /// The names and the help text are kept as they are instead of being copied, so a table of
+    /// options can be a `const` or a `static` without allocating anything until it's added to
+    /// an Argument. The builder functions only changing settings (like `takes`, `required` or
+    /// `global`) can be used at compile time as well
+    ///
+    /// Code Example:
+    /// ```no_run
+    /// use taap::Opt;
+    ///
+    /// static OPTIONS: [Opt; 2] = [
+    ///     Opt::new_static('v', "verbose", "Print more details"),
+    ///     Opt::new_static('o', "output", "Where to write the result").takes(1).required(),
+    /// ];
+    ///
+    /// fn main() {
+    /// // first initialize a new Argument instance using the "new" function
+    /// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+    /// for option in OPTIONS.iter() {
+    ///     arguments.add(option.clone());
+    /// }
+    /// let parsed_arguments = arguments.parse_args(None);
+    /// // ...
+    /// }
+    /// ```
+    ///
+    /// | Parameter | Type         | Description                             |
+    /// |-----------|--------------|-----------------------------------------|
+    /// | short     | char         | The short name of the optional argument |
+    /// | long      | &'static str | The long name of the optional argument  |
+    /// | help      | &'static str | The help text of the optional argument  |
+    ///
+    pub const fn new_static(short: char, long: &'static str, help: &'static str) -> Self {
+        let long = match long_name(long) {
+            Some(long) => Some(Cow::Borrowed(long)),
+            None => None,
+        };
+        Self::blank(short_name(short), long, Text::Static(Cow::Borrowed(help)))
+    }
+
+    const fn blank(short: Option<char>, long: Option<Cow<'static, str>>, help: Text) -> Self {
+        Self {
+            short,
+            long,
+            arity: Arity::Exactly(0),
+            help,
+            required: false,
+            global: false,
+            default: None,
+            default_template: false,
+            choices: None,
+            normalizers: Vec::new(),
+            sink: None,
+            value_source: None,
+            env: None,
+            #[cfg(feature = "async")]
+            async_value_source: None,
+            #[cfg(feature = "async")]
+            async_choices: None,
+            require_equals: false,
+            until: None,
+            required_if: Vec::new(),
+            short_circuit: false,
+            merge_strategy: MergeStrategy::Replace,
+            duplicates: None,
+            value_files: false,
+            secret: false,
+            allow_empty: None,
+            negatable: false,
+            enables: Vec::new(),
+            since: None,
+            removed: None,
+        }
+    }
+
+    /// Sets the short name of the option, replacing the one it had. A space (' ') or a dash ('-')
+    /// removes it
+    pub const fn short(mut self, short: char) -> Self {
+        self.short = short_name(short);
+        self
+    }
+
+    /// Sets the amount of values the option takes
+    pub const fn takes(self, amount: usize) -> Self {
+        self.arity(Arity::Exactly(amount))
+    }
+
+    /// Makes the option take an unspecified amount of values (the same as "+")
+    pub const fn infinite(self) -> Self {
+        self.arity(Arity::Infinite)
+    }
+
+    /// Sets the amount of values the option takes
+    pub const fn arity(mut self, arity: Arity) -> Self {
+        self.arity = arity;
+        self
+    }
+
+    /// Sets the help text of the option
+    ///
+    /// An empty line starts a new paragraph, which is kept on the help page. The other line
+    /// breaks are joined into one line. A &'static str is kept as it is instead of being copied
+    pub fn help(mut self, help: impl Into<Cow<'static, str>>) -> Self {
+        self.help = Text::Static(help.into());
+        self
+    }
+
+    /// Sets a closure producing the help text of the option, which is only called when the
+    /// help is rendered
+    pub fn help_with(mut self, help: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.help = Text::lazy(help);
+        self
+    }
+
+    /// Makes the option required, parsing fails if it isn't used
+    ///
+    /// When several required options are missing, the error lists all of them, like
+    /// "--host, --port and -u are required"
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Makes the option required if another argument has a specific value
+    ///
+    /// The other argument is an optional argument (by its short or long name) or a positional
+    /// argument (by its placeholder), e.g. `.required_if("MODE", "remote")` requires the option
+    /// if the positional argument MODE is "remote". Can be used several times, the option is then
+    /// required if any of the conditions is met
+    pub fn required_if(mut self, name: &str, value: &str) -> Self {
+        self.required_if.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Makes the option skip all requirements when it's used, like --help does
+    ///
+    /// Meant for options like --version or --list-formats, which do something else than the
+    /// program usually does. When one of them is used, missing required options and positional
+    /// arguments are no longer errors, so the program can check for the option right after
+    /// parsing
+    pub const fn short_circuit(mut self) -> Self {
+        self.short_circuit = true;
+        self
+    }
+
+    /// Sets how values from the environment, profiles and the command line are combined
+    ///
+    /// By default the command line replaces the values of the environment variable and the
+    /// profiles (see `MergeStrategy`)
+    pub const fn merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_strategy = strategy;
+        self
+    }
+
+    /// Sets what happens when the option is used more than once on the command line
+    ///
+    /// Overrides the policy set for all options using `Argument::set_duplicates` (see
+    /// `Duplicates`)
+    pub const fn duplicates(mut self, duplicates: Duplicates) -> Self {
+        self.duplicates = Some(duplicates);
+        self
+    }
+
+    /// Lets the values of the option be read from files, using @PATH or file:PATH
+    ///
+    /// E.g. `--token @token.txt` or `--token file:token.txt` uses the contents of token.txt as
+    /// the value, without the whitespace around it, so secrets and long values don't end up in
+    /// the shell history or the process list. The size of the file counts against
+    /// `Limits::max_value_length`, and `ParsedArgs::value_files` returns the files that were
+    /// read
+    pub const fn value_files(mut self) -> Self {
+        self.value_files = true;
+        self
+    }
+
+    /// Sets if the option accepts empty values, as in `--name ""`
+    ///
+    /// By default empty values are accepted, unless the option has choices or a sink, which
+    /// expect a real value. A rejected empty value is reported as an error, instead of flowing
+    /// into the program as an empty string
+    pub const fn allow_empty(mut self, allow: bool) -> Self {
+        self.allow_empty = Some(allow);
+        self
+    }
+
+    /// Marks the values of the option as secret, e.g. for passwords and tokens
+    ///
+    /// `ParsedArgs::secret_args` returns the positions of the arguments holding them, so the
+    /// program can hide them from its process title or its logs
+    pub const fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    /// Lets the flag be turned off again with --no-NAME, e.g. `--no-color` for `--color`
+    ///
+    /// The last of the two counts, so a flag switched on by the environment variable holding
+    /// default arguments can still be switched off on the command line. `ParsedArgs::presence`
+    /// tells a flag that was turned off apart from one that wasn't used at all. Only options
+    /// taking no values and having a long name can be negatable
+    pub const fn negatable(mut self) -> Self {
+        self.negatable = true;
+        self
+    }
+
+    /// Makes the flag turn on other flags as well, e.g. `--all` for `--foo --bar --baz`
+    ///
+    /// The flags are given by any of their names, and may enable others in turn. A flag that's
+    /// used or turned off with --no-NAME on its own keeps that setting, no matter where it is on
+    /// the command line, and the rest get the source of this flag. The help of the flag lists
+    /// the flags it enables. Only options taking no values can enable others
+    pub fn enables<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.enables
+            .extend(names.into_iter().map(|name| name.as_ref().to_string()));
+        self
+    }
+
+    /// Sets the version of the program the option was added in
+    ///
+    /// Using the option warns if it's newer than the compatibility level set with
+    /// `Argument::set_compatibility`, and `Argument::whats_new` lists it
+    pub fn since(mut self, version: &str) -> Self {
+        self.since = Some(version.to_string());
+        self
+    }
+
+    /// Sets the version of the program the option was removed in
+    ///
+    /// The option keeps working, for scripts written for older versions, but using it warns if
+    /// the compatibility level set with `Argument::set_compatibility` is this version or newer
+    pub fn removed(mut self, version: &str) -> Self {
+        self.removed = Some(version.to_string());
+        self
+    }
+
+    /// Makes the option global, so it's inherited by all subcommands (and their subcommands)
+    ///
+    /// A global option can be used both before and after the subcommand, and is stored in the
+    /// result of the Argument it was added to as well as in the result of the subcommand
+    pub const fn global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    /// Makes the value of the option optional, and only accepted when it's attached using =
+    ///
+    /// `--color=auto` is then used with the value "auto", and `--color` without any, while
+    /// `--color auto` leaves "auto" as a positional argument. The option has to take one value
+    /// and have a long name
+    pub const fn require_equals(mut self) -> Self {
+        self.require_equals = true;
+        self
+    }
+
+    /// Makes the values of the option end at a keyword, e.g. `--set key value end`
+    ///
+    /// Everything up to the keyword is taken as a value, even values starting with a dash, and
+    /// the keyword itself is dropped. An infinite option has to be ended with the keyword, while
+    /// an option taking a fixed amount of values takes up to that many, or less if the keyword
+    /// comes first. The keyword is mentioned in the help of the option
+    pub fn until(mut self, keyword: &str) -> Self {
+        self.until = Some(keyword.to_string());
+        self
+    }
+
+    /// Sets the value the option gets when it isn't used
+    ///
+    /// The option is then stored as used, with the default as its value, and
+    /// `ParsedArgs::source` returns `Source::Default` for it. Options taking more than one
+    /// value need `default_values` instead
+    pub fn default(self, value: &str) -> Self {
+        self.default_values([value])
+    }
+
+    /// Sets the values the option gets when it isn't used, one for every value it takes
+    pub fn default_values<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.default = Some(
+            values
+                .into_iter()
+                .map(|value| value.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Sets a default that refers to the values of other arguments, like "{INPUT}.out"
+    ///
+    /// Every name between braces is replaced by the value of that option or positional argument
+    /// once the rest of the command line is parsed, with several values joined by spaces. "{{"
+    /// and "}}" stand for literal braces. If an argument it refers to has no value, the option
+    /// stays unused. The template may refer to options with a template of their own, but not in
+    /// a circle
+    pub fn default_template(mut self, template: &str) -> Self {
+        self.default_template = true;
+        self.default(template)
+    }
+
+    /// Sets the values the option accepts, parsing fails if it's given anything else
+    pub fn choices<I, S>(mut self, choices: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.choices = Some(Choices::Static(
+            choices
+                .into_iter()
+                .map(|choice| choice.as_ref().to_string())
+                .collect(),
+        ));
+        self
+    }
+
+    /// Sets a closure producing the values the option accepts, which is called every time
+    /// they're needed, so they always reflect the current state of the system (e.g. the
+    /// available devices)
+    pub fn choices_with(
+        mut self,
+        choices: impl Fn() -> Vec<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.choices = Some(Choices::dynamic(choices));
+        self
+    }
+
+    /// Adds a function normalizing the values of the option (e.g. `taap::normalize::lowercase`)
+    ///
+    /// It's applied to every value after it has been checked against the choices and before
+    /// it's stored, including the default. Several functions are applied in the order they were
+    /// added
+    pub fn normalize(
+        mut self,
+        normalize: impl Fn(String) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.normalizers.push(normalizer(normalize));
+        self
+    }
+
+    /// Hands the values of the option to a custom storage, instead of storing them in the parse
+    /// result (see `ValueSink`)
+    pub fn sink(mut self, sink: impl ValueSink + 'static) -> Self {
+        self.sink = Some(shared(sink));
+        self
+    }
+
+    /// Fetches the values of the option from an external store, under the key, when it isn't
+    /// used (see `ValueSource`)
+    pub fn value_source(mut self, source: impl ValueSource + 'static, key: &str) -> Self {
+        self.value_source = Some(value_source::shared(source, key));
+        self
+    }
+
+    /// Reads the values of the option from an environment variable when it isn't used
+    ///
+    /// The variable wins over a value source and the default, and the command line wins over
+    /// the variable. An option taking one value gets the whole variable, and one taking more
+    /// gets it split like a shell would. A flag is used if the variable is 1, true, yes or on,
+    /// and not if it's 0, false, no or off. An empty variable counts as unset. The variable is
+    /// mentioned in the help of the option, as "\[env: NAME\]"
+    pub fn env(mut self, variable: &str) -> Self {
+        self.env = Some(variable.to_string());
+        self
+    }
+
+    /// Fetches the values of the option from an external store asynchronously, under the key,
+    /// when it isn't used (see `AsyncValueSource`)
+    ///
+    /// The store is asked once the command line is matched, only if the option didn't get a
+    /// value otherwise (and not for the help), and the result takes the place of a store set
+    /// with `value_source`. The command line has to
+    /// be parsed using `Argument::parse_args_async` (requires the "async" feature)
+    #[cfg(feature = "async")]
+    pub fn async_value_source(
+        mut self,
+        source: impl AsyncValueSource + 'static,
+        key: &str,
+    ) -> Self {
+        self.async_value_source = Some(asynchronous::shared(source, key));
+        self
+    }
+
+    /// Sets the values the option accepts, produced by a future every time the command line is
+    /// parsed, e.g. from a network service
+    ///
+    /// The future is awaited when the command the option is in gets parsed (so not for the
+    /// options of a subcommand that isn't used), and replaces the choices set with `choices` or
+    /// `choices_fn` while parsing. The command line has to be parsed using
+    /// `Argument::parse_args_async` (requires the "async" feature)
+    #[cfg(feature = "async")]
+    pub fn async_choices(
+        mut self,
+        choices: impl Fn() -> BoxFuture<'static, Vec<String>> + Send + Sync + 'static,
+    ) -> Self {
+        self.async_choices = Some(asynchronous::async_choices(choices));
+        self
+    }
+
+    // The name the option is stored under in the parse result: the short name, or the long name
+    // if there is no short name
+    pub(crate) fn key(&self) -> String {
+        match (self.short, &self.long) {
+            (Some(short), _) => short.to_string(),
+            (None, Some(long)) => long.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+
+    // The option moved into a namespace: the long name (or the short name, if there is no long
+    // name) gets the prefix, and the short name is dropped, so the result key is prefixed as well
+    pub(crate) fn prefixed(&self, prefix: &str) -> OptionSpec {
+        let name = match (self.short, &self.long) {
+            (_, Some(long)) => long.to_string(),
+            (Some(short), None) => short.to_string(),
+            (None, None) => String::new(),
+        };
+        OptionSpec {
+            short: None,
+            long: Some(Cow::Owned(format!("{}-{}", prefix, name))),
+            ..self.clone()
+        }
+    }
+
+    // Returns true if the option can be referred to by this name (its key or its long name)
+    pub(crate) fn is_named(&self, name: &str) -> bool {
+        self.key() == name || self.long.as_deref() == Some(name)
+    }
+
+    // The name of the option as it's written on the command line, preferring the long name
+    pub(crate) fn display_name(&self) -> String {
+        match (self.short, &self.long) {
+            (_, Some(long)) => format!("--{}", long),
+            (Some(short), None) => format!("-{}", short),
+            (None, None) => String::new(),
+        }
+    }
+
+    pub(crate) fn allows_empty(&self) -> bool {
+        self.allow_empty
+            .unwrap_or(self.choices.is_none() && self.sink.is_none())
+    }
+}
+
+/// An optional argument being defined in place, returned by `Argument::option`
+///
+/// Its functions set the same things as the ones of `OptionSpec` with the same names, and `with`
+/// applies any other setting of `OptionSpec`. The option is added to the Argument once the
+/// handle is dropped, which usually is at the end of the statement, so the settings can be
+/// chained in any order.
+///
+/// Code Example:
+/// ```no_run
+/// let mut arguments = taap::Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.option("foo").short('f').takes(2).help("I take two values").required();
+/// arguments.option("token").takes(1).with(|option| option.secret().allow_empty(false));
+/// // ...
+/// ```
+pub struct OptionHandle<'a> {
+    argument: &'a mut Argument,
+    // None once the option was added
+    option: Option<OptionSpec>,
+}
+
+impl<'a> OptionHandle<'a> {
+    pub(crate) fn new(argument: &'a mut Argument, long: &str) -> Self {
+        Self {
+            argument,
+            option: Some(OptionSpec::long(long.to_string())),
+        }
+    }
+
+    /// Applies any setting of `OptionSpec` to the option
+    pub fn with(mut self, change: impl FnOnce(OptionSpec) -> OptionSpec) -> Self {
+        self.option = self.option.take().map(change);
+        self
+    }
+
+    /// Sets the short name of the option, see `OptionSpec::short`
+    pub fn short(self, short: char) -> Self {
+        self.with(|option| option.short(short))
+    }
+
+    /// Sets the amount of values the option takes, see `OptionSpec::takes`
+    pub fn takes(self, amount: usize) -> Self {
+        self.with(|option| option.takes(amount))
+    }
+
+    /// Makes the option take an unspecified amount of values, see `OptionSpec::infinite`
+    pub fn infinite(self) -> Self {
+        self.with(OptionSpec::infinite)
+    }
+
+    /// Sets the help text of the option, see `OptionSpec::help`
+    pub fn help(self, help: impl Into<Cow<'static, str>>) -> Self {
+        self.with(|option| option.help(help))
+    }
+
+    /// Makes the option required, see `OptionSpec::required`
+    pub fn required(self) -> Self {
+        self.with(OptionSpec::required)
+    }
+
+    /// Sets the value the option gets when it isn't used, see `OptionSpec::default`
+    pub fn default(self, value: &str) -> Self {
+        self.with(|option| option.default(value))
+    }
+
+    /// Makes the option usable after a subcommand as well, see `OptionSpec::global`
+    pub fn global(self) -> Self {
+        self.with(OptionSpec::global)
+    }
+
+    /// Reads the option from an environment variable, see `OptionSpec::env`
+    pub fn env(self, variable: &str) -> Self {
+        self.with(|option| option.env(variable))
+    }
+}
+
+impl Drop for OptionHandle<'_> {
+    fn drop(&mut self) {
+        // the option isn't checked while a panic is unwinding, which would abort the program
+        if let Some(option) = self.option.take().filter(|_| !std::thread::panicking()) {
+            self.argument.add(option);
+        };
+    }
+}
+
+/// A positional argument, built using chained function calls
+///
+/// Add it to an Argument using `Argument::add`. `Pos` is a shorter name for the same type.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Pos};
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(Pos::new("SOURCE").help("The file to copy"));
+/// arguments.add(Pos::new("DESTINATIONS").infinite().help("Where to copy it"));
+/// // ...
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PositionalSpec {
+    pub(crate) placeholder: Cow<'static, str>,
+    pub(crate) arity: Arity,
+    pub(crate) help: Text,
+    pub(crate) file_expansion: Option<FileExpansion>,
+    pub(crate) normalizers: Vec<Normalizer>,
+    pub(crate) sink: Option<SharedSink>,
+    pub(crate) command: bool,
+    // None allows empty values unless the values are converted
+    pub(crate) allow_empty: Option<bool>,
+    // the version of the program the positional argument was added in
+    pub(crate) since: Option<String>,
+}
+
+/// A shorter name for PositionalSpec
+pub type Pos = PositionalSpec;
+
+impl PositionalSpec {
+    /// Returns a new positional argument, which takes one value and has no help text
+    ///
+    /// The placeholder can be a &'static str, which is kept as it is, or an owned String made at
+    /// runtime
+    ///
+    /// | Parameter   | Type                             | Description                                                         |
+    /// |-------------|----------------------------------|---------------------------------------------------------------------|
+    /// | placeholder | impl Into\<Cow\<'static, str\>\> | The placeholder of the positional argument, meant for the help page |
+    ///
+    pub fn new(placeholder: impl Into<Cow<'static, str>>) -> Self {
+        Self::blank(placeholder.into(), Text::default())
+    }
+
+    /// Returns a new positional argument with a help text, which takes one value and can be
+    /// created at compile time
+    ///
+    /// Just like `OptionSpec::new_static`, the placeholder and the help text are kept as they
+    /// are, so the positional argument can be part of a `const` or a `static` table
+    ///
+    /// | Parameter   | Type         | Description                                                         |
+    /// |-------------|--------------|---------------------------------------------------------------------|
+    /// | placeholder | &'static str | The placeholder of the positional argument, meant for the help page |
+    /// | help        | &'static str | The help text of the positional argument                            |
+    ///
+    pub const fn new_static(placeholder: &'static str, help: &'static str) -> Self {
+        Self::blank(
+            Cow::Borrowed(placeholder),
+            Text::Static(Cow::Borrowed(help)),
+        )
+    }
+
+    const fn blank(placeholder: Cow<'static, str>, help: Text) -> Self {
+        Self {
+            placeholder,
+            arity: Arity::Exactly(1),
+            help,
+            file_expansion: None,
+            normalizers: Vec::new(),
+            sink: None,
+            command: false,
+            allow_empty: None,
+            since: None,
+        }
+    }
+
+    /// Sets the amount of values the positional argument takes
+    pub const fn takes(self, amount: usize) -> Self {
+        self.arity(Arity::Exactly(amount))
+    }
+
+    /// Makes the positional argument take an unspecified amount of values (the same as "+")
+    pub const fn infinite(self) -> Self {
+        self.arity(Arity::Infinite)
+    }
+
+    /// Sets the amount of values the positional argument takes
+    pub const fn arity(mut self, arity: Arity) -> Self {
+        self.arity = arity;
+        self
+    }
+
+    /// Sets the help text of the positional argument
+    ///
+    /// An empty line starts a new paragraph, which is kept on the help page. The other line
+    /// breaks are joined into one line. A &'static str is kept as it is instead of being copied
+    pub fn help(mut self, help: impl Into<Cow<'static, str>>) -> Self {
+        self.help = Text::Static(help.into());
+        self
+    }
+
+    /// Sets a closure producing the help text of the positional argument, which is only called
+    /// when the help is rendered
+    pub fn help_with(mut self, help: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.help = Text::lazy(help);
+        self
+    }
+
+    /// Adds a function normalizing the values of the positional argument (e.g.
+    /// `taap::normalize::canonicalize`)
+    ///
+    /// It's applied to every value before it's stored, after file lists have been expanded.
+    /// Several functions are applied in the order they were added
+    pub fn normalize(
+        mut self,
+        normalize: impl Fn(String) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.normalizers.push(normalizer(normalize));
+        self
+    }
+
+    /// Makes the positional argument the command tail, which captures a command to be run (the
+    /// program and its arguments) exactly as it was given, like with `mytool \[OPTIONS\] -- CMD
+    /// \[ARGS...\]`
+    ///
+    /// The command starts at the first value after the other positional arguments, even without
+    /// "--", so its own options aren't taken for options of your program. It must be the last
+    /// positional argument, and the ones before it can't be infinite. Get the command using
+    /// `ParsedArgs::command`
+    pub const fn command(mut self) -> Self {
+        self.arity = Arity::Infinite;
+        self.command = true;
+        self
+    }
+
+    /// Sets if the positional argument accepts empty values
+    ///
+    /// By default empty values are accepted, unless the positional argument has a sink, which
+    /// expects a real value. The arguments of a command tail are never checked
+    pub const fn allow_empty(mut self, allow: bool) -> Self {
+        self.allow_empty = Some(allow);
+        self
+    }
+
+    /// Sets the version of the program the positional argument was added in, for
+    /// `Argument::whats_new`
+    pub fn since(mut self, version: &str) -> Self {
+        self.since = Some(version.to_string());
+        self
+    }
+
+    /// Hands the values of the positional argument to a custom storage, instead of storing them
+    /// in the parse result (see `ValueSink`)
+    pub fn sink(mut self, sink: impl ValueSink + 'static) -> Self {
+        self.sink = Some(shared(sink));
+        self
+    }
+
+    pub(crate) fn allows_empty(&self) -> bool {
+        self.command || self.allow_empty.unwrap_or(self.sink.is_none())
+    }
+}
+
+/// A bundle of related optional arguments, e.g. connection flags
+///
+/// Add all of them to an Argument (or a subcommand) in one call using `Argument::add_option_set`,
+/// so options used by several subcommands only have to be declared once.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Opt, OptionSet};
+///
+/// fn main() {
+/// let connection = OptionSet::new()
+///     .option(Opt::new('H', "host").takes(1).help("The host to connect to"))
+///     .option(Opt::new('p', "port").takes(1).help("The port to connect to"));
+///
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add_subcommand("pull", "Pull from the server").add_option_set(&connection);
+/// arguments.add_subcommand("push", "Push to the server").add_option_set(&connection);
+/// // ...
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct OptionSet {
+    pub(crate) options: Vec<OptionSpec>,
+}
+
+impl OptionSet {
+    /// Returns a new, empty set of options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an optional argument to the set
+    pub fn option(mut self, option: OptionSpec) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    /// Moves every option of the set into a namespace, to avoid collisions with options from
+    /// elsewhere
+    ///
+    /// The prefix is added to the long names (e.g. "--telemetry-endpoint"), which are also the
+    /// names in the parse result. Short names are dropped, and options with only a short name use
+    /// it as their long name (e.g. "--telemetry-q").
+    pub fn prefixed(self, prefix: &str) -> Self {
+        self.options
+            .iter()
+            .map(|option| option.prefixed(prefix))
+            .collect()
+    }
+}
+
+impl FromIterator<OptionSpec> for OptionSet {
+    fn from_iter<I: IntoIterator<Item = OptionSpec>>(iter: I) -> Self {
+        Self {
+            options: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Either an optional or a positional argument, as accepted by `Argument::add`
+// an ArgSpec only lives until it's added, so boxing the larger OptionSpec isn't worth it
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ArgSpec {
+    /// An optional argument
+    Option(OptionSpec),
+    /// A positional argument
+    Positional(PositionalSpec),
+}
+
+impl From<OptionSpec> for ArgSpec {
+    fn from(option: OptionSpec) -> Self {
+        ArgSpec::Option(option)
+    }
+}
+
+impl From<PositionalSpec> for ArgSpec {
+    fn from(positional: PositionalSpec) -> Self {
+        ArgSpec::Positional(positional)
+    }
+}
+
+/// The file-list expansion of an infinite positional argument
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct FileExpansion {
+    // the result key of the option taking file lists
+    pub(crate) files_from: Option<String>,
+}
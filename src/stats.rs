@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+/// Statistics about the last call to parse_args
+///
+/// Only collected when enabled with `Argument::set_collect_stats`, and retrieved with
+/// `Argument::stats` after parsing. Useful for tools wanting to keep track of the overhead of
+/// the parser across releases.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ParseStats {
+    /// The amount of command line arguments that were parsed
+    pub tokens: usize,
+    /// The amount of optional arguments that were used
+    pub options_matched: usize,
+    /// The amount of positional arguments that were filled
+    pub positionals_matched: usize,
+    /// A rough estimate of the heap allocations made for the result (strings and vectors)
+    pub allocations_estimate: usize,
+    /// The time it took to parse the arguments
+    pub elapsed: Duration,
+}
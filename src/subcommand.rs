@@ -0,0 +1,135 @@
+// Subcommands, and the help dispatch for them
+//
+// A subcommand is an Argument of its own, stored in its parent. When it's used, it's parsed as
+// an Argument named after its full path (e.g. "mytool remote add"), with the global options of
+// its parents added to it, so both its usage line and its help show how it's actually invoked.
+
+use crate::{
+    help, locale,
+    output::{self, fail_with, Stop},
+    parse,
+    sanitize::sanitize,
+    suggest::did_you_mean,
+    Argument, Error, ErrorKind, ParsedArgs,
+};
+
+// The name of the built-in subcommand printing the help of other subcommands
+pub(crate) const HELP: &str = "help";
+
+// Returns true if the token starts a subcommand (or the help dispatch) of the Argument
+pub(crate) fn is_subcommand(argument: &Argument, token: &str) -> bool {
+    !argument.subcommands.is_empty()
+        && (token == HELP || argument.subcommands.iter().any(|sub| sub.name == token))
+}
+
+// Parses the rest of the command line as the given subcommand, or prints the help of a
+// subcommand when it's the help dispatch
+pub(crate) fn parse_subcommand(
+    argument: &Argument,
+    name: &str,
+    tokens: &[String],
+) -> Result<(String, ParsedArgs), Stop> {
+    match effective(argument, name) {
+        // an external subcommand gets the rest of the command line exactly as it was given
+        Some(subcommand) if subcommand.external.is_some() => {
+            let mut parsed = ParsedArgs::default();
+            parsed.set_external(subcommand.external.clone());
+            parsed.set_rest(tokens.to_vec());
+            Ok((name.to_string(), parsed))
+        }
+        Some(mut subcommand) => Ok((
+            name.to_string(),
+            parse::parse(&mut subcommand, tokens.to_vec())?,
+        )),
+        None => {
+            // "help" wasn't declared as a subcommand, so it's the help dispatch
+            print_help_of(argument, tokens)?;
+            Err(Stop::Exited(0))
+        }
+    }
+}
+
+// Prints the help of the subcommand at the given path, e.g. ["remote", "add"]
+fn print_help_of(argument: &Argument, path: &[String]) -> Result<(), Error> {
+    match path.split_first() {
+        None => {
+            help::print(argument, output::format());
+            Ok(())
+        }
+        Some((name, rest)) => match effective(argument, name) {
+            Some(subcommand) => print_help_of(&subcommand, rest),
+            None => Err(fail_with(
+                ErrorKind::UnknownSubcommand,
+                format_args!(
+                    "Unknown subcommand \"{}\"{}",
+                    sanitize(name),
+                    did_you_mean(argument.subcommands.iter().map(|sub| &sub.name), name)
+                ),
+            )),
+        },
+    }
+}
+
+// Returns the subcommand as it's parsed: named after its full path, and with the global options
+// of its parent, unless it has options using the same names itself
+pub(crate) fn effective(argument: &Argument, name: &str) -> Option<Argument> {
+    let mut subcommand = argument
+        .subcommands
+        .iter()
+        .find(|sub| sub.name == name)?
+        .clone();
+    // {bin} stays the name of the program
+    for (name, value) in argument.placeholders.iter() {
+        subcommand
+            .placeholders
+            .entry(name.to_owned())
+            .or_insert_with(|| value.to_owned());
+    }
+    subcommand
+        .placeholders
+        .entry("bin".to_string())
+        .or_insert_with(|| argument.name.to_owned());
+    for (language, bundle) in locale::subcommand_translations(&argument.translations, name) {
+        let translations = subcommand.translations.entry(language).or_default();
+        for (key, text) in bundle {
+            translations.entry(key).or_insert(text);
+        }
+    }
+    if subcommand.language.is_none() {
+        subcommand.language = argument.language.to_owned();
+    };
+    subcommand.name = format!("{} {}", argument.name, subcommand.name);
+    subcommand.limits = argument.limits;
+    subcommand.help_style = argument.help_style;
+    subcommand.arity_notation = argument.arity_notation;
+    subcommand.help_annotations = argument.help_annotations;
+    subcommand.decorations = argument.decorations;
+    subcommand.text_direction = argument.text_direction;
+    subcommand.exit_status_section = argument.exit_status_section;
+    subcommand.exit_status_format = argument.exit_status_format;
+    subcommand.duplicates = argument.duplicates;
+    subcommand.example_shell = argument.example_shell;
+    if subcommand.compatibility.is_none() {
+        subcommand.compatibility = argument.compatibility.to_owned();
+    };
+    if subcommand.terminator.is_none() {
+        subcommand.terminator = argument.terminator.to_owned();
+    };
+    subcommand.format_option |= argument.format_option;
+    subcommand.show_config_option |= argument.show_config_option;
+    subcommand.strict |= argument.strict;
+    subcommand.reserve_positionals |= argument.reserve_positionals;
+    for option in argument.options.iter().filter(|option| option.global) {
+        let taken = option
+            .short
+            .is_some_and(|short| subcommand.shorts.contains_key(&short))
+            || option
+                .long
+                .as_deref()
+                .is_some_and(|long| subcommand.longs.contains_key(long));
+        if !taken {
+            subcommand.insert_option(option.clone());
+        };
+    }
+    Some(subcommand)
+}
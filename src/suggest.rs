@@ -0,0 +1,99 @@
+// Suggestions for misspelled names, based on the Levenshtein distance
+
+use crate::sanitize::sanitize;
+
+/// Returns the candidate closest to the input, if any of them is close enough to be a likely
+/// typo of it
+///
+/// The same matcher is used by taap's own error messages (e.g. "Unknown subcommand "stauts",
+/// did you mean "status"?"), so suggestions made by the program itself behave the same way.
+/// A candidate is close enough if at most a third of its characters (and at least one) have to
+/// be changed, added or removed to get the input. Comparisons are case sensitive.
+///
+/// Code Example:
+/// ```no_run
+/// let colors = ["red", "green", "blue"];
+/// match taap::suggest(colors, "gren") {
+///     Some(color) => eprintln!("Unknown color \"gren\", did you mean \"{}\"?", color),
+///     None => eprintln!("Unknown color \"gren\""),
+/// };
+/// // ...
+/// ```
+///
+/// | Parameter  | Type                                 | Description                  |
+/// |------------|--------------------------------------|------------------------------|
+/// | candidates | impl IntoIterator\<Item: AsRef\<str\>\> | The names that are valid     |
+/// | input      | &str                                 | The name that was used       |
+///
+pub fn suggest<I, S>(candidates: I, input: &str) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let input: Vec<char> = input.chars().collect();
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let characters: Vec<char> = candidate.as_ref().chars().collect();
+            let distance = distance(&characters, &input);
+            let allowed = (characters.len().max(input.len()) / 3).max(1);
+            (distance <= allowed).then(|| (distance, candidate.as_ref().to_string()))
+        })
+        // the first of the closest candidates
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+// The amount of characters that have to be changed, added or removed to get from one word to
+// the other
+fn distance(from: &[char], to: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=to.len()).collect();
+    for (i, from_character) in from.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, to_character) in to.iter().enumerate() {
+            let substitution = previous[j] + usize::from(from_character != to_character);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[to.len()]
+}
+
+// Appends a hint to use "--" to an error message, if one of the unknown options that were skipped
+// was likely meant as a value: one following the values of an infinite positional argument (the
+// bool), or, with the "fs-hints" feature, a file that exists
+pub(crate) fn dash_hint(unknown: &[(String, bool)]) -> String {
+    match unknown
+        .iter()
+        .find(|(token, after_values)| *after_values || is_file(token))
+    {
+        Some((token, _)) => format!(
+            ", \"{}\" was skipped as an unknown option (use \"--\" before arguments that begin \
+             with '-')",
+            sanitize(token)
+        ),
+        None => String::new(),
+    }
+}
+
+#[cfg(feature = "fs-hints")]
+fn is_file(token: &str) -> bool {
+    std::path::Path::new(token).exists()
+}
+
+#[cfg(not(feature = "fs-hints"))]
+fn is_file(_token: &str) -> bool {
+    false
+}
+
+// Appends a suggestion to an error message about an unknown name, if there is one
+pub(crate) fn did_you_mean<I, S>(candidates: I, input: &str) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    match suggest(candidates, input) {
+        Some(candidate) => format!(", did you mean \"{}\"?", sanitize(&candidate)),
+        None => String::new(),
+    }
+}
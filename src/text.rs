@@ -0,0 +1,38 @@
+// Text shown on the help page, which is either given up front or produced by a closure when the
+// help is actually rendered
+
+use crate::callback::Callback;
+use std::{borrow::Cow, sync::Arc};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) enum Text {
+    // a &'static str is kept as it is, so it doesn't need an allocation
+    Static(Cow<'static, str>),
+    Lazy(Callback<dyn Fn() -> String + Send + Sync>),
+}
+
+impl Text {
+    pub(crate) fn lazy(text: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        Text::Lazy(Callback(Arc::new(text)))
+    }
+
+    // Returns the text, calling the closure if it's lazy
+    pub(crate) fn render(&self) -> Cow<'_, str> {
+        match self {
+            Text::Static(text) => Cow::Borrowed(text),
+            Text::Lazy(text) => Cow::Owned((text.0)()),
+        }
+    }
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Text::Static(Cow::Borrowed(""))
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Text::Static(Cow::Owned(text.to_string()))
+    }
+}
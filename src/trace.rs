@@ -0,0 +1,35 @@
+// Instrumentation of the parser with tracing spans and events (requires the "tracing" feature),
+// so programs already using tracing can follow the parser in their own telemetry. Everything is
+// recorded at the debug level with the target "taap", and without the feature the macros expand
+// to nothing
+
+// Enters a span, which lasts until the returned guard is dropped
+macro_rules! span {
+    ($name:literal $(, $($field:tt)*)?) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::debug_span!(target: "taap", $name $(, $($field)*)?).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = $crate::trace::NoSpan;
+        guard
+    }};
+}
+
+// Records an event in the current span
+macro_rules! event {
+    ($($argument:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(target: "taap", $($argument)*);
+    };
+}
+
+pub(crate) use {event, span};
+
+// The guard of a span without the "tracing" feature. It implements Drop like the real guard, so
+// a span can be ended early with drop either way
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+#[cfg(not(feature = "tracing"))]
+impl Drop for NoSpan {
+    fn drop(&mut self) {}
+}
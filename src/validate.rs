@@ -0,0 +1,171 @@
+// Checks of the whole definition of an Argument, for mistakes that can't be caught while it's
+// being built (e.g. because the arguments can be added in any order)
+
+use crate::{choices::Choices, interpolate, spec::Arity, subcommand, Argument};
+use std::collections::HashMap;
+
+// Returns a description of every problem found in the Argument and its subcommands
+pub(crate) fn problems(argument: &Argument) -> Vec<String> {
+    let mut problems: Vec<String> = vec![];
+    collect(argument, "", &mut problems);
+    problems
+}
+
+// Adds the problems of an Argument (and its subcommands), starting with the prefix
+fn collect(argument: &Argument, prefix: &str, all_problems: &mut Vec<String>) {
+    let mut problems: Vec<String> = vec![];
+    // the name of the option or positional argument stored under each key of the parse result
+    let mut keys: HashMap<String, String> = HashMap::new();
+    let mut shorts: HashMap<char, String> = HashMap::new();
+    let mut longs: HashMap<&str, String> = HashMap::new();
+
+    for option in argument.options.iter() {
+        let name = option.display_name();
+        if let Some(other) = option
+            .short
+            .and_then(|short| shorts.insert(short, name.to_owned()))
+        {
+            problems.push(format!(
+                "{} and {} both use -{}",
+                other,
+                name,
+                option.short.unwrap_or_default()
+            ));
+        };
+        if let Some(long) = option.long.as_deref() {
+            if let Some(other) = longs.insert(long, name.to_owned()) {
+                problems.push(format!("{} is added twice", other));
+            };
+        };
+        keys.insert(option.key(), name.to_owned());
+        if let (Some(default), Some(Choices::Static(choices)), false) =
+            (&option.default, &option.choices, option.default_template)
+        {
+            if let Some(invalid) = default.iter().find(|value| !choices.contains(value)) {
+                problems.push(format!(
+                    "The default \"{}\" of {} isn't one of its choices",
+                    invalid, name
+                ));
+            };
+        };
+    }
+
+    for positional in argument.positionals.iter() {
+        if let Some(other) = keys.insert(
+            positional.placeholder.to_string(),
+            positional.placeholder.to_string(),
+        ) {
+            problems.push(format!(
+                "{} and {} are both stored as \"{}\"",
+                other, positional.placeholder, positional.placeholder
+            ));
+        };
+    }
+
+    // values are only handed to the positional argument following an infinite one if an option
+    // splits them up, and the help option can't
+    let splittable = argument
+        .options
+        .iter()
+        .any(|option| option.key() != "h" || argument.raw);
+    let mut infinite = None;
+    for positional in argument.positionals.iter() {
+        if let Some(previous) = infinite.filter(|_| !splittable && !positional.command) {
+            problems.push(format!(
+                "{} can never get any values, since it follows the infinite {}",
+                positional.placeholder, previous
+            ));
+        };
+        if positional.arity == Arity::Infinite && !positional.command {
+            infinite = Some(&positional.placeholder);
+        };
+    }
+
+    for option in argument.options.iter() {
+        for (name, _) in option.required_if.iter() {
+            let known = argument.option_id(name).is_some()
+                || argument
+                    .positionals
+                    .iter()
+                    .any(|positional| positional.placeholder == *name);
+            if !known {
+                problems.push(format!(
+                    "{} is required depending on \"{}\", which doesn't exist",
+                    option.display_name(),
+                    name
+                ));
+            };
+        }
+    }
+
+    for name in argument.exclusive.iter().flatten() {
+        if argument.argument_key(name).is_none() {
+            problems.push(format!(
+                "\"{}\" is exclusive with other arguments, but doesn't exist",
+                name
+            ));
+        };
+    }
+
+    for renamed in argument.renamed.iter() {
+        if let Some(other) = longs.get(renamed.old.as_str()) {
+            problems.push(format!(
+                "--{} was renamed, but {} still uses the name",
+                renamed.old, other
+            ));
+        };
+        if argument.option_id(&renamed.new).is_none() {
+            problems.push(format!(
+                "--{} was renamed to \"{}\", which doesn't exist",
+                renamed.old, renamed.new
+            ));
+        };
+    }
+
+    for option in argument.options.iter() {
+        for name in option.enables.iter() {
+            let flag = argument
+                .option_id(name)
+                .is_some_and(|id| argument.options[id].arity == Arity::Exactly(0));
+            if !flag {
+                problems.push(format!(
+                    "{} enables \"{}\", which isn't a flag",
+                    option.display_name(),
+                    name
+                ));
+            };
+        }
+    }
+
+    for option in argument
+        .options
+        .iter()
+        .filter(|option| option.default_template)
+    {
+        let template = &option.default.as_ref().unwrap()[0];
+        for (_, name) in interpolate::parts(template)
+            .into_iter()
+            .filter(|(reference, _)| *reference)
+        {
+            if argument.argument_key(&name).is_none() {
+                problems.push(format!(
+                    "The default of {} refers to \"{}\", which doesn't exist",
+                    option.display_name(),
+                    name
+                ));
+            };
+        }
+    }
+
+    all_problems.extend(
+        problems
+            .into_iter()
+            .map(|problem| format!("{}{}", prefix, problem)),
+    );
+    // subcommands are checked the way they're parsed, with the global options of their parents
+    for sub in argument.subcommands.iter() {
+        if let Some(subcommand) = subcommand::effective(argument, &sub.name) {
+            collect(&subcommand, &format!("{}: ", subcommand.name), all_problems);
+        };
+    }
+}
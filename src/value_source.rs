@@ -0,0 +1,192 @@
+// External stores the values of options can be fetched from when they aren't given, like a
+// keyring, a secrets manager or a file, and the environment variables of options
+
+use crate::{
+    callback::Callback,
+    normalize, output,
+    output::fail,
+    parse::ParseResult,
+    parsed::Source,
+    sanitize::sanitize,
+    shell,
+    spec::{Arity, OptionSpec},
+    Error,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+/// An external store the values of an option are fetched from when it isn't used
+///
+/// When an option has a value source (see `OptionSpec::value_source`), the source is asked for
+/// the values stored under the key of the option once the command line is parsed, but before
+/// defaults are applied. So values given on the command line (or in the environment variable
+/// holding default arguments, or a profile) win over the source, and the source wins over the
+/// default. Sources are only asked when the option isn't used, and never when help or another
+/// short-circuiting option is used, so a slow or interactive store isn't touched needlessly.
+///
+/// Fetching blocks the parser. A source backed by an async client runs it to completion
+/// inside `fetch`, e.g. with the `block_on` of its runtime.
+///
+/// Code Example:
+/// ```no_run
+/// use taap::{Argument, Opt, ValueSource};
+///
+/// // reads the values from the files in a directory, one file per key
+/// struct SecretsDir(std::path::PathBuf);
+///
+/// impl ValueSource for SecretsDir {
+///     fn name(&self) -> String {
+///         format!("the secrets in {}", self.0.display())
+///     }
+///
+///     fn fetch(&self, key: &str) -> Result<Option<Vec<String>>, String> {
+///         match std::fs::read_to_string(self.0.join(key)) {
+///             Ok(secret) => Ok(Some(vec![secret.trim_end().to_string()])),
+///             Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+///             Err(error) => Err(error.to_string()),
+///         }
+///     }
+/// }
+///
+/// fn main() {
+/// let mut arguments = Argument::new("Name", "Description", "Epilog, text at the bottom", "Credits");
+/// arguments.add(
+///     Opt::new('t', "token")
+///         .takes(1)
+///         .value_source(SecretsDir("/run/secrets".into()), "api-token"),
+/// );
+/// let parsed_arguments = arguments.parse_args(None);
+/// }
+/// ```
+pub trait ValueSource: Send + Sync {
+    /// The name of the store, shown in error messages and returned by `ParsedArgs::source`
+    fn name(&self) -> String;
+
+    /// Returns the values stored under the key, None if there are none, or a description of
+    /// what went wrong, which makes parsing fail
+    fn fetch(&self, key: &str) -> Result<Option<Vec<String>>, String>;
+}
+
+// A value source as stored in the spec, with the key of the option in it
+pub(crate) type SharedSource = (Callback<dyn ValueSource>, String);
+
+pub(crate) fn shared(source: impl ValueSource + 'static, key: &str) -> SharedSource {
+    (Callback(Arc::new(source)), key.to_string())
+}
+
+// Reads the values of the unused options that have an environment variable, skipping the flags
+// turned off with --no-NAME
+pub(crate) fn environment(
+    options: &[OptionSpec],
+    negated: &BTreeSet<String>,
+    return_map: &mut ParseResult,
+    sources: &mut BTreeMap<String, Source>,
+) -> Result<(), Error> {
+    // a command line that's only matched isn't run in this environment
+    if output::transcribing() {
+        return Ok(());
+    };
+    for option in options.iter() {
+        let Some(variable) = &option.env else {
+            continue;
+        };
+        if return_map.get(&option.key()).unwrap().0 || negated.contains(&option.key()) {
+            continue;
+        };
+        let value = match std::env::var_os(variable) {
+            Some(value) if !value.is_empty() => value,
+            _ => continue,
+        };
+        let Some(value) = value.to_str() else {
+            return Err(fail(format_args!("{} isn't valid UTF-8", variable)));
+        };
+        let values = match option.arity {
+            Arity::Exactly(0) => match value.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => vec![],
+                "0" | "false" | "no" | "off" => continue,
+                _ => {
+                    return Err(fail(format_args!(
+                        "{} is \"{}\", but {} can only be turned on (1, true, yes or on) or off \
+                         (0, false, no or off)",
+                        variable,
+                        sanitize(value),
+                        option.display_name()
+                    )))
+                }
+            },
+            Arity::Exactly(1) => vec![value.to_string()],
+            arity => {
+                let Some(values) = shell::split(value) else {
+                    return Err(fail(format_args!(
+                        "{} has a quote that isn't closed: \"{}\"",
+                        variable,
+                        sanitize(value)
+                    )));
+                };
+                let fits = match arity {
+                    Arity::Exactly(amount) => amount == values.len(),
+                    Arity::Infinite => !values.is_empty(),
+                };
+                if !fits {
+                    return Err(fail(format_args!(
+                        "{} got {} values from {}, which doesn't match the amount it takes",
+                        option.display_name(),
+                        values.len(),
+                        variable
+                    )));
+                };
+                values
+            }
+        };
+        sources.insert(option.key(), Source::Environment(variable.to_owned()));
+        *return_map.get_mut(&option.key()).unwrap() =
+            (true, normalize::apply(&option.normalizers, values));
+    }
+    Ok(())
+}
+
+// Fetches the values of the unused options that have a value source
+pub(crate) fn fetch(
+    options: &[OptionSpec],
+    return_map: &mut ParseResult,
+    sources: &mut BTreeMap<String, Source>,
+) -> Result<(), Error> {
+    for option in options.iter() {
+        let Some((source, key)) = &option.value_source else {
+            continue;
+        };
+        if return_map.get(&option.key()).unwrap().0 {
+            continue;
+        };
+        let values = match source.0.fetch(key) {
+            Ok(Some(values)) => values,
+            Ok(None) => continue,
+            Err(error) => {
+                return Err(fail(format_args!(
+                    "Can't get {} from {}: {}",
+                    option.display_name(),
+                    source.0.name(),
+                    sanitize(&error)
+                )))
+            }
+        };
+        let fits = match option.arity {
+            Arity::Exactly(amount) => amount == values.len(),
+            Arity::Infinite => !values.is_empty(),
+        };
+        if !fits {
+            return Err(fail(format_args!(
+                "{} got {} values from {}, which doesn't match the amount it takes",
+                option.display_name(),
+                values.len(),
+                source.0.name()
+            )));
+        };
+        sources.insert(option.key(), Source::External(source.0.name()));
+        *return_map.get_mut(&option.key()).unwrap() =
+            (true, normalize::apply(&option.normalizers, values));
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// The versions arguments were added or removed in, and the compatibility level a program
+// declares (see Argument::set_compatibility)
+
+use crate::{output, Argument};
+use std::cmp::Ordering;
+
+// Compares the parts of two versions separated by dots one by one, so 1.10 is newer than 1.9.
+// Parts that aren't numbers are compared as text, and missing parts count as 0
+pub(crate) fn compare(first: &str, second: &str) -> Ordering {
+    let mut first_parts = first.trim_start_matches('v').split('.');
+    let mut second_parts = second.trim_start_matches('v').split('.');
+    loop {
+        let (first_part, second_part) = match (first_parts.next(), second_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (first_part, second_part) => (first_part.unwrap_or("0"), second_part.unwrap_or("0")),
+        };
+        let ordering = match (first_part.parse::<u64>(), second_part.parse::<u64>()) {
+            (Ok(first_number), Ok(second_number)) => first_number.cmp(&second_number),
+            _ => first_part.cmp(second_part),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        };
+    }
+}
+
+// Warns about an option used on the command line that doesn't exist at the compatibility level,
+// because it was added later or removed already
+pub(crate) fn check_compatibility(argument: &Argument, id: usize, used_as: &str) {
+    let Some(level) = &argument.compatibility else {
+        return;
+    };
+    let option = &argument.options[id];
+    if let Some(since) = option.since.as_deref() {
+        if compare(since, level) == Ordering::Greater {
+            output::warn(format_args!(
+                "{} was added in {}, after the compatibility level {}",
+                used_as, since, level
+            ));
+        };
+    };
+    if let Some(removed) = option.removed.as_deref() {
+        if compare(removed, level) != Ordering::Greater {
+            output::warn(format_args!(
+                "{} was removed in {}, which is within the compatibility level {}",
+                used_as, removed, level
+            ));
+        };
+    };
+}
+
+// The lines of the "What's New" text: the arguments added or removed after a version, oldest
+// first
+pub(crate) fn whats_new<'a>(argument: &'a Argument, after: &str) -> String {
+    let mut changes: Vec<(&str, String, &str)> = vec![];
+    let newer = |version: Option<&'a str>| {
+        version.filter(|version| compare(version, after) == Ordering::Greater)
+    };
+    for option in argument.options.iter() {
+        if let Some(since) = newer(option.since.as_deref()) {
+            changes.push((since, option.display_name(), "added"));
+        };
+        if let Some(removed) = newer(option.removed.as_deref()) {
+            changes.push((removed, option.display_name(), "removed"));
+        };
+    }
+    for positional in argument.positionals.iter() {
+        if let Some(since) = newer(positional.since.as_deref()) {
+            changes.push((since, positional.placeholder.to_string(), "added"));
+        };
+    }
+    changes.sort_by(|first, second| compare(first.0, second.0));
+    changes
+        .iter()
+        .map(|(version, name, change)| format!("{}\t{} was {}", version, name, change))
+        .collect::<Vec<String>>()
+        .join("\n")
+}